@@ -1,8 +1,17 @@
 //! Addon information.
 
-use crate::api::AddonApi;
+use crate::{
+    api::{
+        gui::{register_render_fn, RenderType},
+        paths::get_addon_dir,
+        AddonApi,
+    },
+    globals::addon_name,
+    state::AddonState,
+};
 use bitflags::bitflags;
-use std::ffi::c_char;
+use imgui::Ui;
+use std::{ffi::c_char, path::PathBuf};
 
 /// Addon definition.
 #[derive(Debug, Clone, Copy)]
@@ -56,6 +65,9 @@ pub type RawAddonLoad = unsafe extern "C-unwind" fn(api: *const AddonApi);
 
 pub type RawAddonUnload = unsafe extern "C-unwind" fn();
 
+/// Exported function returning the [`AddonDefinition`], called by the Loader.
+pub type RawGetAddonDef = unsafe extern "system-unwind" fn() -> *const AddonDefinition;
+
 /// Addon version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -86,7 +98,8 @@ bitflags! {
 }
 
 /// Addon update provider.
-// TODO: rust enum encapsulating provider & link?
+///
+/// See [`UpdateSource`] for a provider together with its link.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(
@@ -120,3 +133,182 @@ pub enum UpdateProvider {
     /// Addon has to check versions itself and request updates manually.
     Manual = 4,
 }
+
+/// Addon update source.
+///
+/// Encapsulates the [`UpdateProvider`] together with the link it requires.
+/// Accepted by the [`export`](crate::export) macro as `update_source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum UpdateSource {
+    /// Does not support auto updating.
+    None,
+
+    /// Raidcore via API.
+    Raidcore,
+
+    /// GitHub releases.
+    GitHub {
+        /// URL of the GitHub repository.
+        repo: &'static str,
+    },
+
+    /// Direct file link.
+    Direct {
+        /// URL of the file.
+        url: &'static str,
+    },
+
+    /// Manual updating.
+    ///
+    /// Addon has to check versions itself and request updates manually.
+    Manual,
+}
+
+impl UpdateSource {
+    /// Returns the [`UpdateProvider`] of the source.
+    #[inline]
+    pub const fn provider(&self) -> UpdateProvider {
+        match self {
+            Self::None => UpdateProvider::None,
+            Self::Raidcore => UpdateProvider::Raidcore,
+            Self::GitHub { .. } => UpdateProvider::GitHub,
+            Self::Direct { .. } => UpdateProvider::Direct,
+            Self::Manual => UpdateProvider::Manual,
+        }
+    }
+
+    /// Returns the link to the update resource, if any.
+    #[inline]
+    pub const fn link(&self) -> Option<&'static str> {
+        match self {
+            Self::GitHub { repo } => Some(repo),
+            Self::Direct { url } => Some(url),
+            Self::None | Self::Raidcore | Self::Manual => None,
+        }
+    }
+}
+
+impl From<UpdateSource> for UpdateProvider {
+    #[inline]
+    fn from(source: UpdateSource) -> Self {
+        source.provider()
+    }
+}
+
+/// Object-oriented addon entry point.
+///
+/// Used via the [`addon`](macro@crate::addon) attribute macro.
+/// The addon is created via [`Default`] on load and stored as [`AddonState`] after [`Addon::load`] returned.
+/// The [`AddonState`] is not available yet during [`Addon::load`].
+/// The addon is dropped on unload.
+///
+/// # Usage
+/// ```no_run
+/// # mod main {
+/// use nexus::{
+///     addon::{Addon, AddonContext},
+///     imgui::{Ui, Window},
+///     log::{log, LogLevel},
+/// };
+///
+/// #[nexus::addon(signature = -0x12345678)]
+/// #[derive(Default)]
+/// struct MyAddon {
+///     clicks: usize,
+/// }
+///
+/// impl Addon for MyAddon {
+///     fn load(&mut self, ctx: &AddonContext) {
+///         log(LogLevel::Info, ctx.name(), "Loading addon");
+///     }
+///
+///     fn render(&mut self, ui: &Ui) {
+///         Window::new("My Window").build(ui, || {
+///             if ui.button("Click me") {
+///                 self.clicks += 1;
+///             }
+///         });
+///     }
+/// }
+/// # }
+/// ```
+pub trait Addon: Default + Send + 'static {
+    /// Called when the addon is loaded.
+    #[inline]
+    fn load(&mut self, _ctx: &AddonContext) {}
+
+    /// Called when the addon is unloaded.
+    #[inline]
+    fn unload(&mut self) {}
+
+    /// Called during the ImGui frame.
+    #[inline]
+    fn render(&mut self, _ui: &Ui) {}
+
+    /// Called during the ImGui frame, appended to the options window.
+    #[inline]
+    fn options(&mut self, _ui: &Ui) {}
+}
+
+/// Context passed to [`Addon::load`].
+#[derive(Debug, Clone, Copy)]
+pub struct AddonContext {
+    name: &'static str,
+    api: &'static AddonApi,
+}
+
+impl AddonContext {
+    /// Returns the name of the addon.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the Nexus [`AddonApi`] instance.
+    #[inline]
+    pub fn api(&self) -> &'static AddonApi {
+        self.api
+    }
+
+    /// Returns the directory of the addon.
+    #[inline]
+    pub fn addon_dir(&self) -> Option<PathBuf> {
+        get_addon_dir(self.name)
+    }
+}
+
+/// Loads the [`Addon`] stored as [`AddonState`].
+///
+/// A call to this is inserted automatically by the [`addon`](macro@crate::addon) macro.
+#[doc(hidden)]
+pub fn load_addon<A: Addon>() {
+    let ctx = AddonContext {
+        name: addon_name().expect("addon not initialized"),
+        api: AddonApi::get(),
+    };
+
+    // load before storing, callbacks accessing the state must not block on the load
+    let mut addon = A::default();
+    addon.load(&ctx);
+    crate::state::init(move || addon);
+
+    register_render_fn(
+        RenderType::Render,
+        AddonState::render(|ui, addon: &mut A| addon.render(ui)),
+    )
+    .revert_on_unload();
+    register_render_fn(
+        RenderType::OptionsRender,
+        AddonState::render(|ui, addon: &mut A| addon.options(ui)),
+    )
+    .revert_on_unload();
+}
+
+/// Unloads the [`Addon`] stored as [`AddonState`].
+///
+/// A call to this is inserted automatically by the [`addon`](macro@crate::addon) macro.
+#[doc(hidden)]
+pub fn unload_addon<A: Addon>() {
+    AddonState::<A>::with(|addon| addon.unload());
+}
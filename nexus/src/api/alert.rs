@@ -1,13 +1,25 @@
 //! Alert notifications displayed to the user.
 
-use crate::{util::str_to_c, AddonApi, UiApi};
+use crate::{
+    util::{try_str_to_c, unwrap_or_panic},
+    AddonApi, Error, UiApi,
+};
 use std::ffi::c_char;
 
 pub type RawAlertNotify = unsafe extern "C-unwind" fn(message: *const c_char);
 
 /// Sends an alert that is visible to the user for a short amount of time.
+///
+/// Panics if the message contains a nul byte.
+#[track_caller]
 pub fn send_alert(message: impl AsRef<str>) {
-    let message = str_to_c(message, "failed to convert alert message");
-    let UiApi { send_alert, .. } = AddonApi::get().ui;
-    unsafe { send_alert(message.as_ptr()) }
+    unwrap_or_panic(try_send_alert(message))
+}
+
+/// Fallible version of [`send_alert`].
+pub fn try_send_alert(message: impl AsRef<str>) -> Result<(), Error> {
+    let UiApi { send_alert, .. } = AddonApi::try_get()?.ui;
+    let message = try_str_to_c(message, "alert message")?;
+    unsafe { send_alert(message.as_ptr()) };
+    Ok(())
 }
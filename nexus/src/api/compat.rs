@@ -0,0 +1,689 @@
+//! Compatibility with older Addon API revisions.
+//!
+//! The Loader passes the [`AddonApi`] revision requested in the [`AddonDefinition`](crate::addon::AddonDefinition).
+//! Older revisions are normalized into the current [`AddonApi`] layout during initialization.
+//! Functions missing in the passed revision are replaced by fallbacks, which either emulate the call or ignore it with a warning logged once.
+//! Fallible functions return [`Error::Unsupported`] instead of calling an ignoring fallback.
+//! Use the capability queries like [`AddonApi::has_gamebinds`] to check whether a function is actually available.
+
+use super::{
+    alert::RawAlertNotify,
+    font::{RawFontReceive, RawFontResize},
+    gamebind::GameBind,
+    gui::RawGuiRender,
+    keybind::{Keybind, RawKeybindHandler},
+    log::LogLevel,
+    updater::RawRequestUpdate,
+    v2, v3, v4, AddonApi, DataLinkApi, EventApi, FontApi, GameBindApi, InputBindsApi,
+    LocalizationApi, MinHookApi, PathApi, QuickAccessApi, RendererApi, TextureApi, UiApi,
+    WndProcApi,
+};
+use crate::{globals::addon_name, log::try_log, win::HMODULE, Error};
+use imgui::sys::ImFontConfig;
+use std::{
+    collections::{BTreeSet, HashMap},
+    ffi::{c_char, c_void, CStr},
+    sync::{Mutex, PoisonError},
+};
+
+/// Addon API versions supported by this crate.
+pub const SUPPORTED_VERSIONS: [i32; 4] = [
+    v2::AddonApi::VERSION,
+    v3::AddonApi::VERSION,
+    v4::AddonApi::VERSION,
+    AddonApi::VERSION,
+];
+
+/// Checks whether the Addon API version is supported by this crate.
+#[inline]
+pub const fn is_supported_version(version: i32) -> bool {
+    matches!(version, 2 | 3 | 4 | 6)
+}
+
+/// Addon API in the revision passed by the Loader.
+#[derive(Debug, Clone, Copy)]
+pub enum RawAddonApi {
+    V2(&'static v2::AddonApi),
+    V3(&'static v3::AddonApi),
+    V4(&'static v4::AddonApi),
+    V6(&'static AddonApi),
+}
+
+impl RawAddonApi {
+    /// Interprets the pointer passed by the Loader as the given Addon API version.
+    ///
+    /// Returns [`None`] if the pointer is `null` or the version is unsupported.
+    ///
+    /// # Safety
+    /// The pointer must be `null` or point to a valid Addon API struct of the given version with `'static` lifetime.
+    pub unsafe fn from_ptr(api: *const c_void, version: i32) -> Option<Self> {
+        if api.is_null() {
+            return None;
+        }
+        match version {
+            2 => Some(Self::V2(&*api.cast())),
+            3 => Some(Self::V3(&*api.cast())),
+            4 => Some(Self::V4(&*api.cast())),
+            6 => Some(Self::V6(&*api.cast())),
+            _ => None,
+        }
+    }
+
+    /// Returns the Addon API version.
+    #[inline]
+    pub fn version(&self) -> i32 {
+        match self {
+            Self::V2(_) => v2::AddonApi::VERSION,
+            Self::V3(_) => v3::AddonApi::VERSION,
+            Self::V4(_) => v4::AddonApi::VERSION,
+            Self::V6(_) => AddonApi::VERSION,
+        }
+    }
+
+    /// Normalizes the Addon API into the current [`AddonApi`] layout.
+    ///
+    /// Missing functions are replaced by fallbacks.
+    /// Some fallbacks rely on the [`RawAddonApi`] being initialized globally.
+    pub fn normalize(&self) -> AddonApi {
+        match *self {
+            Self::V2(api) => AddonApi {
+                swap_chain: api.swap_chain,
+                imgui_context: api.imgui_context,
+                imgui_malloc: api.imgui_malloc,
+                imgui_free: api.imgui_free,
+                renderer: RendererApi {
+                    register: api.register_render,
+                    deregister: api.deregister_render,
+                },
+                request_update: fallback::request_update,
+                log: api.log,
+                ui: UiApi {
+                    send_alert: fallback::send_alert,
+                    register_close_on_escape: fallback::register_close_on_escape,
+                    deregister_close_on_escape: fallback::deregister_close_on_escape,
+                },
+                path: PathApi {
+                    get_game_dir: api.get_game_dir,
+                    get_addon_dir: api.get_addon_dir,
+                    get_common_dir: api.get_common_dir,
+                },
+                min_hook: MinHookApi {
+                    create: api.hook_create,
+                    remove: api.hook_remove,
+                    enable: api.hook_enable,
+                    disable: api.hook_disable,
+                },
+                event: EventApi {
+                    raise: api.event_raise,
+                    raise_notification: api.event_raise_notification,
+                    raise_targeted: fallback::event_raise_targeted,
+                    raise_notification_targeted: fallback::event_raise_notification_targeted,
+                    subscribe: api.event_subscribe,
+                    unsubscribe: api.event_unsubscribe,
+                },
+                wnd_proc: WndProcApi {
+                    register: api.register_wnd_proc,
+                    deregister: api.deregister_wnd_proc,
+                    send_to_game_only: api.send_wnd_proc_to_game_only,
+                },
+                input_binds: fallback::INPUT_BINDS_OLD,
+                game_bind: fallback::GAME_BIND,
+                data_link: DataLinkApi {
+                    get: api.get_resource,
+                    share: api.share_resource,
+                },
+                texture: TextureApi {
+                    get: api.get_texture,
+                    get_or_create_from_file: api.get_texture_or_create_from_file,
+                    get_or_create_from_resource: api.get_texture_or_create_from_resource,
+                    get_or_create_from_url: api.get_texture_or_create_from_url,
+                    get_or_create_from_memory: api.get_texture_or_create_from_memory,
+                    load_from_file: api.load_texture_from_file,
+                    load_from_resource: api.load_texture_from_resource,
+                    load_from_url: api.load_texture_from_url,
+                    load_from_memory: api.load_texture_from_memory,
+                },
+                quick_access: QuickAccessApi {
+                    add: api.add_shortcut,
+                    remove: api.remove_shortcut,
+                    notify: api.notify_shortcut,
+                    add_context_menu: fallback::quick_access_add_context_menu,
+                    remove_context_menu: api.remove_simple_shortcut,
+                },
+                localization: LocalizationApi {
+                    translate: api.translate,
+                    translate_to: api.translate_to,
+                    set: fallback::localization_set,
+                },
+                font: fallback::FONT,
+            },
+            Self::V3(api) => AddonApi {
+                swap_chain: api.swap_chain,
+                imgui_context: api.imgui_context,
+                imgui_malloc: api.imgui_malloc,
+                imgui_free: api.imgui_free,
+                renderer: RendererApi {
+                    register: api.register_render,
+                    deregister: api.deregister_render,
+                },
+                request_update: fallback::request_update,
+                log: api.log,
+                ui: UiApi {
+                    send_alert: api.alert_notify,
+                    register_close_on_escape: fallback::register_close_on_escape,
+                    deregister_close_on_escape: fallback::deregister_close_on_escape,
+                },
+                path: PathApi {
+                    get_game_dir: api.get_game_dir,
+                    get_addon_dir: api.get_addon_dir,
+                    get_common_dir: api.get_common_dir,
+                },
+                min_hook: MinHookApi {
+                    create: api.hook_create,
+                    remove: api.hook_remove,
+                    enable: api.hook_enable,
+                    disable: api.hook_disable,
+                },
+                event: EventApi {
+                    raise: api.event_raise,
+                    raise_notification: api.event_raise_notification,
+                    raise_targeted: api.event_raise_targeted,
+                    raise_notification_targeted: api.event_raise_notification_targeted,
+                    subscribe: api.event_subscribe,
+                    unsubscribe: api.event_unsubscribe,
+                },
+                wnd_proc: WndProcApi {
+                    register: api.register_wnd_proc,
+                    deregister: api.deregister_wnd_proc,
+                    send_to_game_only: api.send_wnd_proc_to_game_only,
+                },
+                input_binds: fallback::INPUT_BINDS_OLD,
+                game_bind: fallback::GAME_BIND,
+                data_link: DataLinkApi {
+                    get: api.get_resource,
+                    share: api.share_resource,
+                },
+                texture: TextureApi {
+                    get: api.get_texture,
+                    get_or_create_from_file: api.get_texture_or_create_from_file,
+                    get_or_create_from_resource: api.get_texture_or_create_from_resource,
+                    get_or_create_from_url: api.get_texture_or_create_from_url,
+                    get_or_create_from_memory: api.get_texture_or_create_from_memory,
+                    load_from_file: api.load_texture_from_file,
+                    load_from_resource: api.load_texture_from_resource,
+                    load_from_url: api.load_texture_from_url,
+                    load_from_memory: api.load_texture_from_memory,
+                },
+                quick_access: QuickAccessApi {
+                    add: api.add_shortcut,
+                    remove: api.remove_shortcut,
+                    notify: api.notify_shortcut,
+                    add_context_menu: fallback::quick_access_add_context_menu,
+                    remove_context_menu: api.remove_simple_shortcut,
+                },
+                localization: LocalizationApi {
+                    translate: api.translate,
+                    translate_to: api.translate_to,
+                    set: fallback::localization_set,
+                },
+                font: fallback::FONT,
+            },
+            Self::V4(api) => AddonApi {
+                swap_chain: api.swap_chain,
+                imgui_context: api.imgui_context,
+                imgui_malloc: api.imgui_malloc,
+                imgui_free: api.imgui_free,
+                renderer: RendererApi {
+                    register: api.register_render,
+                    deregister: api.deregister_render,
+                },
+                request_update: api.request_update,
+                log: api.log,
+                ui: UiApi {
+                    send_alert: api.alert_notify,
+                    register_close_on_escape: fallback::register_close_on_escape,
+                    deregister_close_on_escape: fallback::deregister_close_on_escape,
+                },
+                path: PathApi {
+                    get_game_dir: api.get_game_dir,
+                    get_addon_dir: api.get_addon_dir,
+                    get_common_dir: api.get_common_dir,
+                },
+                min_hook: MinHookApi {
+                    create: api.hook_create,
+                    remove: api.hook_remove,
+                    enable: api.hook_enable,
+                    disable: api.hook_disable,
+                },
+                event: EventApi {
+                    raise: api.event_raise,
+                    raise_notification: api.event_raise_notification,
+                    raise_targeted: api.event_raise_targeted,
+                    raise_notification_targeted: api.event_raise_notification_targeted,
+                    subscribe: api.event_subscribe,
+                    unsubscribe: api.event_unsubscribe,
+                },
+                wnd_proc: WndProcApi {
+                    register: api.register_wnd_proc,
+                    deregister: api.deregister_wnd_proc,
+                    send_to_game_only: api.send_wnd_proc_to_game_only,
+                },
+                input_binds: InputBindsApi {
+                    invoke: fallback::keybind_invoke,
+                    register_with_string: api.keybind_register_with_string,
+                    register_with_struct: api.keybind_register_with_struct,
+                    deregister: api.keybind_deregister,
+                },
+                game_bind: fallback::GAME_BIND,
+                data_link: DataLinkApi {
+                    get: api.get_resource,
+                    share: api.share_resource,
+                },
+                texture: TextureApi {
+                    get: api.get_texture,
+                    get_or_create_from_file: api.get_texture_or_create_from_file,
+                    get_or_create_from_resource: api.get_texture_or_create_from_resource,
+                    get_or_create_from_url: api.get_texture_or_create_from_url,
+                    get_or_create_from_memory: api.get_texture_or_create_from_memory,
+                    load_from_file: api.load_texture_from_file,
+                    load_from_resource: api.load_texture_from_resource,
+                    load_from_url: api.load_texture_from_url,
+                    load_from_memory: api.load_texture_from_memory,
+                },
+                quick_access: QuickAccessApi {
+                    add: api.add_shortcut,
+                    remove: api.remove_shortcut,
+                    notify: api.notify_shortcut,
+                    add_context_menu: fallback::quick_access_add_context_menu,
+                    remove_context_menu: api.remove_simple_shortcut,
+                },
+                localization: LocalizationApi {
+                    translate: api.translate,
+                    translate_to: api.translate_to,
+                    set: fallback::localization_set,
+                },
+                font: FontApi {
+                    get: api.get_font,
+                    release: api.release_font,
+                    add_from_file: api.add_font_from_file,
+                    add_from_resource: api.add_font_from_resource,
+                    add_from_memory: api.add_font_from_memory,
+                    resize: fallback::font_resize,
+                },
+            },
+            Self::V6(api) => *api,
+        }
+    }
+}
+
+impl AddonApi {
+    /// Returns the Addon API version this instance was normalized from.
+    ///
+    /// The version is determined from the fallbacks present in the instance.
+    pub fn version(&self) -> i32 {
+        // each revision lacks a function the next one introduced
+        if self.ui.send_alert as usize == fallback::send_alert as RawAlertNotify as usize {
+            v2::AddonApi::VERSION
+        } else if self.request_update as usize
+            == fallback::request_update as RawRequestUpdate as usize
+        {
+            v3::AddonApi::VERSION
+        } else if self.font.resize as usize == fallback::font_resize as RawFontResize as usize {
+            v4::AddonApi::VERSION
+        } else {
+            Self::VERSION
+        }
+    }
+
+    /// Returns [`Error::Unsupported`] if the capability is missing in this instance.
+    #[inline]
+    pub(crate) fn require(
+        &self,
+        available: fn(&Self) -> bool,
+        what: &'static str,
+    ) -> Result<&Self, Error> {
+        if available(self) {
+            Ok(self)
+        } else {
+            Err(Error::Unsupported {
+                what,
+                version: self.version(),
+            })
+        }
+    }
+
+    /// Checks whether alerts are available.
+    ///
+    /// Without them alerts are logged instead.
+    #[inline]
+    pub fn has_alerts(&self) -> bool {
+        self.version() >= 3
+    }
+
+    /// Checks whether targeted events are available.
+    #[inline]
+    pub fn has_targeted_events(&self) -> bool {
+        self.version() >= 3
+    }
+
+    /// Checks whether update requests are available.
+    #[inline]
+    pub fn has_request_update(&self) -> bool {
+        self.version() >= 4
+    }
+
+    /// Checks whether keybind handlers receive key releases.
+    ///
+    /// Without this handlers are only invoked on key press.
+    #[inline]
+    pub fn has_keybind_release(&self) -> bool {
+        self.version() >= 4
+    }
+
+    /// Checks whether fonts are available.
+    #[inline]
+    pub fn has_fonts(&self) -> bool {
+        self.version() >= 4
+    }
+
+    /// Checks whether font resizing is available.
+    #[inline]
+    pub fn has_font_resize(&self) -> bool {
+        self.version() >= 6
+    }
+
+    /// Checks whether closing windows on escape is available.
+    #[inline]
+    pub fn has_close_on_escape(&self) -> bool {
+        self.version() >= 6
+    }
+
+    /// Checks whether invoking keybinds is available.
+    #[inline]
+    pub fn has_keybind_invoke(&self) -> bool {
+        self.version() >= 6
+    }
+
+    /// Checks whether game binds are available.
+    #[inline]
+    pub fn has_gamebinds(&self) -> bool {
+        self.version() >= 6
+    }
+
+    /// Checks whether quick access context menus can target a shortcut.
+    ///
+    /// Without this context menus are always added to the Nexus icon.
+    #[inline]
+    pub fn has_quick_access_target(&self) -> bool {
+        self.version() >= 6
+    }
+
+    /// Checks whether setting translations at runtime is available.
+    #[inline]
+    pub fn has_set_translation(&self) -> bool {
+        self.version() >= 6
+    }
+}
+
+/// Resets state kept by fallbacks.
+pub(crate) fn reset() {
+    fallback::clear_keybind_handlers();
+    fallback::clear_reported();
+}
+
+/// Fallbacks for functions missing in older revisions.
+mod fallback {
+    use super::*;
+
+    pub const INPUT_BINDS_OLD: InputBindsApi = InputBindsApi {
+        invoke: keybind_invoke,
+        register_with_string: keybind_register_with_string,
+        register_with_struct: keybind_register_with_struct,
+        deregister: keybind_deregister,
+    };
+
+    pub const GAME_BIND: GameBindApi = GameBindApi {
+        press_async: gamebind,
+        release_async: gamebind,
+        invoke_async: gamebind_invoke,
+        press: gamebind,
+        release: gamebind,
+        is_bound: gamebind_is_bound,
+    };
+
+    pub const FONT: FontApi = FontApi {
+        get: font_receiver,
+        release: font_receiver,
+        add_from_file: font_add_from_file,
+        add_from_resource: font_add_from_resource,
+        add_from_memory: font_add_from_memory,
+        resize: font_resize,
+    };
+
+    /// Ignored functions already reported.
+    static REPORTED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    /// Clears the ignored functions already reported.
+    pub fn clear_reported() {
+        REPORTED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Logs a warning the first time an ignored function is called.
+    fn report(what: &'static str) {
+        let first = REPORTED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(what);
+        if let (true, Some(addon_name), Some(raw)) =
+            (first, addon_name(), crate::globals::raw_addon_api())
+        {
+            let err = Error::Unsupported {
+                what,
+                version: raw.version(),
+            };
+            let _ = try_log(LogLevel::Warning, addon_name, format!("{err}, ignoring"));
+        }
+    }
+
+    /// Keybind handlers registered via the old keybind API.
+    static KEYBIND_HANDLERS: Mutex<Option<HashMap<String, RawKeybindHandler>>> = Mutex::new(None);
+
+    /// Clears keybind handlers registered via the old keybind API.
+    pub fn clear_keybind_handlers() {
+        KEYBIND_HANDLERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+
+    fn with_keybind_handlers<R>(f: impl FnOnce(&mut HashMap<String, RawKeybindHandler>) -> R) -> R {
+        let mut guard = KEYBIND_HANDLERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        f(guard.get_or_insert_with(HashMap::new))
+    }
+
+    unsafe fn insert_keybind_handler(identifier: *const c_char, handler: RawKeybindHandler) {
+        let identifier = CStr::from_ptr(identifier).to_string_lossy().into_owned();
+        with_keybind_handlers(|handlers| handlers.insert(identifier, handler));
+    }
+
+    /// Old keybind handler invoking the registered new keybind handler.
+    extern "C-unwind" fn keybind_handler_old(identifier: *const c_char) {
+        let key = unsafe { CStr::from_ptr(identifier) }.to_string_lossy();
+        let handler = with_keybind_handlers(|handlers| handlers.get(key.as_ref()).copied());
+        if let Some(handler) = handler {
+            handler(identifier, false)
+        }
+    }
+
+    pub unsafe extern "C-unwind" fn keybind_register_with_string(
+        identifier: *const c_char,
+        keybind_handler: RawKeybindHandler,
+        keybind: *const c_char,
+    ) {
+        insert_keybind_handler(identifier, keybind_handler);
+        match crate::globals::raw_addon_api() {
+            Some(RawAddonApi::V2(api)) => {
+                (api.keybind_register_with_string)(identifier, keybind_handler_old, keybind)
+            }
+            Some(RawAddonApi::V3(api)) => {
+                (api.keybind_register_with_string)(identifier, keybind_handler_old, keybind)
+            }
+            _ => {}
+        }
+    }
+
+    pub unsafe extern "C-unwind" fn keybind_register_with_struct(
+        identifier: *const c_char,
+        keybind_handler: RawKeybindHandler,
+        keybind: Keybind,
+    ) {
+        insert_keybind_handler(identifier, keybind_handler);
+        match crate::globals::raw_addon_api() {
+            Some(RawAddonApi::V2(api)) => {
+                (api.keybind_register_with_struct)(identifier, keybind_handler_old, keybind)
+            }
+            Some(RawAddonApi::V3(api)) => {
+                (api.keybind_register_with_struct)(identifier, keybind_handler_old, keybind)
+            }
+            _ => {}
+        }
+    }
+
+    pub unsafe extern "C-unwind" fn keybind_deregister(identifier: *const c_char) {
+        let key = CStr::from_ptr(identifier).to_string_lossy();
+        with_keybind_handlers(|handlers| handlers.remove(key.as_ref()));
+        match crate::globals::raw_addon_api() {
+            Some(RawAddonApi::V2(api)) => (api.keybind_deregister)(identifier),
+            Some(RawAddonApi::V3(api)) => (api.keybind_deregister)(identifier),
+            _ => {}
+        }
+    }
+
+    pub unsafe extern "C-unwind" fn keybind_invoke(_identifier: *const c_char, _is_release: bool) {
+        report("invoking keybinds")
+    }
+
+    pub unsafe extern "C-unwind" fn send_alert(message: *const c_char) {
+        if let Some(RawAddonApi::V2(api)) = crate::globals::raw_addon_api() {
+            (api.log)(LogLevel::Info, b"Alert\0".as_ptr().cast(), message)
+        }
+    }
+
+    pub unsafe extern "C-unwind" fn register_close_on_escape(
+        _window_name: *const c_char,
+        _is_visible: *mut bool,
+    ) {
+        report("closing windows on escape")
+    }
+
+    pub unsafe extern "C-unwind" fn deregister_close_on_escape(_window_name: *const c_char) {
+        report("closing windows on escape")
+    }
+
+    pub unsafe extern "C-unwind" fn request_update(_signature: i32, _update_url: *const c_char) {
+        report("update requests")
+    }
+
+    pub unsafe extern "C-unwind" fn event_raise_targeted(
+        _signature: i32,
+        _identifier: *const c_char,
+        _event_data: *const c_void,
+    ) {
+        report("targeted events")
+    }
+
+    pub unsafe extern "C-unwind" fn event_raise_notification_targeted(
+        _signature: i32,
+        _identifier: *const c_char,
+    ) {
+        report("targeted events")
+    }
+
+    pub unsafe extern "C-unwind" fn gamebind(_bind: GameBind) {
+        report("game binds")
+    }
+
+    pub unsafe extern "C-unwind" fn gamebind_invoke(_bind: GameBind, _duration: i32) {
+        report("game binds")
+    }
+
+    pub unsafe extern "C-unwind" fn gamebind_is_bound(_bind: GameBind) -> bool {
+        report("game binds");
+        false
+    }
+
+    pub unsafe extern "C-unwind" fn quick_access_add_context_menu(
+        identifier: *const c_char,
+        _target_identifier: *const c_char,
+        shortcut_render_callback: RawGuiRender,
+    ) {
+        match crate::globals::raw_addon_api() {
+            Some(RawAddonApi::V2(api)) => {
+                (api.add_simple_shortcut)(identifier, shortcut_render_callback)
+            }
+            Some(RawAddonApi::V3(api)) => {
+                (api.add_simple_shortcut)(identifier, shortcut_render_callback)
+            }
+            Some(RawAddonApi::V4(api)) => {
+                (api.add_simple_shortcut)(identifier, shortcut_render_callback)
+            }
+            _ => {}
+        }
+    }
+
+    pub unsafe extern "C-unwind" fn localization_set(
+        _identifier: *const c_char,
+        _language_identifier: *const c_char,
+        _string: *const c_char,
+    ) {
+        report("setting translations")
+    }
+
+    pub unsafe extern "C-unwind" fn font_receiver(
+        _identifier: *const c_char,
+        _callback: RawFontReceive,
+    ) {
+        report("fonts")
+    }
+
+    pub unsafe extern "C-unwind" fn font_add_from_file(
+        _identifier: *const c_char,
+        _font_size: f32,
+        _filename: *const c_char,
+        _callback: RawFontReceive,
+        _config: *mut ImFontConfig,
+    ) {
+        report("fonts")
+    }
+
+    pub unsafe extern "C-unwind" fn font_add_from_resource(
+        _identifier: *const c_char,
+        _font_size: f32,
+        _resource_id: u32,
+        _module: HMODULE,
+        _callback: RawFontReceive,
+        _config: *mut ImFontConfig,
+    ) {
+        report("fonts")
+    }
+
+    pub unsafe extern "C-unwind" fn font_add_from_memory(
+        _identifier: *const c_char,
+        _font_size: f32,
+        _data: *const c_void,
+        _size: usize,
+        _callback: RawFontReceive,
+        _config: *mut ImFontConfig,
+    ) {
+        report("fonts")
+    }
+
+    pub unsafe extern "C-unwind" fn font_resize(_identifier: *const c_char, _font_size: f32) {
+        report("font resizing")
+    }
+}
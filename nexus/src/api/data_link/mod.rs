@@ -17,7 +17,10 @@ pub use self::nexus::*;
 #[cfg(feature = "mumble")]
 pub use self::mumble::{get_mumble_link, get_mumble_link_ptr, read_mumble_link, MumbleLink};
 
-use crate::{util::str_to_c, AddonApi, DataLinkApi};
+use crate::{
+    util::{try_ident_to_c, unwrap_or_panic},
+    AddonApi, DataLinkApi, Error, IntoCStr,
+};
 use std::{
     ffi::{c_char, c_void},
     mem,
@@ -30,26 +33,53 @@ pub type RawDataShareResource =
     unsafe extern "C-unwind" fn(identifier: *const c_char, resource_size: usize) -> *mut c_void;
 
 /// Returns a pointer to a shared resource.
-pub fn get_resource<T>(identifier: impl AsRef<str>) -> *const T {
-    let identifier = str_to_c(identifier, "failed to convert data link identifier");
-    let DataLinkApi { get, .. } = AddonApi::get().data_link;
-    unsafe { get(identifier.as_ptr()).cast() }
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn get_resource<T>(identifier: impl IntoCStr) -> *const T {
+    unwrap_or_panic(try_get_resource(identifier))
+}
+
+/// Fallible version of [`get_resource`].
+pub fn try_get_resource<T>(identifier: impl IntoCStr) -> Result<*const T, Error> {
+    let identifier = try_ident_to_c(identifier, "data link identifier")?;
+    let DataLinkApi { get, .. } = AddonApi::try_get()?.data_link;
+    Ok(unsafe { get(identifier.as_ptr()).cast() })
 }
 
 /// Reads a shared resource.
 ///
+/// Panics if the identifier contains a nul byte.
+///
 /// # Safety
 /// The caller must ensure the data associated with the given identifier is of type `T`.
-pub unsafe fn read_resource<T>(identifier: impl AsRef<str>) -> Option<T> {
-    let ptr = get_resource::<T>(identifier);
+#[track_caller]
+pub unsafe fn read_resource<T>(identifier: impl IntoCStr) -> Option<T> {
+    unwrap_or_panic(try_read_resource(identifier))
+}
+
+/// Fallible version of [`read_resource`].
+///
+/// # Safety
+/// See [`read_resource`].
+pub unsafe fn try_read_resource<T>(identifier: impl IntoCStr) -> Result<Option<T>, Error> {
+    let ptr = try_get_resource::<T>(identifier)?;
     let valid = !ptr.is_null();
-    valid.then(|| unsafe { ptr.read_volatile() })
+    Ok(valid.then(|| unsafe { ptr.read_volatile() }))
 }
 
 /// Creates a new shared resource.
-pub fn share_resource<T>(identifier: impl AsRef<str>) -> *mut T {
-    let identifier = str_to_c(identifier, "failed to convert data link identifier");
-    let DataLinkApi { share, .. } = AddonApi::get().data_link;
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn share_resource<T>(identifier: impl IntoCStr) -> *mut T {
+    unwrap_or_panic(try_share_resource(identifier))
+}
+
+/// Fallible version of [`share_resource`].
+pub fn try_share_resource<T>(identifier: impl IntoCStr) -> Result<*mut T, Error> {
+    let identifier = try_ident_to_c(identifier, "data link identifier")?;
+    let DataLinkApi { share, .. } = AddonApi::try_get()?.data_link;
     let size = mem::size_of::<T>();
-    unsafe { share(identifier.as_ptr(), size).cast() }
+    Ok(unsafe { share(identifier.as_ptr(), size).cast() })
 }
@@ -1,14 +1,20 @@
 pub use gw2_mumble::{LinkedMem as MumbleLink, *};
 
 use super::{get_resource, read_resource};
+use crate::{Global, Identifier};
 
 /// Mumble link identifier.
+///
+/// Needs to be passed as [`Global`] if namespacing is enabled.
 pub const MUMBLE_LINK: &str = "DL_MUMBLE_LINK";
 
+/// Mumble link identifier as global [`Identifier`].
+const MUMBLE_LINK_IDENT: Global<Identifier<'static>> = Global(crate::c_ident!("DL_MUMBLE_LINK"));
+
 /// Returns the shared [`MumbleLink`] pointer.
 #[inline]
 pub fn get_mumble_link_ptr() -> *const MumbleLink {
-    get_resource(MUMBLE_LINK)
+    get_resource(MUMBLE_LINK_IDENT)
 }
 
 /// Returns the shared [`MumbleLink`] pointer as [`MumblePtr`].
@@ -21,5 +27,5 @@ pub fn get_mumble_link() -> Option<MumblePtr> {
 /// Reads the shared [`MumbleLink`] data.
 #[inline]
 pub fn read_mumble_link() -> Option<MumbleLink> {
-    unsafe { read_resource(MUMBLE_LINK) }
+    unsafe { read_resource(MUMBLE_LINK_IDENT) }
 }
@@ -1,4 +1,5 @@
 use super::{get_resource, read_resource};
+use crate::{Global, Identifier};
 use imgui::sys::ImFont;
 
 /// Nexus link identifier.
@@ -28,18 +29,23 @@ pub struct NexusLink {
 
 impl NexusLink {
     /// Nexus link identifier.
+    ///
+    /// Needs to be passed as [`Global`] if namespacing is enabled.
     pub const LINK: &str = "DL_NEXUS_LINK";
 
+    /// Nexus link identifier as global [`Identifier`].
+    const LINK_IDENT: Global<Identifier<'static>> = Global(crate::c_ident!("DL_NEXUS_LINK"));
+
     /// Returns the shared [`NexusLink`] pointer.
     #[inline]
     pub fn get() -> *const Self {
-        get_resource(Self::LINK)
+        get_resource(Self::LINK_IDENT)
     }
 
     /// Reads the shared [`NexusLink`] data.
     #[inline]
     pub fn read() -> Option<NexusLink> {
-        unsafe { read_resource(Self::LINK) }
+        unsafe { read_resource(Self::LINK_IDENT) }
     }
 }
 
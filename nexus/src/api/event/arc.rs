@@ -1,16 +1,17 @@
 //! [ArcDPS EVTC](https://deltaconnected.com/arcdps/) bridge events.
 
 use super::Event;
+use crate::identifier::c_ident;
 use arcdps::evtc::{self, Agent};
 use std::ffi::{c_char, CStr};
 
 /// ArcDPS EVTC combat local event.
 pub const COMBAT_LOCAL: Event<CombatData> =
-    unsafe { Event::new("EV_ARCDPS_COMBATEVENT_LOCAL_RAW") };
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_COMBATEVENT_LOCAL_RAW\0")) };
 
 /// ArcDPS EVTC combat squad event.
 pub const COMBAT_SQUAD: Event<CombatData> =
-    unsafe { Event::new("EV_ARCDPS_COMBATEVENT_SQUAD_RAW") };
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_COMBATEVENT_SQUAD_RAW\0")) };
 
 /// ArcDPS self join event.
 ///
@@ -18,17 +19,20 @@ pub const COMBAT_SQUAD: Event<CombatData> =
 ///
 /// Events of this type are triggered upon map load.
 /// The last event can be retriggered on demand by addons sending an [`REPLAY_SELF_JOIN`] event.
-pub const SELF_JOIN: Event<AgentUpdate> = unsafe { Event::new("EV_ARCDPS_SELF_JOIN") };
+pub const SELF_JOIN: Event<AgentUpdate> =
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_SELF_JOIN\0")) };
 
 /// Replays the last [`SELF_JOIN`] event.
-pub const REPLAY_SELF_JOIN: Event<()> = unsafe { Event::new("EV_REPLAY_ARCDPS_SELF_JOIN") };
+pub const REPLAY_SELF_JOIN: Event<()> =
+    unsafe { Event::new_global(c_ident(b"EV_REPLAY_ARCDPS_SELF_JOIN\0")) };
 
 /// ArcDPS self leave event.
 ///
 /// Payload is [`AgentUpdate`] of the self player agent.
 ///
 /// Events of this type are triggered when changing instance or leaving a party / squad.
-pub const SELF_LEAVE: Event<AgentUpdate> = unsafe { Event::new("EV_ARCDPS_SELF_LEAVE") };
+pub const SELF_LEAVE: Event<AgentUpdate> =
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_SELF_LEAVE\0")) };
 
 /// ArcDPS squad join event.
 ///
@@ -38,10 +42,12 @@ pub const SELF_LEAVE: Event<AgentUpdate> = unsafe { Event::new("EV_ARCDPS_SELF_L
 ///
 /// Nexus tracks all players in your squad and can retrigger these events on demand by addons sending an [`REPLAY_SQUAD_JOIN`] event.
 /// This is intended to be used during addon load, you should be careful to handle duplicates since this can be triggered by other addons.
-pub const SQUAD_JOIN: Event<AgentUpdate> = unsafe { Event::new("EV_ARCDPS_SQUAD_JOIN") };
+pub const SQUAD_JOIN: Event<AgentUpdate> =
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_SQUAD_JOIN\0")) };
 
 /// Replays [`SQUAD_JOIN`] events for the current squad.
-pub const REPLAY_SQUAD_JOIN: Event<()> = unsafe { Event::new("EV_REPLAY_ARCDPS_SQUAD_JOIN") };
+pub const REPLAY_SQUAD_JOIN: Event<()> =
+    unsafe { Event::new_global(c_ident(b"EV_REPLAY_ARCDPS_SQUAD_JOIN\0")) };
 
 /// ArcDPS squad leave event.
 ///
@@ -50,23 +56,25 @@ pub const REPLAY_SQUAD_JOIN: Event<()> = unsafe { Event::new("EV_REPLAY_ARCDPS_S
 /// Events of this type are triggered when allied players in your instance and party/squad either leave your instance or leave your party/squad.
 /// You will not recieve these events if you are the one to change instance or leave the party/squad.
 /// These events have a 2 second delay.
-pub const SQUAD_LEAVE: Event<AgentUpdate> = unsafe { Event::new("EV_ARCDPS_SQUAD_LEAVE") };
+pub const SQUAD_LEAVE: Event<AgentUpdate> =
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_SQUAD_LEAVE\0")) };
 
 /// ArcDPS target changed event.
 ///
 /// Events of this type are triggered when you target an agent.
 /// The last event can be retriggered on demand by addons sending an [`REPLAY_TARGET_CHAGNED`] event.
-pub const TARGET_CHANGED: Event<AgentUpdate> = unsafe { Event::new("EV_ARCDPS_TARGET_CHANGED") };
+pub const TARGET_CHANGED: Event<AgentUpdate> =
+    unsafe { Event::new_global(c_ident(b"EV_ARCDPS_TARGET_CHANGED\0")) };
 
 /// Replays the [`TARGET_CHANGED`] event for the current target.
 pub const REPLAY_TARGET_CHANGED: Event<()> =
-    unsafe { Event::new("EV_REPLAY_ARCDPS_TARGET_CHANGED") };
+    unsafe { Event::new_global(c_ident(b"EV_REPLAY_ARCDPS_TARGET_CHANGED\0")) };
 
 /// ArcDPS player account name.
 ///
 /// Triggered on first map load.
 /// Can be triggered on demand by sending `"EV_REQUEST_ACCOUNT_NAME"`.
-pub const ACCOUNT_NAME: Event<c_char> = unsafe { Event::new("EV_ACCOUNT_NAME") };
+pub const ACCOUNT_NAME: Event<c_char> = unsafe { Event::new_global(c_ident(b"EV_ACCOUNT_NAME\0")) };
 
 /// ArcDPS agent update.
 #[derive(Debug, Clone)]
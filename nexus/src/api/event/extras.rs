@@ -1,6 +1,7 @@
 //! [Unofficial Extras](https://github.com/Krappa322/arcdps_unofficial_extras_releases) bridge events.
 
 use super::Event;
+use crate::identifier::c_ident;
 use arcdps::{
     extras::{
         keybinds::RawKeybindChange, message::RawChatMessageInfo, user::to_user_info_iter, UserInfo,
@@ -11,7 +12,7 @@ use arcdps::{
 
 /// Unofficial Extras squad update event.
 pub const EXTRAS_SQUAD_UPDATE: Event<SquadUpdate> =
-    unsafe { Event::new("EV_UNOFFICIAL_EXTRAS_SQUAD_UPDATE") };
+    unsafe { Event::new_global(c_ident(b"EV_UNOFFICIAL_EXTRAS_SQUAD_UPDATE\0")) };
 
 /// Unofficial Extras squad update payload.
 #[derive(Debug, Clone)]
@@ -30,12 +31,12 @@ impl SquadUpdate {
 
 /// Unofficial Extras language changed event.
 pub const LANGUAGE_CHANGED: Event<Language> =
-    unsafe { Event::new("EV_UNOFFICIAL_EXTRAS_LANGUAGE_CHANGED") };
+    unsafe { Event::new_global(c_ident(b"EV_UNOFFICIAL_EXTRAS_LANGUAGE_CHANGED\0")) };
 
 /// Unofficial Extras keybind changed event.
 pub const KEYBIND_CHANGED: Event<RawKeybindChange> =
-    unsafe { Event::new("EV_UNOFFICIAL_EXTRAS_KEYBIND_CHANGED") };
+    unsafe { Event::new_global(c_ident(b"EV_UNOFFICIAL_EXTRAS_KEYBIND_CHANGED\0")) };
 
 /// Unofficial Extras chat message event.
 pub const CHAT_MESSAGE: Event<RawChatMessageInfo> =
-    unsafe { Event::new("EV_UNOFFICIAL_EXTRAS_CHAT_MESSAGE") };
+    unsafe { Event::new_global(c_ident(b"EV_UNOFFICIAL_EXTRAS_CHAT_MESSAGE\0")) };
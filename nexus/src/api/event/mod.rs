@@ -28,48 +28,116 @@ pub mod extras;
 pub mod rtapi;
 
 use super::EventApi;
-use crate::{revertible::Revertible, util::str_to_c, AddonApi};
+use crate::{
+    dispatch::Dispatch,
+    registry::{self, RegistrationKind},
+    revertible::Revertible,
+    util::{try_c_to_str, try_ident_to_c, unwrap_or_panic},
+    AddonApi, Error, Global, IntoCStr,
+};
 use std::{
-    ffi::{c_char, c_void},
+    ffi::{c_char, c_void, CStr, CString, NulError},
     marker::PhantomData,
     mem,
+    sync::{Arc, Mutex, PoisonError},
 };
 
 pub use self::nexus::*;
 
 /// An event identifier & payload type pair.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 pub struct Event<T> {
-    pub identifier: &'static str,
+    pub identifier: &'static CStr,
+    global: bool,
     _phantom: PhantomData<T>,
 }
 
 impl<T> Event<T> {
     /// Creates a new event identifier & payload type pair.
     ///
+    /// The identifier is namespaced if enabled.
+    /// It is passed to Nexus without allocation when raising the event.
+    ///
     /// # Safety
     /// See [`event_subscribe_typed`].
     #[inline]
-    pub const unsafe fn new(identifier: &'static str) -> Self {
+    pub const unsafe fn new(identifier: &'static CStr) -> Self {
         Self {
             identifier,
+            global: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new event identifier & payload type pair exempt from namespacing.
+    ///
+    /// Used for events shared with Nexus or other addons.
+    ///
+    /// # Safety
+    /// See [`event_subscribe_typed`].
+    #[inline]
+    pub const unsafe fn new_global(identifier: &'static CStr) -> Self {
+        Self {
+            identifier,
+            global: true,
             _phantom: PhantomData,
         }
     }
 
     /// Subscribes to the event.
     #[inline]
+    #[track_caller]
     pub fn subscribe(
         &self,
         callback: RawEventConsume<T>,
     ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
-        unsafe { event_subscribe_typed(self.identifier, callback) }
+        unsafe { event_subscribe_typed(*self, callback) }
+    }
+
+    /// Subscribes to the event with a closure.
+    ///
+    /// Panics if closures are already subscribed to 64 other distinct events.
+    /// See [`event_subscribe_typed_fn`].
+    #[inline]
+    #[track_caller]
+    pub fn subscribe_fn(
+        &self,
+        callback: impl FnMut(Option<&T>) + Send + 'static,
+    ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static>
+    where
+        T: 'static,
+    {
+        unsafe { event_subscribe_typed_fn(*self, callback) }
     }
 
     /// Raises the event.
     #[inline]
+    #[track_caller]
     pub fn raise(&self, event_data: &T) {
-        unsafe { event_raise(self.identifier, event_data) }
+        unsafe { event_raise(*self, event_data) }
+    }
+}
+
+impl<T> Clone for Event<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Event<T> {}
+
+impl<T> IntoCStr for Event<T> {
+    type Output = &'static CStr;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self.identifier)
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        self.global
     }
 }
 
@@ -99,41 +167,263 @@ pub type RawEventSubscribe = unsafe extern "C-unwind" fn(
 /// Subscribes to an event with a raw callback using an unknown payload.
 ///
 /// Returns a [`Revertible`] to revert the subscribe.
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
 pub fn event_subscribe_unknown(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     callback: RawEventConsumeUnknown,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
-    let identifier = str_to_c(identifier, "failed to convert event identifier");
+    unwrap_or_panic(try_event_subscribe_unknown(identifier, callback))
+}
+
+/// Fallible version of [`event_subscribe_unknown`].
+pub fn try_event_subscribe_unknown(
+    identifier: impl IntoCStr,
+    callback: RawEventConsumeUnknown,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let identifier: CString = try_ident_to_c(identifier, "event identifier")?.into();
     let EventApi {
         subscribe,
         unsubscribe,
         ..
-    } = AddonApi::get().event;
+    } = AddonApi::try_get()?.event;
     unsafe { subscribe(identifier.as_ptr(), callback) };
+    #[cfg(feature = "debug")]
+    crate::debug::record_event_callback(&identifier, callback as usize);
+    let name = registry::name(&identifier);
     let revert = move || unsafe { unsubscribe(identifier.as_ptr(), callback) };
-    revert.into()
+    Ok(registry::track(
+        RegistrationKind::Event,
+        name,
+        Some(callback as usize),
+        revert,
+    ))
 }
 
 /// Subscribes to an event with a raw callback using a typed payload.
 ///
 /// Returns a [`Revertible`] to revert the subscribe.
+/// Panics if the identifier contains a nul byte.
 ///
 /// # Safety
 /// The passed event identifier must always come with valid data of the given type.
+#[track_caller]
 pub unsafe fn event_subscribe_typed<T>(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     callback: RawEventConsume<T>,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_event_subscribe_typed(identifier, callback))
+}
+
+/// Fallible version of [`event_subscribe_typed`].
+///
+/// # Safety
+/// See [`event_subscribe_typed`].
+pub unsafe fn try_event_subscribe_typed<T>(
+    identifier: impl IntoCStr,
+    callback: RawEventConsume<T>,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let identifier: CString = try_ident_to_c(identifier, "event identifier")?.into();
+    #[cfg(feature = "debug")]
+    crate::debug::record_event_payload(&identifier, mem::size_of::<T>());
     let callback =
         unsafe { mem::transmute::<RawEventConsume<T>, RawEventConsumeUnknown>(callback) };
-    event_subscribe_unknown(identifier, callback)
+    try_event_subscribe_unknown(Global(identifier), callback)
+}
+
+/// Records a received event for the diagnostics of the `debug` feature.
+///
+/// The statistics are recorded for the subscribed raw callback.
+/// A call to this is inserted by the [`event_subscribe!`] macro.
+#[doc(hidden)]
+#[inline]
+pub fn record_event_hit(_callback: usize, _payload_null: bool) {
+    #[cfg(feature = "debug")]
+    crate::debug::record_callback_hit(_callback, _payload_null);
 }
 
 /// Unsubscribes a previously registered raw event callback.
-pub fn event_unsubscribe(identifier: impl AsRef<str>, callback: RawEventConsumeUnknown) {
-    let identifier = str_to_c(identifier, "failed to convert event identifier");
-    let EventApi { unsubscribe, .. } = AddonApi::get().event;
-    unsafe { unsubscribe(identifier.as_ptr(), callback) }
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn event_unsubscribe(identifier: impl IntoCStr, callback: RawEventConsumeUnknown) {
+    unwrap_or_panic(try_event_unsubscribe(identifier, callback))
+}
+
+/// Fallible version of [`event_unsubscribe`].
+pub fn try_event_unsubscribe(
+    identifier: impl IntoCStr,
+    callback: RawEventConsumeUnknown,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "event identifier")?;
+    let EventApi { unsubscribe, .. } = AddonApi::try_get()?.event;
+    unsafe { unsubscribe(identifier.as_ptr(), callback) };
+    registry::untrack(
+        RegistrationKind::Event,
+        Some(&identifier.to_string_lossy()),
+        Some(callback as usize),
+    );
+    Ok(())
+}
+
+/// Closure consuming an event.
+type EventConsumeFn = dyn FnMut(*const c_void) + Send;
+
+/// Registered event closures.
+static EVENT_FNS: Dispatch<String, EventConsumeFn> = Dispatch::new();
+
+/// Event identifiers assigned to the trampoline slots.
+static EVENT_SLOTS: Mutex<[Option<String>; EVENT_SLOT_COUNT]> = Mutex::new(EVENT_SLOTS_EMPTY);
+
+/// Maximum number of distinct events subscribed with closures at the same time.
+const EVENT_SLOT_COUNT: usize = 64;
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EVENT_SLOTS_EMPTY: [Option<String>; EVENT_SLOT_COUNT] = {
+    const NONE: Option<String> = None;
+    [NONE; EVENT_SLOT_COUNT]
+};
+
+/// Event callbacks receive no identifier, so every subscribed event needs its own trampoline.
+const EVENT_TRAMPOLINES: [RawEventConsumeUnknown; EVENT_SLOT_COUNT] = {
+    extern "C-unwind" fn trampoline<const SLOT: usize>(event_args: *const c_void) {
+        dispatch_event(SLOT, event_args)
+    }
+
+    macro_rules! trampolines {
+        ( $( $slot:literal )* ) => {
+            [ $( trampoline::<$slot> ),* ]
+        };
+    }
+
+    trampolines!(
+        0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+        32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60
+        61 62 63
+    )
+};
+
+/// Subscribes to an event with a closure using an unknown payload.
+///
+/// Unlike [`event_subscribe_unknown`] the closure can capture state.
+/// Closures can be subscribed to at most 64 distinct events at the same time.
+/// Panics if this limit is exceeded or the identifier contains a nul byte.
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+#[track_caller]
+pub fn event_subscribe_unknown_fn(
+    identifier: impl IntoCStr,
+    callback: impl FnMut(*const c_void) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_event_subscribe_unknown_fn(identifier, callback))
+}
+
+/// Fallible version of [`event_subscribe_unknown_fn`].
+///
+/// Returns [`Error::TooManySubscriptions`] if closures are already subscribed to 64 other distinct events.
+pub fn try_event_subscribe_unknown_fn(
+    identifier: impl IntoCStr,
+    callback: impl FnMut(*const c_void) + Send + 'static,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let c_identifier = try_ident_to_c(identifier, "event identifier")?;
+    let identifier = try_c_to_str(&c_identifier, "event identifier")?;
+    let id = EVENT_FNS.try_insert(
+        identifier.into(),
+        Arc::new(Mutex::new(callback)),
+        |identifier| {
+            let slot =
+                {
+                    let mut slots = EVENT_SLOTS.lock().unwrap_or_else(PoisonError::into_inner);
+                    let slot = slots.iter().position(Option::is_none).ok_or(
+                        Error::TooManySubscriptions {
+                            capacity: EVENT_SLOT_COUNT,
+                        },
+                    )?;
+                    slots[slot] = Some(identifier.clone());
+                    slot
+                };
+            let EventApi { subscribe, .. } = AddonApi::try_get()?.event;
+            unsafe { subscribe(c_identifier.as_ptr(), EVENT_TRAMPOLINES[slot]) };
+            let key = identifier.clone();
+            registry::track_trampoline(
+                RegistrationKind::Event,
+                Some(identifier.clone()),
+                EVENT_TRAMPOLINES[slot] as usize,
+                move || EVENT_FNS.remove_all(&key, unsubscribe_event_trampoline),
+            );
+            Ok(())
+        },
+        unsubscribe_event_trampoline,
+    )?;
+    let revert = move || EVENT_FNS.remove(id, unsubscribe_event_trampoline);
+    Ok(registry::track_fn(
+        RegistrationKind::Event,
+        Some(identifier.into()),
+        revert,
+    ))
+}
+
+/// Subscribes to an event with a closure using a typed payload.
+///
+/// Unlike [`event_subscribe_typed`] the closure can capture state.
+/// Closures can be subscribed to at most 64 distinct events at the same time.
+/// Panics if this limit is exceeded or the identifier contains a nul byte.
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+///
+/// # Safety
+/// The passed event identifier must always come with valid data of the given type.
+#[track_caller]
+pub unsafe fn event_subscribe_typed_fn<T: 'static>(
+    identifier: impl IntoCStr,
+    callback: impl FnMut(Option<&T>) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_event_subscribe_typed_fn(identifier, callback))
+}
+
+/// Fallible version of [`event_subscribe_typed_fn`].
+///
+/// Returns [`Error::TooManySubscriptions`] if closures are already subscribed to 64 other distinct events.
+///
+/// # Safety
+/// See [`event_subscribe_typed_fn`].
+pub unsafe fn try_event_subscribe_typed_fn<T: 'static>(
+    identifier: impl IntoCStr,
+    mut callback: impl FnMut(Option<&T>) + Send + 'static,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let identifier: CString = try_ident_to_c(identifier, "event identifier")?.into();
+    #[cfg(feature = "debug")]
+    crate::debug::record_event_payload(&identifier, mem::size_of::<T>());
+    try_event_subscribe_unknown_fn(Global(identifier), move |event_args| {
+        callback(unsafe { event_args.cast::<T>().as_ref() })
+    })
+}
+
+fn unsubscribe_event_trampoline(identifier: &String) {
+    let slot = {
+        let mut slots = EVENT_SLOTS.lock().unwrap_or_else(PoisonError::into_inner);
+        slots
+            .iter()
+            .position(|slot| slot.as_ref() == Some(identifier))
+            .map(|slot| {
+                slots[slot] = None;
+                slot
+            })
+    };
+    if let Some(slot) = slot {
+        event_unsubscribe(Global(identifier), EVENT_TRAMPOLINES[slot])
+    }
+}
+
+fn dispatch_event(slot: usize, event_args: *const c_void) {
+    let identifier = EVENT_SLOTS.lock().unwrap_or_else(PoisonError::into_inner)[slot].clone();
+    if let Some(identifier) = identifier {
+        #[cfg(feature = "debug")]
+        crate::debug::record_event(&identifier, event_args.is_null());
+        EVENT_FNS.call(&identifier, "event callback", |callback| {
+            callback(event_args)
+        })
+    }
 }
 
 /// Macro to wrap an event callback.
@@ -182,8 +472,12 @@ macro_rules! event_consume {
         const __CALLBACK: fn(::std::option::Option<&$ty>) = ($callback);
 
         extern "C-unwind" fn __event_callback_wrapper(data: *const $ty) {
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
             let _ = unsafe { ::std::mem::transmute::<*const $ty, *const ::std::ffi::c_void>(data) }; // size check
-            __CALLBACK(unsafe { data.as_ref() })
+            $crate::__macro::catch("event callback", &__DISABLED, || {
+                __CALLBACK(unsafe { data.as_ref() })
+            });
         }
 
         __event_callback_wrapper
@@ -264,50 +558,108 @@ macro_rules! event_subscribe {
     ( $event:expr , $ty:ty , $callback:expr $(,)? ) => {
         $crate::event::event_subscribe!($event => $ty, $callback)
     };
-    ( $event:expr => $ty:ty , $callback:expr $(,)? ) => {
-        $crate::event::event_subscribe_typed($event, $crate::event::event_consume!(<$ty> $callback))
-    };
+    ( $event:expr => $ty:ty , $callback:expr $(,)? ) => {{
+        extern "C-unwind" fn __event_hit_wrapper(data: *const $ty) {
+            $crate::__macro::record_event_hit(__event_hit_wrapper as usize, data.is_null());
+            ($crate::event::event_consume!(<$ty> $callback))(data)
+        }
+
+        $crate::event::event_subscribe_typed($event, __event_hit_wrapper)
+    }};
 }
 
 pub use event_subscribe;
 
 /// Raises an event to all subscribing addons.
 ///
+/// Panics if the identifier contains a nul byte.
+///
 /// # Safety
 /// The passed event identifier must be associated with data of the given type.
-pub unsafe fn event_raise<T>(identifier: impl AsRef<str>, event_data: &T) {
-    let identifier = str_to_c(identifier, "failed to convert event identifier");
+#[track_caller]
+pub unsafe fn event_raise<T>(identifier: impl IntoCStr, event_data: &T) {
+    unwrap_or_panic(try_event_raise(identifier, event_data))
+}
+
+/// Fallible version of [`event_raise`].
+///
+/// # Safety
+/// See [`event_raise`].
+pub unsafe fn try_event_raise<T>(identifier: impl IntoCStr, event_data: &T) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "event identifier")?;
     let data: *const _ = event_data;
-    let EventApi { raise, .. } = AddonApi::get().event;
-    unsafe { raise(identifier.as_ptr(), data.cast()) }
+    let EventApi { raise, .. } = AddonApi::try_get()?.event;
+    unsafe { raise(identifier.as_ptr(), data.cast()) };
+    Ok(())
 }
 
 /// Raises an event without payload to all subscribing addons.
-pub fn event_raise_notification(identifier: impl AsRef<str>) {
-    let identifier = str_to_c(identifier, "failed to convert event identifier");
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn event_raise_notification(identifier: impl IntoCStr) {
+    unwrap_or_panic(try_event_raise_notification(identifier))
+}
+
+/// Fallible version of [`event_raise_notification`].
+pub fn try_event_raise_notification(identifier: impl IntoCStr) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "event identifier")?;
     let EventApi {
         raise_notification, ..
-    } = AddonApi::get().event;
-    unsafe { raise_notification(identifier.as_ptr()) }
+    } = AddonApi::try_get()?.event;
+    unsafe { raise_notification(identifier.as_ptr()) };
+    Ok(())
 }
 
 /// Raises an event for a specific subscribing addon.
 ///
+/// Panics if the identifier contains a nul byte or targeted events are not supported.
+///
+/// # Safety
+/// See [`event_raise`].
+#[track_caller]
+pub unsafe fn event_raise_targeted<T>(signature: i32, identifier: impl IntoCStr, event_data: &T) {
+    unwrap_or_panic(try_event_raise_targeted(signature, identifier, event_data))
+}
+
+/// Fallible version of [`event_raise_targeted`].
+///
 /// # Safety
 /// See [`event_raise`].
-pub unsafe fn event_raise_targeted<T>(signature: i32, identifier: impl AsRef<str>, event_data: &T) {
-    let identifier = str_to_c(identifier, "failed to convert event identifier");
+pub unsafe fn try_event_raise_targeted<T>(
+    signature: i32,
+    identifier: impl IntoCStr,
+    event_data: &T,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "event identifier")?;
     let data: *const _ = event_data;
-    let EventApi { raise_targeted, .. } = AddonApi::get().event;
-    unsafe { raise_targeted(signature, identifier.as_ptr(), data.cast()) }
+    let EventApi { raise_targeted, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_targeted_events, "targeted events")?
+        .event;
+    unsafe { raise_targeted(signature, identifier.as_ptr(), data.cast()) };
+    Ok(())
 }
 
 /// Raises an event without payload for a specific subscribing addon.
-pub fn event_raise_notification_targeted(signature: i32, identifier: impl AsRef<str>) {
-    let identifier = str_to_c(identifier, "failed to convert event identifier");
+///
+/// Panics if the identifier contains a nul byte or targeted events are not supported.
+#[track_caller]
+pub fn event_raise_notification_targeted(signature: i32, identifier: impl IntoCStr) {
+    unwrap_or_panic(try_event_raise_notification_targeted(signature, identifier))
+}
+
+/// Fallible version of [`event_raise_notification_targeted`].
+pub fn try_event_raise_notification_targeted(
+    signature: i32,
+    identifier: impl IntoCStr,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "event identifier")?;
     let EventApi {
         raise_notification_targeted,
         ..
-    } = AddonApi::get().event;
-    unsafe { raise_notification_targeted(signature, identifier.as_ptr()) }
+    } = AddonApi::try_get()?
+        .require(AddonApi::has_targeted_events, "targeted events")?
+        .event;
+    unsafe { raise_notification_targeted(signature, identifier.as_ptr()) };
+    Ok(())
 }
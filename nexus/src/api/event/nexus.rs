@@ -1,22 +1,25 @@
 //! Nexus events.
 
 use super::Event;
+use crate::identifier::c_ident;
 
 /// Nexus addon loaded event.
-pub const ADDON_LOADED: Event<i32> = unsafe { Event::new("EV_ADDON_LOADED") };
+pub const ADDON_LOADED: Event<i32> = unsafe { Event::new_global(c_ident(b"EV_ADDON_LOADED\0")) };
 
 /// Nexus addon unloaded event.
-pub const ADDON_UNLOADED: Event<i32> = unsafe { Event::new("EV_ADDON_UNLOADED") };
+pub const ADDON_UNLOADED: Event<i32> =
+    unsafe { Event::new_global(c_ident(b"EV_ADDON_UNLOADED\0")) };
 
 /// Nexus volatile addon disabled event.
-pub const VOLATILE_ADDON_DISABLED: Event<i32> = unsafe { Event::new("EV_VOLATILE_ADDON_DISABLED") };
+pub const VOLATILE_ADDON_DISABLED: Event<i32> =
+    unsafe { Event::new_global(c_ident(b"EV_VOLATILE_ADDON_DISABLED\0")) };
 
 /// Window resized event.
-pub const WINDOW_RESIZED: Event<()> = unsafe { Event::new("EV_WINDOW_RESIZED") };
+pub const WINDOW_RESIZED: Event<()> = unsafe { Event::new_global(c_ident(b"EV_WINDOW_RESIZED\0")) };
 
 /// Mumble identity updated event.
 pub const MUMBLE_IDENTITY_UPDATED: Event<MumbleIdentityUpdate> =
-    unsafe { Event::new("EV_MUMBLE_IDENTITY_UPDATED") };
+    unsafe { Event::new_global(c_ident(b"EV_MUMBLE_IDENTITY_UPDATED\0")) };
 
 /// Mumble identity.
 #[derive(Debug, Clone)]
@@ -1,15 +1,19 @@
 //! Font loading.
 
 use crate::{
-    util::{path_to_c, str_to_c},
-    AddonApi, FontApi, Revertible,
+    dispatch::Dispatch,
+    identifier::strip_namespace,
+    registry::{self, RegistrationKind},
+    util::{str_from_c, try_c_to_str, try_ident_to_c, try_path_to_c, unwrap_or_panic},
+    win::HMODULE,
+    AddonApi, Error, FontApi, Global, IntoCStr, Revertible,
 };
 use imgui::sys::{ImFont, ImFontConfig};
 use std::{
-    ffi::{c_char, c_void},
+    ffi::{c_char, c_void, CStr, CString},
     path::Path,
+    sync::{Arc, Mutex},
 };
-use windows::Win32::Foundation::HMODULE;
 
 pub type RawFontReceive = unsafe extern "C-unwind" fn(identifier: *const c_char, font: *mut ImFont);
 
@@ -48,39 +52,101 @@ pub type RawFontAddFromMemory = unsafe extern "C-unwind" fn(
 pub type RawFontResize = unsafe extern "C-unwind" fn(identifier: *const c_char, font_size: f32);
 
 /// Registers a new callback to receive the font with the given identifier.
+///
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
 pub fn get_font(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     callback: RawFontReceive,
 ) -> Revertible<impl Fn() + Send + Sync + 'static> {
-    let FontApi { get, release, .. } = AddonApi::get().font;
-    let identifier = str_to_c(identifier, "failed to convert font identifier");
+    unwrap_or_panic(try_get_font(identifier, callback))
+}
+
+/// Fallible version of [`get_font`].
+pub fn try_get_font(
+    identifier: impl IntoCStr,
+    callback: RawFontReceive,
+) -> Result<Revertible<impl Fn() + Send + Sync + 'static>, Error> {
+    let FontApi { get, release, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_fonts, "fonts")?
+        .font;
+    let identifier: CString = try_ident_to_c(identifier, "font identifier")?.into();
     unsafe { get(identifier.as_ptr(), callback) };
-    let revert = move || unsafe { release(identifier.as_ptr(), callback) };
-    revert.into()
+    Ok(track_font(identifier, callback, release))
 }
 
 /// Releases a previously registered callback for the font with the given identifier.
-pub fn release_font(identifier: impl AsRef<str>, callback: RawFontReceive) {
-    let FontApi { release, .. } = AddonApi::get().font;
-    let identifier = str_to_c(identifier, "failed to convert font identifier");
-    unsafe { release(identifier.as_ptr(), callback) }
+///
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
+pub fn release_font(identifier: impl IntoCStr, callback: RawFontReceive) {
+    unwrap_or_panic(try_release_font(identifier, callback))
+}
+
+/// Fallible version of [`release_font`].
+pub fn try_release_font(identifier: impl IntoCStr, callback: RawFontReceive) -> Result<(), Error> {
+    let FontApi { release, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_fonts, "fonts")?
+        .font;
+    let identifier = try_ident_to_c(identifier, "font identifier")?;
+    unsafe { release(identifier.as_ptr(), callback) };
+    registry::untrack(
+        RegistrationKind::Font,
+        Some(&identifier.to_string_lossy()),
+        Some(callback as usize),
+    );
+    Ok(())
+}
+
+/// Records a font callback registration.
+fn track_font(
+    identifier: CString,
+    callback: RawFontReceive,
+    release: RawFontRelease,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let name = registry::name(&identifier);
+    let revert = move || unsafe { release(identifier.as_ptr(), callback) };
+    registry::track(
+        RegistrationKind::Font,
+        name,
+        Some(callback as usize),
+        revert,
+    )
 }
 
 /// Adds a font from a file path and sends updates to the callback.
+///
+/// Panics if the identifier contains a nul byte, the path is not valid UTF-8 or fonts are not supported.
+#[track_caller]
 pub fn add_font_from_file(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     file: impl AsRef<Path>,
     font_size: f32,
     config: &mut ImFontConfig,
     callback: RawFontReceive,
 ) -> Revertible<impl Fn() + Send + Sync + 'static> {
+    unwrap_or_panic(try_add_font_from_file(
+        identifier, file, font_size, config, callback,
+    ))
+}
+
+/// Fallible version of [`add_font_from_file`].
+pub fn try_add_font_from_file(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: RawFontReceive,
+) -> Result<Revertible<impl Fn() + Send + Sync + 'static>, Error> {
     let FontApi {
         add_from_file,
         release,
         ..
-    } = AddonApi::get().font;
-    let identifier = str_to_c(identifier, "failed to convert font identifier");
-    let file = path_to_c(file, "failed to convert font file path");
+    } = AddonApi::try_get()?
+        .require(AddonApi::has_fonts, "fonts")?
+        .font;
+    let identifier: CString = try_ident_to_c(identifier, "font identifier")?.into();
+    let file = try_path_to_c(file, "font file path")?;
     unsafe {
         add_from_file(
             identifier.as_ptr(),
@@ -90,25 +156,43 @@ pub fn add_font_from_file(
             config,
         )
     };
-    let revert = move || unsafe { release(identifier.as_ptr(), callback) };
-    revert.into()
+    Ok(track_font(identifier, callback, release))
 }
 
 /// Adds a font from a resource and sends updates to the callback.
+///
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
 pub fn add_font_from_resource(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     handle: HMODULE,
     resource: u32,
     font_size: f32,
     config: &mut ImFontConfig,
     callback: RawFontReceive,
 ) -> Revertible<impl Fn() + Send + Sync + 'static> {
+    unwrap_or_panic(try_add_font_from_resource(
+        identifier, handle, resource, font_size, config, callback,
+    ))
+}
+
+/// Fallible version of [`add_font_from_resource`].
+pub fn try_add_font_from_resource(
+    identifier: impl IntoCStr,
+    handle: HMODULE,
+    resource: u32,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: RawFontReceive,
+) -> Result<Revertible<impl Fn() + Send + Sync + 'static>, Error> {
     let FontApi {
         add_from_resource,
         release,
         ..
-    } = AddonApi::get().font;
-    let identifier = str_to_c(identifier, "failed to convert font identifier");
+    } = AddonApi::try_get()?
+        .require(AddonApi::has_fonts, "fonts")?
+        .font;
+    let identifier: CString = try_ident_to_c(identifier, "font identifier")?.into();
     unsafe {
         add_from_resource(
             identifier.as_ptr(),
@@ -119,24 +203,41 @@ pub fn add_font_from_resource(
             config,
         )
     };
-    let revert = move || unsafe { release(identifier.as_ptr(), callback) };
-    revert.into()
+    Ok(track_font(identifier, callback, release))
 }
 
 /// Adds a font from memory and sends updates to the callback.
+///
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
 pub fn add_font_from_memory(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     data: impl AsRef<[u8]>,
     font_size: f32,
     config: &mut ImFontConfig,
     callback: RawFontReceive,
 ) -> Revertible<impl Fn() + Send + Sync + 'static> {
+    unwrap_or_panic(try_add_font_from_memory(
+        identifier, data, font_size, config, callback,
+    ))
+}
+
+/// Fallible version of [`add_font_from_memory`].
+pub fn try_add_font_from_memory(
+    identifier: impl IntoCStr,
+    data: impl AsRef<[u8]>,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: RawFontReceive,
+) -> Result<Revertible<impl Fn() + Send + Sync + 'static>, Error> {
     let FontApi {
         add_from_memory,
         release,
         ..
-    } = AddonApi::get().font;
-    let identifier = str_to_c(identifier, "failed to convert font identifier");
+    } = AddonApi::try_get()?
+        .require(AddonApi::has_fonts, "fonts")?
+        .font;
+    let identifier: CString = try_ident_to_c(identifier, "font identifier")?.into();
     let data = data.as_ref();
     unsafe {
         add_from_memory(
@@ -148,8 +249,7 @@ pub fn add_font_from_memory(
             config,
         )
     };
-    let revert = move || unsafe { release(identifier.as_ptr(), callback) };
-    revert.into()
+    Ok(track_font(identifier, callback, release))
 }
 
 /// Macro to wrap a font receive callback.
@@ -176,10 +276,14 @@ macro_rules! font_receive {
             identifier: *const ::std::ffi::c_char,
             font: *mut $crate::imgui::sys::ImFont,
         ) {
-            let identifier = unsafe { $crate::__macro::str_from_c(identifier) }
-                .expect("invalid identifier in font callback");
-            let font = unsafe { font.as_mut() };
-            __CALLBACK(identifier, font)
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            $crate::__macro::catch("font receive callback", &__DISABLED, || {
+                let identifier = unsafe { $crate::__macro::str_from_c(identifier) }
+                    .expect("invalid identifier in font callback");
+                let font = unsafe { font.as_mut() };
+                __CALLBACK($crate::__macro::strip_namespace(identifier), font)
+            });
         }
 
         __font_receive_wrapper
@@ -188,9 +292,251 @@ macro_rules! font_receive {
 
 pub use font_receive;
 
+/// Closure receiving a font.
+type FontReceiveFn = dyn FnMut(&str, Option<&mut ImFont>) + Send;
+
+/// Registered font receive closures.
+static FONT_FNS: Dispatch<String, FontReceiveFn> = Dispatch::new();
+
+/// Registers a new closure to receive the font with the given identifier.
+///
+/// Unlike [`get_font`] the closure can capture state.
+/// Returns a [`Revertible`] to release the closure.
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
+pub fn get_font_fn(
+    identifier: impl IntoCStr,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_get_font_fn(identifier, callback))
+}
+
+/// Fallible version of [`get_font_fn`].
+pub fn try_get_font_fn(
+    identifier: impl IntoCStr,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let FontApi { get, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_fonts, "fonts")?
+        .font;
+    let c_identifier = try_ident_to_c(identifier, "font identifier")?;
+    let identifier = try_c_to_str(&c_identifier, "font identifier")?;
+    let id = insert_font_fn(identifier, callback, |identifier| {
+        unsafe { get(c_identifier.as_ptr(), font_trampoline) };
+        track_font_trampoline(identifier);
+    });
+    Ok(font_fn_revertible(id, registry::name(&c_identifier)))
+}
+
+/// Adds a font from a file path and sends updates to the closure.
+///
+/// Unlike [`add_font_from_file`] the closure can capture state.
+/// Returns a [`Revertible`] to release the closure.
+/// Panics if the identifier contains a nul byte, the path is not valid UTF-8 or fonts are not supported.
+#[track_caller]
+pub fn add_font_from_file_fn(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_add_font_from_file_fn(
+        identifier, file, font_size, config, callback,
+    ))
+}
+
+/// Fallible version of [`add_font_from_file_fn`].
+pub fn try_add_font_from_file_fn(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let identifier = try_ident_to_c(identifier, "font identifier")?;
+    let id = insert_font_fn(
+        try_c_to_str(&identifier, "font identifier")?,
+        callback,
+        |_| {},
+    );
+    let result = try_add_font_from_file(
+        Global(&*identifier),
+        file,
+        font_size,
+        config,
+        font_trampoline,
+    );
+    finish_font_fn(id, &identifier, result.map(Revertible::leak))
+}
+
+/// Adds a font from a resource and sends updates to the closure.
+///
+/// Unlike [`add_font_from_resource`] the closure can capture state.
+/// Returns a [`Revertible`] to release the closure.
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
+pub fn add_font_from_resource_fn(
+    identifier: impl IntoCStr,
+    handle: HMODULE,
+    resource: u32,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_add_font_from_resource_fn(
+        identifier, handle, resource, font_size, config, callback,
+    ))
+}
+
+/// Fallible version of [`add_font_from_resource_fn`].
+pub fn try_add_font_from_resource_fn(
+    identifier: impl IntoCStr,
+    handle: HMODULE,
+    resource: u32,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let identifier = try_ident_to_c(identifier, "font identifier")?;
+    let id = insert_font_fn(
+        try_c_to_str(&identifier, "font identifier")?,
+        callback,
+        |_| {},
+    );
+    let result = try_add_font_from_resource(
+        Global(&*identifier),
+        handle,
+        resource,
+        font_size,
+        config,
+        font_trampoline,
+    );
+    finish_font_fn(id, &identifier, result.map(Revertible::leak))
+}
+
+/// Adds a font from memory and sends updates to the closure.
+///
+/// Unlike [`add_font_from_memory`] the closure can capture state.
+/// Returns a [`Revertible`] to release the closure.
+/// Panics if the identifier contains a nul byte or fonts are not supported.
+#[track_caller]
+pub fn add_font_from_memory_fn(
+    identifier: impl IntoCStr,
+    data: impl AsRef<[u8]>,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_add_font_from_memory_fn(
+        identifier, data, font_size, config, callback,
+    ))
+}
+
+/// Fallible version of [`add_font_from_memory_fn`].
+pub fn try_add_font_from_memory_fn(
+    identifier: impl IntoCStr,
+    data: impl AsRef<[u8]>,
+    font_size: f32,
+    config: &mut ImFontConfig,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let identifier = try_ident_to_c(identifier, "font identifier")?;
+    let id = insert_font_fn(
+        try_c_to_str(&identifier, "font identifier")?,
+        callback,
+        |_| {},
+    );
+    let result = try_add_font_from_memory(
+        Global(&*identifier),
+        data,
+        font_size,
+        config,
+        font_trampoline,
+    );
+    finish_font_fn(id, &identifier, result.map(Revertible::leak))
+}
+
+fn insert_font_fn(
+    identifier: &str,
+    callback: impl FnMut(&str, Option<&mut ImFont>) + Send + 'static,
+    register: impl FnOnce(&String),
+) -> u64 {
+    FONT_FNS.insert(
+        identifier.into(),
+        Arc::new(Mutex::new(callback)),
+        register,
+        release_font_trampoline,
+    )
+}
+
+fn font_fn_revertible(
+    id: u64,
+    name: Option<String>,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let revert = move || FONT_FNS.remove(id, release_font_trampoline);
+    registry::track_fn(RegistrationKind::Font, name, revert)
+}
+
+/// Removes the closure again if the font failed to be added.
+///
+/// The registration of the trampoline is tracked as releasing all closures for the font.
+fn finish_font_fn(
+    id: u64,
+    identifier: &CStr,
+    result: Result<(), Error>,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    match result {
+        Ok(()) => {
+            track_font_trampoline(&identifier.to_string_lossy());
+            Ok(font_fn_revertible(id, registry::name(identifier)))
+        }
+        Err(err) => {
+            FONT_FNS.remove(id, |_| {});
+            Err(err)
+        }
+    }
+}
+
+/// Records the registration of the trampoline, replacing previous records for the identifier.
+fn track_font_trampoline(identifier: &str) {
+    let callback = font_trampoline as RawFontReceive as usize;
+    registry::untrack(RegistrationKind::Font, Some(identifier), Some(callback));
+    let key = identifier.to_owned();
+    registry::track_trampoline(
+        RegistrationKind::Font,
+        Some(identifier.into()),
+        callback,
+        move || FONT_FNS.remove_all(&key, release_font_trampoline),
+    );
+}
+
+fn release_font_trampoline(identifier: &String) {
+    release_font(Global(identifier), font_trampoline)
+}
+
+unsafe extern "C-unwind" fn font_trampoline(identifier: *const c_char, font: *mut ImFont) {
+    if let Some(identifier) = str_from_c(identifier) {
+        FONT_FNS.call(identifier, "font receive callback", |callback| {
+            callback(strip_namespace(identifier), font.as_mut())
+        })
+    }
+}
+
 /// Resizes an existing font, sending the update to registered callbacks.
-pub fn resize_font(identifier: impl AsRef<str>, font_size: f32) {
-    let FontApi { resize, .. } = AddonApi::get().font;
-    let identifier = str_to_c(identifier, "failed to convert font identifier");
-    unsafe { resize(identifier.as_ptr(), font_size) }
+///
+/// Panics if the identifier contains a nul byte or font resizing is not supported.
+#[track_caller]
+pub fn resize_font(identifier: impl IntoCStr, font_size: f32) {
+    unwrap_or_panic(try_resize_font(identifier, font_size))
+}
+
+/// Fallible version of [`resize_font`].
+pub fn try_resize_font(identifier: impl IntoCStr, font_size: f32) -> Result<(), Error> {
+    let FontApi { resize, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_font_resize, "font resizing")?
+        .font;
+    let identifier = try_ident_to_c(identifier, "font identifier")?;
+    unsafe { resize(identifier.as_ptr(), font_size) };
+    Ok(())
 }
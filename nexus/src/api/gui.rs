@@ -1,7 +1,18 @@
 //! [ImGui](https://github.com/ocornut/imgui) rendering via [`imgui-rs`](crate::imgui).
 
-use crate::{util::str_to_c, AddonApi, RendererApi, Revertible, UiApi};
-use std::ffi::{c_char, c_void};
+use crate::{
+    dispatch::Dispatch,
+    registry::{self, RegistrationKind},
+    util::{try_str_to_c, unwrap_or_panic},
+    AddonApi, Error, RendererApi, Revertible, UiApi,
+};
+use imgui::Ui;
+use std::{
+    cell::Cell,
+    ffi::{c_char, c_void},
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
 
 /// ImGui version.
 // TODO: is this still correct?
@@ -39,6 +50,99 @@ pub enum RenderType {
 
 pub type RawGuiRender = extern "C-unwind" fn();
 
+thread_local! {
+    /// Whether a render callback is currently running on this thread.
+    static RENDERING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Panics if no render callback is currently running on this thread.
+///
+/// Guards against tokens kept beyond the render callback they were handed to.
+#[inline]
+pub(crate) fn assert_rendering() {
+    assert!(
+        RENDERING.with(Cell::get),
+        "ui accessed outside of a render callback"
+    );
+}
+
+/// Token proving the current thread is the render thread.
+///
+/// Handed to callbacks generated by [`render!`], render closures, deferred tasks and timers.
+/// Required to access the [`Ui`] via [`ui`](crate::ui).
+/// Elsewhere the token is available via [`RenderThread::current`] while a render callback is running.
+/// The token can not be sent to other threads.
+/// Accessing the [`Ui`] with a token kept beyond its render callback panics.
+///
+/// Texture and font functions only call into Nexus and do not require the token,
+/// drawing the received textures and fonts does via the [`Ui`].
+///
+/// # Usage
+/// ```no_run
+/// # use nexus::gui::*;
+/// fn helper(thread: RenderThread) {
+///     nexus::ui(thread).text("Hello World");
+/// }
+///
+/// let render_callback = render!(|_ui, thread| helper(thread));
+/// register_render_fn(RenderType::Render, |_ui, thread| helper(thread)).revert_on_unload();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RenderThread {
+    _marker: PhantomData<*const ()>,
+}
+
+impl RenderThread {
+    /// Creates a new token without checking the current thread.
+    ///
+    /// # Safety
+    /// Must only be called on the render thread, during a render callback invoked by Nexus.
+    #[inline]
+    pub const unsafe fn new_unchecked() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a token if a render callback is currently running on this thread.
+    #[inline]
+    pub fn current() -> Option<Self> {
+        RENDERING
+            .with(Cell::get)
+            .then(|| unsafe { Self::new_unchecked() })
+    }
+
+    /// Returns the [`Ui`] for rendering the current frame.
+    ///
+    /// Panics if called outside of a render callback.
+    #[inline]
+    #[track_caller]
+    pub fn ui(&self) -> &Ui<'static> {
+        assert_rendering();
+        unsafe { crate::globals::ui_unchecked() }
+    }
+
+    /// Runs the render callback with a new token, marking the current thread as rendering.
+    ///
+    /// A call to this is inserted by the [`render!`] macro.
+    ///
+    /// # Safety
+    /// See [`RenderThread::new_unchecked`].
+    #[doc(hidden)]
+    pub unsafe fn enter<R>(callback: impl FnOnce(Self) -> R) -> R {
+        struct Restore(bool);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                RENDERING.with(|rendering| rendering.set(self.0))
+            }
+        }
+
+        let _restore = Restore(RENDERING.with(|rendering| rendering.replace(true)));
+        callback(Self::new_unchecked())
+    }
+}
+
 pub type RawGuiAddRender =
     unsafe extern "C-unwind" fn(render_type: RenderType, render_callback: RawGuiRender);
 
@@ -75,33 +179,166 @@ pub fn register_render(
     } = AddonApi::get().renderer;
     unsafe { register(render_type, callback) };
     let revert = move || unsafe { deregister(callback) };
-    revert.into()
+    registry::track(
+        RegistrationKind::Render,
+        Some(format!("{render_type:?}")),
+        Some(callback as usize),
+        revert,
+    )
 }
 
 /// Unregisters a previously registered ImGui render callback.
+///
+/// Nexus removes the callback for all render types it was registered for.
 #[inline]
 pub fn unregister_render(callback: RawGuiRender) {
+    deregister_render(callback, None)
+}
+
+/// Unregisters a render callback, untracking registrations for the render type if passed.
+fn deregister_render(callback: RawGuiRender, render_type: Option<RenderType>) {
     let RendererApi { deregister, .. } = AddonApi::get().renderer;
-    unsafe { deregister(callback) }
+    unsafe { deregister(callback) };
+    let name = render_type.map(|render_type| format!("{render_type:?}"));
+    registry::untrack(
+        RegistrationKind::Render,
+        name.as_deref(),
+        Some(callback as usize),
+    );
+}
+
+/// Closure rendering via ImGui.
+type RenderFn = dyn FnMut(&Ui, RenderThread) + Send;
+
+/// Registered render closures.
+static RENDER_FNS: Dispatch<RenderType, RenderFn> = Dispatch::new();
+
+/// Registers a new ImGui render closure of the given [`RenderType`].
+///
+/// Unlike [`register_render`] the closure can capture state.
+/// The closure receives the [`RenderThread`] token in addition to the [`Ui`].
+/// Returns a [`Revertible`] to revert the register.
+///
+/// # Usage
+/// ```no_run
+/// # use nexus::gui::*;
+/// let mut clicks = 0;
+/// register_render_fn(RenderType::Render, move |ui, _thread| {
+///     if ui.button("Click me") {
+///         clicks += 1;
+///     }
+///     ui.text(format!("Clicked {clicks} times"));
+/// })
+/// .revert_on_unload();
+/// ```
+pub fn register_render_fn(
+    render_type: RenderType,
+    callback: impl FnMut(&Ui, RenderThread) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let id = RENDER_FNS.insert(
+        render_type,
+        Arc::new(Mutex::new(callback)),
+        |render_type| {
+            let RendererApi { register, .. } = AddonApi::get().renderer;
+            let trampoline = render_trampoline(*render_type);
+            unsafe { register(*render_type, trampoline) };
+            let render_type = *render_type;
+            registry::track_trampoline(
+                RegistrationKind::Render,
+                Some(format!("{render_type:?}")),
+                trampoline as usize,
+                move || RENDER_FNS.remove_all(&render_type, deregister_render_trampoline),
+            );
+        },
+        deregister_render_trampoline,
+    );
+    let revert = move || RENDER_FNS.remove(id, deregister_render_trampoline);
+    registry::track_fn(
+        RegistrationKind::Render,
+        Some(format!("{render_type:?}")),
+        revert,
+    )
+}
+
+fn deregister_render_trampoline(render_type: &RenderType) {
+    deregister_render(render_trampoline(*render_type), Some(*render_type))
+}
+
+/// Returns the trampoline dispatching to render closures of the given type.
+fn render_trampoline(render_type: RenderType) -> RawGuiRender {
+    extern "C-unwind" fn pre_render() {
+        dispatch_render(RenderType::PreRender)
+    }
+
+    extern "C-unwind" fn render() {
+        dispatch_render(RenderType::Render)
+    }
+
+    extern "C-unwind" fn post_render() {
+        dispatch_render(RenderType::PostRender)
+    }
+
+    extern "C-unwind" fn options_render() {
+        dispatch_render(RenderType::OptionsRender)
+    }
+
+    match render_type {
+        RenderType::PreRender => pre_render,
+        RenderType::Render => render,
+        RenderType::PostRender => post_render,
+        RenderType::OptionsRender => options_render,
+    }
+}
+
+fn dispatch_render(render_type: RenderType) {
+    unsafe {
+        RenderThread::enter(|thread| {
+            RENDER_FNS.call(&render_type, "render callback", |callback| {
+                callback(thread.ui(), thread)
+            })
+        })
+    }
 }
 
 /// Macro to wrap an ImGui render callback.
 ///
 /// Generates a [`RawGuiRender`] wrapper around the passed callback.
+/// Closures taking a second parameter additionally receive the [`RenderThread`] token.
 ///
 /// # Usage
 /// ```no_run
 /// # use nexus::gui::*;
 /// let render_callback: RawGuiRender = render!(|ui| ui.text("Hello World"));
+///
+/// let render_callback: RawGuiRender = render!(|ui, thread| {
+///     ui.text("Hello World");
+///     let _ = thread;
+/// });
 /// ```
 #[macro_export]
 macro_rules! render {
+    ( | $ui:pat_param , $thread:pat_param | $body:expr $(,)? ) => {{
+        const __CALLBACK: fn(&$crate::imgui::Ui, $crate::gui::RenderThread) = |$ui, $thread| $body;
+
+        extern "C-unwind" fn __render_callback_wrapper() {
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            $crate::__macro::catch("render callback", &__DISABLED, || unsafe {
+                $crate::gui::RenderThread::enter(|thread| __CALLBACK(thread.ui(), thread))
+            });
+        }
+
+        __render_callback_wrapper
+    }};
     ( $callback:expr $(,)? ) => {{
         const __CALLBACK: fn(&$crate::imgui::Ui) = $callback;
 
         extern "C-unwind" fn __render_callback_wrapper() {
-            let ui = unsafe { $crate::ui() };
-            __CALLBACK(&ui)
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            $crate::__macro::catch("render callback", &__DISABLED, || unsafe {
+                $crate::gui::RenderThread::enter(|thread| __CALLBACK(thread.ui()))
+            });
         }
 
         __render_callback_wrapper
@@ -111,21 +348,46 @@ macro_rules! render {
 pub use render;
 
 /// Registers a window name to get its bool toggled when escape is pressed.
+///
+/// Panics if the window name contains a nul byte or closing windows on escape is not supported.
+#[track_caller]
 pub fn register_close_on_escape(window_name: impl AsRef<str>, opened: &mut bool) {
+    unwrap_or_panic(try_register_close_on_escape(window_name, opened))
+}
+
+/// Fallible version of [`register_close_on_escape`].
+pub fn try_register_close_on_escape(
+    window_name: impl AsRef<str>,
+    opened: &mut bool,
+) -> Result<(), Error> {
     let UiApi {
         register_close_on_escape,
         ..
-    } = AddonApi::get().ui;
-    let window_name = str_to_c(window_name, "failed to convert window name");
-    unsafe { register_close_on_escape(window_name.as_ptr(), opened) }
+    } = AddonApi::try_get()?
+        .require(AddonApi::has_close_on_escape, "closing windows on escape")?
+        .ui;
+    let window_name = try_str_to_c(window_name, "window name")?;
+    unsafe { register_close_on_escape(window_name.as_ptr(), opened) };
+    Ok(())
 }
 
-///  Deregisters a window name to listen to on escape.
+/// Deregisters a window name to listen to on escape.
+///
+/// Panics if the window name contains a nul byte or closing windows on escape is not supported.
+#[track_caller]
 pub fn deregister_close_on_escape(window_name: impl AsRef<str>) {
+    unwrap_or_panic(try_deregister_close_on_escape(window_name))
+}
+
+/// Fallible version of [`deregister_close_on_escape`].
+pub fn try_deregister_close_on_escape(window_name: impl AsRef<str>) -> Result<(), Error> {
     let UiApi {
         deregister_close_on_escape,
         ..
-    } = AddonApi::get().ui;
-    let window_name = str_to_c(window_name, "failed to convert window name");
-    unsafe { deregister_close_on_escape(window_name.as_ptr()) }
+    } = AddonApi::try_get()?
+        .require(AddonApi::has_close_on_escape, "closing windows on escape")?
+        .ui;
+    let window_name = try_str_to_c(window_name, "window name")?;
+    unsafe { deregister_close_on_escape(window_name.as_ptr()) };
+    Ok(())
 }
@@ -1,10 +1,20 @@
 //! Addon keybinds.
 
-use crate::{revertible::Revertible, util::str_to_c, AddonApi, InputBindsApi};
-use std::ffi::c_char;
+use crate::{
+    dispatch::Dispatch,
+    identifier::strip_namespace,
+    registry::{self, RegistrationKind},
+    revertible::Revertible,
+    util::{str_from_c, try_c_to_str, try_ident_to_c, try_to_c, unwrap_or_panic},
+    AddonApi, Error, Global, InputBindsApi, IntoCStr,
+};
+use std::{
+    ffi::{c_char, CString},
+    sync::{Arc, Mutex},
+};
 
 /// A keybind.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Keybind {
@@ -74,15 +84,27 @@ pub type RawKeybindRegisterWithStructOld = unsafe extern "C-unwind" fn(
 );
 
 /// Triggers a previously registered keybind programmatically.
-pub fn invoke_keybind(identifier: impl AsRef<str>, is_release: bool) {
-    let InputBindsApi { invoke, .. } = AddonApi::get().input_binds;
-    let identifier = str_to_c(identifier, "failed to convert keybind identifier");
-    unsafe { invoke(identifier.as_ptr(), is_release) }
+///
+/// Panics if the identifier contains a nul byte or invoking keybinds is not supported.
+#[track_caller]
+pub fn invoke_keybind(identifier: impl IntoCStr, is_release: bool) {
+    unwrap_or_panic(try_invoke_keybind(identifier, is_release))
+}
+
+/// Fallible version of [`invoke_keybind`].
+pub fn try_invoke_keybind(identifier: impl IntoCStr, is_release: bool) -> Result<(), Error> {
+    let InputBindsApi { invoke, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_keybind_invoke, "invoking keybinds")?
+        .input_binds;
+    let identifier = try_ident_to_c(identifier, "keybind identifier")?;
+    unsafe { invoke(identifier.as_ptr(), is_release) };
+    Ok(())
 }
 
 /// Registers a new keybind using a keybind string like `"ALT+SHIFT+T"`.
 ///
 /// Returns a [`Revertible`] to revert the register.
+/// Panics if the identifier or keybind string contain a nul byte.
 ///
 /// # Usage
 /// ```no_run
@@ -97,26 +119,47 @@ pub fn invoke_keybind(identifier: impl AsRef<str>, is_release: bool) {
 /// register_keybind_with_string("MY_KEYBIND", keybind_handler, "ALT+SHIFT+X")
 ///     .revert_on_unload();
 /// ```
+#[track_caller]
 pub fn register_keybind_with_string(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     handler: RawKeybindHandler,
-    keybind: impl AsRef<str>,
+    keybind: impl IntoCStr,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_register_keybind_with_string(
+        identifier, handler, keybind,
+    ))
+}
+
+/// Fallible version of [`register_keybind_with_string`].
+pub fn try_register_keybind_with_string(
+    identifier: impl IntoCStr,
+    handler: RawKeybindHandler,
+    keybind: impl IntoCStr,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
     let InputBindsApi {
         register_with_string,
         deregister,
         ..
-    } = AddonApi::get().input_binds;
-    let identifier = str_to_c(identifier, "failed to convert keybind identifier");
-    let keybind = str_to_c(keybind, "failed to convert keybind string");
+    } = AddonApi::try_get()?.input_binds;
+    let identifier: CString = try_ident_to_c(identifier, "keybind identifier")?.into();
+    let keybind = try_to_c(keybind, "keybind string")?;
     unsafe { register_with_string(identifier.as_ptr(), handler, keybind.as_ptr()) };
+    #[cfg(feature = "debug")]
+    crate::debug::record_keybind(&identifier, keybind.to_string_lossy().into_owned());
+    let name = registry::name(&identifier);
     let revert = move || unsafe { deregister(identifier.as_ptr()) };
-    revert.into()
+    Ok(registry::track(
+        RegistrationKind::Keybind,
+        name,
+        Some(handler as usize),
+        revert,
+    ))
 }
 
 /// Registers a new keybind using a [`Keybind`] struct.
 ///
 /// Returns a [`Revertible`] to revert the register.
+/// Panics if the identifier contains a nul byte.
 ///
 /// # Usage
 /// ```no_run
@@ -137,27 +180,204 @@ pub fn register_keybind_with_string(
 /// register_keybind_with_struct("MY_KEYBIND", keybind_handler, keybind)
 ///     .revert_on_unload();
 /// ```
+#[track_caller]
 pub fn register_keybind_with_struct(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     handler: RawKeybindHandler,
     keybind: Keybind,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_register_keybind_with_struct(
+        identifier, handler, keybind,
+    ))
+}
+
+/// Fallible version of [`register_keybind_with_struct`].
+pub fn try_register_keybind_with_struct(
+    identifier: impl IntoCStr,
+    handler: RawKeybindHandler,
+    keybind: Keybind,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
     let InputBindsApi {
         register_with_struct,
         deregister,
         ..
-    } = AddonApi::get().input_binds;
-    let identifier = str_to_c(identifier, "failed to convert keybind identifier");
+    } = AddonApi::try_get()?.input_binds;
+    let identifier: CString = try_ident_to_c(identifier, "keybind identifier")?.into();
+    #[cfg(feature = "debug")]
+    crate::debug::record_keybind_struct(&identifier, &keybind);
     unsafe { register_with_struct(identifier.as_ptr(), handler, keybind) };
+    let name = registry::name(&identifier);
     let revert = move || unsafe { deregister(identifier.as_ptr()) };
-    revert.into()
+    Ok(registry::track(
+        RegistrationKind::Keybind,
+        name,
+        Some(handler as usize),
+        revert,
+    ))
 }
 
 /// Unregisters a previously registered keybind.
-pub fn unregister_keybind(identifier: impl AsRef<str>) {
-    let InputBindsApi { deregister, .. } = AddonApi::get().input_binds;
-    let identifier = str_to_c(identifier, "failed to convert keybind identifier");
-    unsafe { deregister(identifier.as_ptr()) }
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn unregister_keybind(identifier: impl IntoCStr) {
+    unwrap_or_panic(try_unregister_keybind(identifier))
+}
+
+/// Fallible version of [`unregister_keybind`].
+pub fn try_unregister_keybind(identifier: impl IntoCStr) -> Result<(), Error> {
+    let InputBindsApi { deregister, .. } = AddonApi::try_get()?.input_binds;
+    let identifier = try_ident_to_c(identifier, "keybind identifier")?;
+    unsafe { deregister(identifier.as_ptr()) };
+    registry::untrack(
+        RegistrationKind::Keybind,
+        Some(&identifier.to_string_lossy()),
+        None,
+    );
+    Ok(())
+}
+
+/// Closure handling a keybind.
+type KeybindHandlerFn = dyn FnMut(&str, bool) + Send;
+
+/// Registered keybind closures.
+static KEYBIND_FNS: Dispatch<String, KeybindHandlerFn> = Dispatch::new();
+
+/// Registers a new keybind closure using a keybind string like `"ALT+SHIFT+T"`.
+///
+/// Unlike [`register_keybind_with_string`] the closure can capture state.
+/// Registering the same identifier again replaces the previous closure.
+/// Returns a [`Revertible`] to revert the register.
+/// Panics if the identifier or keybind string contain a nul byte.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::keybind::register_keybind_with_string_fn;
+/// let mut presses = 0;
+/// register_keybind_with_string_fn("MY_KEYBIND", move |_id, is_release| {
+///     if !is_release {
+///         presses += 1;
+///     }
+/// }, "ALT+SHIFT+X")
+/// .revert_on_unload();
+/// ```
+#[track_caller]
+pub fn register_keybind_with_string_fn(
+    identifier: impl IntoCStr,
+    handler: impl FnMut(&str, bool) + Send + 'static,
+    keybind: impl IntoCStr,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_register_keybind_with_string_fn(
+        identifier, handler, keybind,
+    ))
+}
+
+/// Fallible version of [`register_keybind_with_string_fn`].
+pub fn try_register_keybind_with_string_fn(
+    identifier: impl IntoCStr,
+    handler: impl FnMut(&str, bool) + Send + 'static,
+    keybind: impl IntoCStr,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let c_identifier = try_ident_to_c(identifier, "keybind identifier")?;
+    let identifier = try_c_to_str(&c_identifier, "keybind identifier")?;
+    let keybind = try_to_c(keybind, "keybind string")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_keybind(&c_identifier, keybind.to_string_lossy().into_owned());
+    let InputBindsApi {
+        register_with_string,
+        ..
+    } = AddonApi::try_get()?.input_binds;
+    let id = KEYBIND_FNS.replace(
+        identifier.into(),
+        Arc::new(Mutex::new(handler)),
+        |identifier| {
+            unsafe {
+                register_with_string(c_identifier.as_ptr(), keybind_trampoline, keybind.as_ptr())
+            };
+            track_keybind_trampoline(identifier);
+        },
+        deregister_keybind_trampoline,
+    );
+    registry::untrack_fn(RegistrationKind::Keybind, identifier);
+    let revert = move || KEYBIND_FNS.remove(id, deregister_keybind_trampoline);
+    Ok(registry::track_fn(
+        RegistrationKind::Keybind,
+        Some(identifier.into()),
+        revert,
+    ))
+}
+
+/// Registers a new keybind closure using a [`Keybind`] struct.
+///
+/// Unlike [`register_keybind_with_struct`] the closure can capture state.
+/// Registering the same identifier again replaces the previous closure.
+/// Returns a [`Revertible`] to revert the register.
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn register_keybind_with_struct_fn(
+    identifier: impl IntoCStr,
+    handler: impl FnMut(&str, bool) + Send + 'static,
+    keybind: Keybind,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_register_keybind_with_struct_fn(
+        identifier, handler, keybind,
+    ))
+}
+
+/// Fallible version of [`register_keybind_with_struct_fn`].
+pub fn try_register_keybind_with_struct_fn(
+    identifier: impl IntoCStr,
+    handler: impl FnMut(&str, bool) + Send + 'static,
+    keybind: Keybind,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let c_identifier = try_ident_to_c(identifier, "keybind identifier")?;
+    let identifier = try_c_to_str(&c_identifier, "keybind identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_keybind_struct(&c_identifier, &keybind);
+    let InputBindsApi {
+        register_with_struct,
+        ..
+    } = AddonApi::try_get()?.input_binds;
+    let id = KEYBIND_FNS.replace(
+        identifier.into(),
+        Arc::new(Mutex::new(handler)),
+        |identifier| {
+            unsafe { register_with_struct(c_identifier.as_ptr(), keybind_trampoline, keybind) };
+            track_keybind_trampoline(identifier);
+        },
+        deregister_keybind_trampoline,
+    );
+    registry::untrack_fn(RegistrationKind::Keybind, identifier);
+    let revert = move || KEYBIND_FNS.remove(id, deregister_keybind_trampoline);
+    Ok(registry::track_fn(
+        RegistrationKind::Keybind,
+        Some(identifier.into()),
+        revert,
+    ))
+}
+
+/// Records the registration of the trampoline, replacing previous registrations of the identifier.
+fn track_keybind_trampoline(identifier: &String) {
+    registry::untrack(RegistrationKind::Keybind, Some(identifier), None);
+    let key = identifier.clone();
+    registry::track_trampoline(
+        RegistrationKind::Keybind,
+        Some(identifier.clone()),
+        keybind_trampoline as RawKeybindHandler as usize,
+        move || KEYBIND_FNS.remove_all(&key, deregister_keybind_trampoline),
+    );
+}
+
+fn deregister_keybind_trampoline(identifier: &String) {
+    unregister_keybind(Global(identifier))
+}
+
+extern "C-unwind" fn keybind_trampoline(identifier: *const c_char, is_release: bool) {
+    if let Some(identifier) = unsafe { str_from_c(identifier) } {
+        KEYBIND_FNS.call(identifier, "keybind handler", |handler| {
+            handler(strip_namespace(identifier), is_release)
+        })
+    }
 }
 
 /// Macro to wrap a keybind handler callback.
@@ -184,9 +404,13 @@ macro_rules! keybind_handler {
             identifier: *const ::std::ffi::c_char,
             is_release: ::std::primitive::bool,
         ) {
-            let identifier = unsafe { $crate::__macro::str_from_c(identifier) }
-                .expect("invalid identifier in keybind callback");
-            __CALLBACK(identifier, is_release)
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            $crate::__macro::catch("keybind handler", &__DISABLED, || {
+                let identifier = unsafe { $crate::__macro::str_from_c(identifier) }
+                    .expect("invalid identifier in keybind callback");
+                __CALLBACK($crate::__macro::strip_namespace(identifier), is_release)
+            });
         }
 
         __keybind_callback_wrapper
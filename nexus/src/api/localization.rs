@@ -1,8 +1,8 @@
 //! Localization of strings.
 
 use crate::{
-    util::{str_to_c, string_from_c},
-    AddonApi, LocalizationApi,
+    util::{string_from_c, try_ident_to_c, try_to_c, unwrap_or_panic},
+    AddonApi, Error, IntoCStr, LocalizationApi,
 };
 use std::ffi::c_char;
 
@@ -22,42 +22,73 @@ pub type RawLocalizationSet = unsafe extern "C-unwind" fn(
 
 /// Attempts to translate the identifier into the current active language.
 /// Returns the same identifier if not available.
+///
+/// Panics if the identifier contains a nul byte.
 #[inline]
-pub fn translate(identifier: impl AsRef<str>) -> Option<String> {
-    let LocalizationApi { translate, .. } = AddonApi::get().localization;
-    let identifier = str_to_c(identifier, "failed to convert translation identifier");
-    unsafe { string_from_c(translate(identifier.as_ptr())) }
+#[track_caller]
+pub fn translate(identifier: impl IntoCStr) -> Option<String> {
+    unwrap_or_panic(try_translate(identifier))
+}
+
+/// Fallible version of [`translate`].
+#[inline]
+pub fn try_translate(identifier: impl IntoCStr) -> Result<Option<String>, Error> {
+    let LocalizationApi { translate, .. } = AddonApi::try_get()?.localization;
+    let identifier = try_ident_to_c(identifier, "translation identifier")?;
+    Ok(unsafe { string_from_c(translate(identifier.as_ptr())) })
 }
 
 /// Attempts to translate the identifier into the given language.
 /// Returns the same identifier if not available.
+///
+/// Panics if an identifier contains a nul byte.
 #[inline]
+#[track_caller]
 pub fn translate_to(
-    identifier: impl AsRef<str>,
-    language_identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
+    language_identifier: impl IntoCStr,
 ) -> Option<String> {
-    let LocalizationApi { translate_to, .. } = AddonApi::get().localization;
-    let identifier = str_to_c(identifier, "failed to convert translation identifier");
-    let language = str_to_c(
-        language_identifier,
-        "failed to convert translation language identifier",
-    );
-    unsafe { string_from_c(translate_to(identifier.as_ptr(), language.as_ptr())) }
+    unwrap_or_panic(try_translate_to(identifier, language_identifier))
+}
+
+/// Fallible version of [`translate_to`].
+#[inline]
+pub fn try_translate_to(
+    identifier: impl IntoCStr,
+    language_identifier: impl IntoCStr,
+) -> Result<Option<String>, Error> {
+    let LocalizationApi { translate_to, .. } = AddonApi::try_get()?.localization;
+    let identifier = try_ident_to_c(identifier, "translation identifier")?;
+    let language = try_to_c(language_identifier, "translation language identifier")?;
+    Ok(unsafe { string_from_c(translate_to(identifier.as_ptr(), language.as_ptr())) })
 }
 
 /// Attempts to set a translated string for the given identifier and language at runtime.
+///
+/// Panics if an argument contains a nul byte or setting translations is not supported.
 #[inline]
+#[track_caller]
 pub fn set_translation(
-    identifier: impl AsRef<str>,
-    language_identifier: impl AsRef<str>,
-    string: impl AsRef<str>,
+    identifier: impl IntoCStr,
+    language_identifier: impl IntoCStr,
+    string: impl IntoCStr,
 ) {
-    let LocalizationApi { set, .. } = AddonApi::get().localization;
-    let identifier = str_to_c(identifier, "failed to convert translation identifier");
-    let language = str_to_c(
-        language_identifier,
-        "failed to convert translation language identifier",
-    );
-    let string = str_to_c(string, "failed to convert translation string");
-    unsafe { set(identifier.as_ptr(), language.as_ptr(), string.as_ptr()) }
+    unwrap_or_panic(try_set_translation(identifier, language_identifier, string))
+}
+
+/// Fallible version of [`set_translation`].
+#[inline]
+pub fn try_set_translation(
+    identifier: impl IntoCStr,
+    language_identifier: impl IntoCStr,
+    string: impl IntoCStr,
+) -> Result<(), Error> {
+    let LocalizationApi { set, .. } = AddonApi::try_get()?
+        .require(AddonApi::has_set_translation, "setting translations")?
+        .localization;
+    let identifier = try_ident_to_c(identifier, "translation identifier")?;
+    let language = try_to_c(language_identifier, "translation language identifier")?;
+    let string = try_to_c(string, "translation string")?;
+    unsafe { set(identifier.as_ptr(), language.as_ptr(), string.as_ptr()) };
+    Ok(())
 }
@@ -0,0 +1,203 @@
+use super::{
+    sink::{self, Entry},
+    try_log, Color, LogLevel,
+};
+use crate::Error;
+use std::sync::{PoisonError, RwLock};
+
+/// Channels of log records by target.
+static CHANNELS: RwLock<Channels> = RwLock::new(Channels::new());
+
+struct Channels {
+    default: Option<LogChannel>,
+    targets: Vec<(String, LogChannel)>,
+}
+
+impl Channels {
+    const fn new() -> Self {
+        Self {
+            default: None,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Finds the channel for the target, preferring the longest matching module path.
+    fn find(&self, target: &str) -> Option<&LogChannel> {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| is_module_prefix(prefix, target))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, channel)| channel)
+            .or(self.default.as_ref())
+    }
+}
+
+/// Checks whether the prefix is the target itself or one of its parent modules.
+fn is_module_prefix(prefix: &str, target: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Log channel records of the [`log`](https://docs.rs/log) and tracing integrations are sent to.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::log::{map_log_target, set_default_log_channel, Color, LogChannel, LogLevel};
+///
+/// set_default_log_channel(LogChannel::addon().with_default_colors());
+/// map_log_target(
+///     "my_addon::combat",
+///     LogChannel::new("MyAddon/Combat").with_color(LogLevel::Debug, Color::BLUE),
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogChannel {
+    name: Option<String>,
+    colors: Vec<(LogLevel, Color)>,
+}
+
+impl LogChannel {
+    /// Creates a new channel with the given name.
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            colors: Vec::new(),
+        }
+    }
+
+    /// Creates a new channel using the default channel name, usually the addon name.
+    #[inline]
+    pub fn addon() -> Self {
+        Self::default()
+    }
+
+    /// Returns the name of the channel, if set.
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Colors messages of the given level.
+    ///
+    /// Color markup in messages is kept, so parts of a [`LogMessage`](super::LogMessage) keep their own colors.
+    #[inline]
+    pub fn with_color(mut self, level: LogLevel, color: Color) -> Self {
+        self.colors.retain(|(other, _)| *other != level);
+        self.colors.push((level, color));
+        self
+    }
+
+    /// Colors critical messages red, warnings orange and debug as well as trace messages gray.
+    #[inline]
+    pub fn with_default_colors(self) -> Self {
+        self.with_color(LogLevel::Critical, Color::RED)
+            .with_color(LogLevel::Warning, Color::ORANGE)
+            .with_color(LogLevel::Debug, Color::GRAY)
+            .with_color(LogLevel::Trace, Color::GRAY)
+    }
+
+    /// Returns the color of messages of the given level.
+    #[inline]
+    pub fn color(&self, level: LogLevel) -> Option<Color> {
+        self.colors
+            .iter()
+            .find(|(other, _)| *other == level)
+            .map(|(_, color)| *color)
+    }
+}
+
+/// Sends records of the target and its submodules to the given channel.
+///
+/// Targets default to the module path of the record, for example `my_addon::combat`.
+/// The longest matching target is used.
+#[inline]
+pub fn map_log_target(target: impl Into<String>, channel: LogChannel) {
+    let target = target.into();
+    let mut channels = CHANNELS.write().unwrap_or_else(PoisonError::into_inner);
+    channels.targets.retain(|(other, _)| *other != target);
+    channels.targets.push((target, channel));
+}
+
+/// Removes the channel mapped to the target.
+#[inline]
+pub fn unmap_log_target(target: impl AsRef<str>) {
+    CHANNELS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .targets
+        .retain(|(other, _)| other != target.as_ref());
+}
+
+/// Sets the channel for records of targets without mapped channel.
+///
+/// Defaults to [`LogChannel::addon`] without colors.
+#[inline]
+pub fn set_default_log_channel(channel: LogChannel) {
+    CHANNELS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .default = Some(channel);
+}
+
+/// Resets all channels.
+pub(crate) fn reset_channels() {
+    *CHANNELS.write().unwrap_or_else(PoisonError::into_inner) = Channels::new();
+}
+
+/// Logs a message of the target to its mapped channel.
+///
+/// Falls back to the passed default channel name.
+/// Messages without default channel are only kept if asynchronous logging buffers them until initialization.
+pub(crate) fn try_log_target(
+    level: LogLevel,
+    target: &str,
+    default_channel: Option<&str>,
+    message: String,
+) -> Result<(), Error> {
+    if !sink::is_async_logging() {
+        return match default_channel {
+            Some(channel) => try_log_target_now(level, target, channel, message),
+            None => Ok(()),
+        };
+    }
+
+    let entry = Entry {
+        level,
+        target: target.into(),
+        channel: default_channel.map(Into::into),
+        message,
+    };
+    match sink::push(entry) {
+        Ok(()) => Ok(()),
+        Err(Entry {
+            channel: Some(channel),
+            message,
+            ..
+        }) => try_log_target_now(level, target, &channel, message),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Logs a message of the target to its mapped channel on the current thread.
+pub(crate) fn try_log_target_now(
+    level: LogLevel,
+    target: &str,
+    default_channel: &str,
+    message: String,
+) -> Result<(), Error> {
+    let (channel_name, color) = {
+        let channels = CHANNELS.read().unwrap_or_else(PoisonError::into_inner);
+        match channels.find(target) {
+            Some(channel) => (channel.name.clone(), channel.color(level)),
+            None => (None, None),
+        }
+    };
+    let channel_name = channel_name.as_deref().unwrap_or(default_channel);
+    match color {
+        // markup in the message is kept, user content is escaped when building a log message
+        Some(color) => try_log(level, channel_name, format!("<c={color}>{message}</c>")),
+        None => try_log(level, channel_name, message),
+    }
+}
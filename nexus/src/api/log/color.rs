@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// Color for log message markup.
+///
+/// Displayed as `#RRGGBB`, as used in `<c=#RRGGBB>text</c>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const RED: Self = Self::hex(0xFF4D4D);
+    pub const ORANGE: Self = Self::hex(0xFFA040);
+    pub const YELLOW: Self = Self::hex(0xFFE060);
+    pub const GREEN: Self = Self::hex(0x60D060);
+    pub const BLUE: Self = Self::hex(0x60A0FF);
+    pub const GRAY: Self = Self::hex(0xA0A0A0);
+    pub const WHITE: Self = Self::hex(0xFFFFFF);
+
+    /// Creates a new color from its components.
+    #[inline]
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a new color from a hex value in the form `0xRRGGBB`.
+    #[inline]
+    pub const fn hex(hex: u32) -> Self {
+        Self::rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
@@ -0,0 +1,174 @@
+use super::Color;
+use std::{borrow::Cow, fmt};
+
+/// Zero width space used to break up markup in user content.
+const BREAK: char = '\u{200B}';
+
+/// Log message with color markup.
+///
+/// Text added to the message is escaped, so it can not break the markup.
+/// Usable with [`log`](super::log), [`send_alert`](crate::alert::send_alert) and via [`Display`](fmt::Display) in formatting macros.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::log::{log, Color, LogLevel, LogMessage};
+///
+/// let name = "</c> Player";
+/// let message = LogMessage::new()
+///     .text("Hit ")
+///     .colored(Color::RED, name)
+///     .text(" for 1234 damage");
+/// log(LogLevel::Info, "My Addon", &message);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LogMessage {
+    markup: String,
+}
+
+impl LogMessage {
+    /// Creates a new empty message.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            markup: String::new(),
+        }
+    }
+
+    /// Appends uncolored text.
+    #[inline]
+    pub fn text(mut self, text: impl AsRef<str>) -> Self {
+        self.push_text(text);
+        self
+    }
+
+    /// Appends colored text.
+    #[inline]
+    pub fn colored(mut self, color: Color, text: impl AsRef<str>) -> Self {
+        self.push_colored(color, text);
+        self
+    }
+
+    /// Appends uncolored text in place.
+    #[inline]
+    pub fn push_text(&mut self, text: impl AsRef<str>) {
+        self.markup.push_str(&escape(text.as_ref()))
+    }
+
+    /// Appends colored text in place.
+    #[inline]
+    pub fn push_colored(&mut self, color: Color, text: impl AsRef<str>) {
+        self.markup.push_str(&format!("<c={color}>"));
+        self.push_text(text);
+        self.markup.push_str("</c>");
+    }
+
+    /// Checks whether the message is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.markup.is_empty()
+    }
+
+    /// Returns the message with markup.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.markup
+    }
+
+    /// Turns the message into a string with markup.
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.markup
+    }
+}
+
+impl AsRef<str> for LogMessage {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<LogMessage> for String {
+    #[inline]
+    fn from(message: LogMessage) -> Self {
+        message.into_string()
+    }
+}
+
+impl fmt::Display for LogMessage {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Escapes color markup in the text.
+///
+/// Nexus has no escape sequences, instead a zero width space is inserted after `<` starting a tag.
+pub fn escape(text: &str) -> Cow<'_, str> {
+    let is_tag = |rest: &str| {
+        let rest = rest.as_bytes();
+        let starts_with =
+            |tag: &[u8]| rest.len() >= tag.len() && rest[..tag.len()].eq_ignore_ascii_case(tag);
+        starts_with(b"c=") || starts_with(b"/c>")
+    };
+    if !text
+        .match_indices('<')
+        .any(|(index, _)| is_tag(&text[index + 1..]))
+    {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len() + 3);
+    for (index, char) in text.char_indices() {
+        result.push(char);
+        if char == '<' && is_tag(&text[index + 1..]) {
+            result.push(BREAK);
+        }
+    }
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_plain() {
+        assert!(matches!(escape("a < b"), Cow::Borrowed("a < b")));
+        assert!(matches!(escape("<b>c</b> <"), Cow::Borrowed(_)));
+        assert!(matches!(escape(""), Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn escape_markup() {
+        assert_eq!(escape("<c=#FFFFFF>"), "<\u{200B}c=#FFFFFF>");
+        assert_eq!(escape("</c>"), "<\u{200B}/c>");
+        assert_eq!(escape("<C=red></C>"), "<\u{200B}C=red><\u{200B}/C>");
+        assert_eq!(escape("ü<c=</c"), "ü<\u{200B}c=</c");
+    }
+
+    #[test]
+    fn message() {
+        let message = LogMessage::new()
+            .text("Hit ")
+            .colored(Color::RED, "</c> <C=#fff>x")
+            .text(" a<b ü <");
+        assert_eq!(
+            message.as_str(),
+            "Hit <c=#FF4D4D><\u{200B}/c> <\u{200B}C=#fff>x</c> a<b ü <"
+        );
+        assert_eq!(message.to_string(), message.as_str());
+        assert_eq!(String::from(message.clone()), message.into_string());
+    }
+
+    #[test]
+    fn message_push() {
+        let mut message = LogMessage::new();
+        assert!(message.is_empty());
+        message.push_text("<c=");
+        message.push_colored(Color::rgb(1, 2, 3), "text");
+        assert!(!message.is_empty());
+        assert_eq!(message.as_ref(), "<\u{200B}c=<c=#010203>text</c>");
+    }
+}
@@ -0,0 +1,171 @@
+//! Logging.
+
+mod color;
+mod message;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod channel;
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod sink;
+
+pub use self::{color::*, message::*};
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use self::{channel::*, sink::*};
+
+use crate::{
+    util::{try_str_to_c, unwrap_or_panic},
+    AddonApi, Error,
+};
+use std::ffi::c_char;
+
+#[cfg(feature = "log_filter")]
+use std::sync::{Mutex, PoisonError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(C)]
+pub enum LogLevel {
+    Off = 0,
+    Critical = 1,
+    Warning = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+    All,
+}
+
+/// Previous log function.
+pub type RawLogOld = unsafe extern "C-unwind" fn(level: LogLevel, message: *const c_char);
+
+pub type RawLog =
+    unsafe extern "C-unwind" fn(level: LogLevel, channel: *const c_char, message: *const c_char);
+
+/// Logs a message to the given channel.
+///
+/// Supports custom coloring for addon window messages, for example `<c=#FF0000>this text is red</c>`.
+/// See [`LogMessage`] for building colored messages.
+/// Panics if the channel or message contain a nul byte.
+#[inline]
+#[track_caller]
+pub fn log(level: LogLevel, channel_name: impl AsRef<str>, message: impl AsRef<str>) {
+    unwrap_or_panic(try_log(level, channel_name, message))
+}
+
+/// Attempts to log a message to the given channel.
+///
+/// Fallible version of [`log`].
+#[inline]
+pub fn try_log(
+    level: LogLevel,
+    channel_name: impl AsRef<str>,
+    message: impl AsRef<str>,
+) -> Result<(), Error> {
+    let AddonApi { log, .. } = AddonApi::try_get()?;
+    crate::crash::record_log(level, channel_name.as_ref(), message.as_ref());
+    let channel = try_str_to_c(channel_name, "log channel")?;
+    let message = try_str_to_c(message, "log message")?;
+    unsafe { log(level, channel.as_ptr(), message.as_ptr()) };
+    Ok(())
+}
+
+/// Name of the file in the addon directory the log filter is persisted to.
+#[cfg(feature = "log_filter")]
+pub const LOG_FILTER_FILE: &str = "log_filter.txt";
+
+/// Returns the current log filter directives.
+///
+/// An empty string allows everything.
+#[cfg(feature = "log_filter")]
+#[inline]
+pub fn log_filter() -> String {
+    crate::logger::filter::directives()
+}
+
+/// Replaces the log filter at runtime.
+///
+/// Uses the syntax of the `log_filter` field in the [`export`](crate::export) macro, for example `info,my_addon::combat=debug`.
+/// The directives are persisted to [`LOG_FILTER_FILE`] in the addon directory and restored on the next load.
+/// Panics if the directives are invalid.
+#[cfg(feature = "log_filter")]
+#[inline]
+#[track_caller]
+pub fn set_log_filter(directives: impl AsRef<str>) {
+    unwrap_or_panic(try_set_log_filter(directives))
+}
+
+/// Fallible version of [`set_log_filter`].
+#[cfg(feature = "log_filter")]
+#[inline]
+pub fn try_set_log_filter(directives: impl AsRef<str>) -> Result<(), Error> {
+    crate::logger::filter::set(directives.as_ref())
+}
+
+/// Restores the log filter passed to the [`export`](crate::export) macro and removes the persisted one.
+#[cfg(feature = "log_filter")]
+#[inline]
+pub fn reset_log_filter() {
+    crate::logger::filter::restore_default()
+}
+
+/// Log filter edited in [`render_log_filter_options`].
+#[cfg(feature = "log_filter")]
+static FILTER_EDIT: Mutex<Option<FilterEdit>> = Mutex::new(None);
+
+#[cfg(feature = "log_filter")]
+struct FilterEdit {
+    directives: String,
+    error: Option<String>,
+}
+
+/// Renders a widget to edit the log filter, for example in the addon options.
+#[cfg(feature = "log_filter")]
+pub fn render_log_filter_options(ui: &imgui::Ui) {
+    let mut guard = FILTER_EDIT.lock().unwrap_or_else(PoisonError::into_inner);
+    let edit = guard.get_or_insert_with(|| FilterEdit {
+        directives: log_filter(),
+        error: None,
+    });
+
+    let submitted = ui
+        .input_text("Log filter", &mut edit.directives)
+        .hint("info,my_addon=debug")
+        .enter_returns_true(true)
+        .build();
+    if submitted || ui.button("Apply##log_filter") {
+        edit.error = try_set_log_filter(&edit.directives)
+            .err()
+            .map(|err| err.to_string());
+    }
+    ui.same_line();
+    if ui.button("Reset##log_filter") {
+        reset_log_filter();
+        edit.directives = log_filter();
+        edit.error = None;
+    }
+    if let Some(error) = &edit.error {
+        ui.text_colored([1.0, 0.4, 0.4, 1.0], error);
+    }
+}
+
+/// Resets the state of [`render_log_filter_options`].
+#[cfg(feature = "log_filter")]
+pub(crate) fn reset_filter_edit() {
+    FILTER_EDIT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+}
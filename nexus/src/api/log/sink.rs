@@ -0,0 +1,255 @@
+use super::{channel::try_log_target_now, LogLevel};
+use crate::globals::{addon_name, try_addon_api};
+use std::{
+    collections::VecDeque,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Condvar, Mutex, MutexGuard, PoisonError,
+    },
+    thread,
+};
+
+/// State of the asynchronous log sink.
+static SINK: Mutex<Sink> = Mutex::new(Sink::new());
+
+/// Signals the worker about new records and producers about free capacity.
+static CONDVAR: Condvar = Condvar::new();
+
+/// Whether asynchronous logging is enabled.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Behavior when the queue of the asynchronous log sink is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogOverflow {
+    /// Waits for the worker to free capacity.
+    ///
+    /// Records logged before initialization are dropped instead.
+    Block,
+
+    /// Drops the record being logged.
+    DropNewest,
+
+    /// Drops the oldest queued record.
+    DropOldest,
+}
+
+struct Sink {
+    config: Option<Config>,
+    queue: VecDeque<Entry>,
+    dropped: usize,
+    worker: Option<thread::JoinHandle<()>>,
+    busy: bool,
+    shutdown: bool,
+}
+
+impl Sink {
+    const fn new() -> Self {
+        Self {
+            config: None,
+            queue: VecDeque::new(),
+            dropped: 0,
+            worker: None,
+            busy: false,
+            shutdown: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    capacity: usize,
+    overflow: LogOverflow,
+}
+
+/// Queued log record.
+#[derive(Debug)]
+pub(crate) struct Entry {
+    pub level: LogLevel,
+    pub target: String,
+
+    /// Default channel, missing for records logged before initialization.
+    pub channel: Option<String>,
+    pub message: String,
+}
+
+fn lock() -> MutexGuard<'static, Sink> {
+    SINK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sends records of the [`log`](https://docs.rs/log) and tracing integrations through a queue drained by a worker thread.
+///
+/// Logging then no longer calls into Nexus on the logging thread, for example the render thread.
+/// Records logged before initialization are buffered and logged once the addon is initialized,
+/// to capture them enable asynchronous logging as early as possible.
+/// On unload queued records are flushed and logging becomes synchronous again.
+#[inline]
+pub fn enable_async_logging(capacity: usize, overflow: LogOverflow) {
+    lock().config = Some(Config {
+        capacity: capacity.max(1),
+        overflow,
+    });
+    ENABLED.store(true, Ordering::Release);
+
+    #[cfg(feature = "log")]
+    crate::logger::NexusLogger::install();
+
+    if try_addon_api().is_some() {
+        start_sink();
+    }
+}
+
+/// Checks whether asynchronous logging is enabled.
+#[inline]
+pub fn is_async_logging() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// Waits until all queued records are logged.
+///
+/// Returns immediately before initialization.
+pub fn flush_log() {
+    let mut sink = lock();
+    while sink.worker.is_some() && (!sink.queue.is_empty() || sink.busy) {
+        sink = CONDVAR.wait(sink).unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+/// Queues a record if asynchronous logging is enabled.
+///
+/// Returns the entry back if it has to be logged synchronously.
+pub(crate) fn push(entry: Entry) -> Result<(), Entry> {
+    let mut sink = lock();
+    loop {
+        let Some(config) = sink.config else {
+            return Err(entry);
+        };
+        if sink.queue.len() < config.capacity {
+            break;
+        }
+        match config.overflow {
+            LogOverflow::Block if sink.worker.is_some() => {
+                sink = CONDVAR.wait(sink).unwrap_or_else(PoisonError::into_inner);
+            }
+            LogOverflow::Block | LogOverflow::DropNewest => {
+                sink.dropped += 1;
+                return Ok(());
+            }
+            LogOverflow::DropOldest => {
+                sink.queue.pop_front();
+                sink.dropped += 1;
+            }
+        }
+    }
+    sink.queue.push_back(entry);
+    CONDVAR.notify_all();
+    Ok(())
+}
+
+/// Starts the worker of the sink, if asynchronous logging is enabled.
+pub(crate) fn start_sink() {
+    let mut sink = lock();
+    if sink.config.is_some() && sink.worker.is_none() {
+        let handle = thread::Builder::new()
+            .name(format!("{} log", addon_name().unwrap_or("addon")))
+            .spawn(work)
+            .expect("failed to spawn log thread");
+        sink.worker = Some(handle);
+    }
+}
+
+fn work() {
+    loop {
+        let (entries, dropped) = {
+            let mut sink = lock();
+            while sink.queue.is_empty() && !sink.shutdown {
+                sink = CONDVAR.wait(sink).unwrap_or_else(PoisonError::into_inner);
+            }
+            if sink.queue.is_empty() {
+                return;
+            }
+            sink.busy = true;
+            CONDVAR.notify_all();
+            (mem::take(&mut sink.queue), mem::take(&mut sink.dropped))
+        };
+
+        for entry in entries {
+            log_entry(entry);
+        }
+        if dropped > 0 {
+            if let Some(addon_name) = addon_name() {
+                let _ = try_log_target_now(
+                    LogLevel::Warning,
+                    "",
+                    addon_name,
+                    format!("{dropped} log records dropped due to full queue"),
+                );
+            }
+        }
+
+        lock().busy = false;
+        CONDVAR.notify_all();
+    }
+}
+
+fn log_entry(entry: Entry) {
+    let Entry {
+        level,
+        target,
+        channel,
+        message,
+    } = entry;
+    let channel = match channel {
+        Some(channel) => channel,
+        None => {
+            // filter was not available when logged
+            #[cfg(feature = "log_filter")]
+            if !crate::logger::filter::enabled(
+                &log::Metadata::builder()
+                    .level(log_level(level))
+                    .target(&target)
+                    .build(),
+            ) {
+                return;
+            }
+
+            match addon_name() {
+                Some(addon_name) => addon_name.into(),
+                None => return,
+            }
+        }
+    };
+    let _ = try_log_target_now(level, &target, &channel, message);
+}
+
+#[cfg(feature = "log_filter")]
+fn log_level(level: LogLevel) -> log::Level {
+    match level {
+        LogLevel::Critical => log::Level::Error,
+        LogLevel::Warning => log::Level::Warn,
+        LogLevel::Info => log::Level::Info,
+        LogLevel::Debug => log::Level::Debug,
+        LogLevel::Trace | LogLevel::Off | LogLevel::All => log::Level::Trace,
+    }
+}
+
+/// Flushes queued records, stops the worker and disables asynchronous logging.
+pub(crate) fn shutdown_sink() {
+    let worker = {
+        let mut sink = lock();
+        sink.config = None;
+        ENABLED.store(false, Ordering::Release);
+        sink.shutdown = true;
+        CONDVAR.notify_all();
+        sink.worker.take()
+    };
+    if let Some(worker) = worker {
+        let _ = worker.join();
+    }
+
+    let mut sink = lock();
+    sink.queue.clear();
+    sink.dropped = 0;
+    sink.shutdown = false;
+    CONDVAR.notify_all();
+}
@@ -1,4 +1,5 @@
 pub mod alert;
+pub mod compat;
 pub mod data_link;
 pub mod event;
 pub mod font;
@@ -24,12 +25,23 @@ pub mod rtapi;
 // export current supported version
 pub use v6::*;
 
+use crate::Error;
+
 impl AddonApi {
     /// Returns the Nexus [`AddonApi`] instance.
     ///
     /// Panics if called before initialization.
     #[inline]
+    #[track_caller]
     pub fn get() -> &'static Self {
         crate::globals::addon_api()
     }
+
+    /// Returns the Nexus [`AddonApi`] instance, if initialized.
+    ///
+    /// Returns [`Error::NotInitialized`] before initialization or after unload.
+    #[inline]
+    pub fn try_get() -> Result<&'static Self, Error> {
+        crate::globals::try_addon_api().ok_or(Error::NotInitialized)
+    }
 }
@@ -1,8 +1,8 @@
 //! Paths.
 
 use crate::{
-    util::{path_from_c, str_to_c},
-    AddonApi, PathApi,
+    util::{path_from_c, try_str_to_c, unwrap_or_panic},
+    AddonApi, Error, PathApi,
 };
 use std::{ffi::c_char, path::PathBuf};
 
@@ -20,11 +20,20 @@ pub fn get_game_dir() -> Option<PathBuf> {
 }
 
 /// Returns the directory for an addon with the passed name.
+///
+/// Panics if the name contains a nul byte.
 #[inline]
+#[track_caller]
 pub fn get_addon_dir(name: impl AsRef<str>) -> Option<PathBuf> {
-    let PathApi { get_addon_dir, .. } = AddonApi::get().path;
-    let name = str_to_c(name, "failed to convert addon dir name");
-    unsafe { path_from_c(get_addon_dir(name.as_ptr())) }
+    unwrap_or_panic(try_get_addon_dir(name))
+}
+
+/// Fallible version of [`get_addon_dir`].
+#[inline]
+pub fn try_get_addon_dir(name: impl AsRef<str>) -> Result<Option<PathBuf>, Error> {
+    let PathApi { get_addon_dir, .. } = AddonApi::try_get()?.path;
+    let name = try_str_to_c(name, "addon dir name")?;
+    Ok(unsafe { path_from_c(get_addon_dir(name.as_ptr())) })
 }
 
 /// Returns the common addon directory.
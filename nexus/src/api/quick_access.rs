@@ -1,7 +1,13 @@
 //! Quick access creation.
 
-use crate::{gui::RawGuiRender, revertible::Revertible, util::str_to_c, AddonApi, QuickAccessApi};
-use std::{ffi::c_char, ptr};
+use crate::{
+    gui::RawGuiRender,
+    registry::{self, RegistrationKind},
+    revertible::Revertible,
+    util::{try_ident_to_c, try_to_c, unwrap_or_panic, OptionCStrExt},
+    AddonApi, Error, IntoCStr, QuickAccessApi,
+};
+use std::ffi::{c_char, CString};
 
 pub type RawQuickAccessAddShortcut = unsafe extern "C-unwind" fn(
     identifier: *const c_char,
@@ -28,28 +34,41 @@ pub type RawQuickAccessGeneric = unsafe extern "C-unwind" fn(identifier: *const
 /// When clicked the given keybind identifier is triggered.
 ///
 /// Returns a [`Revertible`] to remove the shortcut.
+/// Panics if an argument contains a nul byte.
+#[track_caller]
 pub fn add_quick_access(
-    identifier: impl AsRef<str>,
-    texture_identifier: impl AsRef<str>,
-    texture_hover_identifier: impl AsRef<str>,
-    keybind_identifier: impl AsRef<str>,
-    tooltip_text: impl AsRef<str>,
+    identifier: impl IntoCStr,
+    texture_identifier: impl IntoCStr,
+    texture_hover_identifier: impl IntoCStr,
+    keybind_identifier: impl IntoCStr,
+    tooltip_text: impl IntoCStr,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
-    let QuickAccessApi { add, remove, .. } = AddonApi::get().quick_access;
-    let identifier = str_to_c(identifier, "failed to convert shortcut identifier");
-    let texture_identifier = str_to_c(
+    unwrap_or_panic(try_add_quick_access(
+        identifier,
         texture_identifier,
-        "failed to convert shortcut texture identifier",
-    );
-    let texture_hover_identifier = str_to_c(
         texture_hover_identifier,
-        "failed to convert shortcut hover texture identifier",
-    );
-    let keybind_identifier = str_to_c(
         keybind_identifier,
-        "failed to convert shortcut keybind identifier",
-    );
-    let tooltip_text = str_to_c(tooltip_text, "failed to convert shortcut tooltip text");
+        tooltip_text,
+    ))
+}
+
+/// Fallible version of [`add_quick_access`].
+pub fn try_add_quick_access(
+    identifier: impl IntoCStr,
+    texture_identifier: impl IntoCStr,
+    texture_hover_identifier: impl IntoCStr,
+    keybind_identifier: impl IntoCStr,
+    tooltip_text: impl IntoCStr,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
+    let QuickAccessApi { add, remove, .. } = AddonApi::try_get()?.quick_access;
+    let identifier: CString = try_ident_to_c(identifier, "shortcut identifier")?.into();
+    let texture_identifier = try_ident_to_c(texture_identifier, "shortcut texture identifier")?;
+    let texture_hover_identifier = try_ident_to_c(
+        texture_hover_identifier,
+        "shortcut hover texture identifier",
+    )?;
+    let keybind_identifier = try_ident_to_c(keybind_identifier, "shortcut keybind identifier")?;
+    let tooltip_text = try_to_c(tooltip_text, "shortcut tooltip text")?;
     unsafe {
         add(
             identifier.as_ptr(),
@@ -59,61 +78,124 @@ pub fn add_quick_access(
             tooltip_text.as_ptr(),
         )
     };
+    let name = registry::name(&identifier);
     let revert = move || unsafe { remove(identifier.as_ptr()) };
-    revert.into()
+    Ok(registry::track(
+        RegistrationKind::QuickAccess,
+        name,
+        None,
+        revert,
+    ))
 }
 
 /// Removes a previously registered shortcut from the quick access.
-pub fn remove_quick_access(identifier: impl AsRef<str>) {
-    let QuickAccessApi { remove, .. } = AddonApi::get().quick_access;
-    let identifier = str_to_c(identifier, "failed to convert shortcut identifier");
-    unsafe { remove(identifier.as_ptr()) }
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn remove_quick_access(identifier: impl IntoCStr) {
+    unwrap_or_panic(try_remove_quick_access(identifier))
+}
+
+/// Fallible version of [`remove_quick_access`].
+pub fn try_remove_quick_access(identifier: impl IntoCStr) -> Result<(), Error> {
+    let QuickAccessApi { remove, .. } = AddonApi::try_get()?.quick_access;
+    let identifier = try_ident_to_c(identifier, "shortcut identifier")?;
+    unsafe { remove(identifier.as_ptr()) };
+    registry::untrack(
+        RegistrationKind::QuickAccess,
+        Some(&identifier.to_string_lossy()),
+        None,
+    );
+    Ok(())
 }
 
 /// Sends a notification to the given quick access shortcut.
-pub fn notify_quick_access(identifier: impl AsRef<str>) {
-    let QuickAccessApi { notify, .. } = AddonApi::get().quick_access;
-    let identifier = str_to_c(identifier, "failed to convert shortcut identifier");
-    unsafe { notify(identifier.as_ptr()) }
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn notify_quick_access(identifier: impl IntoCStr) {
+    unwrap_or_panic(try_notify_quick_access(identifier))
+}
+
+/// Fallible version of [`notify_quick_access`].
+pub fn try_notify_quick_access(identifier: impl IntoCStr) -> Result<(), Error> {
+    let QuickAccessApi { notify, .. } = AddonApi::try_get()?.quick_access;
+    let identifier = try_ident_to_c(identifier, "shortcut identifier")?;
+    unsafe { notify(identifier.as_ptr()) };
+    Ok(())
 }
 
 /// Adds a new [`RawGuiRender`] callback for the shortcut context menu.
 ///
 /// Returns a [`Revertible`] to remove the context menu.
+/// Panics if an identifier contains a nul byte.
+#[track_caller]
 pub fn add_quick_access_context_menu(
-    identifier: impl AsRef<str>,
-    target_identifier: Option<impl AsRef<str>>,
+    identifier: impl IntoCStr,
+    target_identifier: Option<impl IntoCStr>,
     render_callback: RawGuiRender,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    unwrap_or_panic(try_add_quick_access_context_menu(
+        identifier,
+        target_identifier,
+        render_callback,
+    ))
+}
+
+/// Fallible version of [`add_quick_access_context_menu`].
+pub fn try_add_quick_access_context_menu(
+    identifier: impl IntoCStr,
+    target_identifier: Option<impl IntoCStr>,
+    render_callback: RawGuiRender,
+) -> Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>, Error> {
     let QuickAccessApi {
         add_context_menu,
         remove_context_menu,
         ..
-    } = AddonApi::get().quick_access;
-    let identifier = str_to_c(identifier, "failed to convert shortcut identifier");
+    } = AddonApi::try_get()?.quick_access;
+    let identifier: CString = try_ident_to_c(identifier, "shortcut identifier")?.into();
     let target_identifier = target_identifier
-        .map(|string| str_to_c(string, "failed to convert shortcut target identifier"));
+        .map(|string| try_ident_to_c(string, "shortcut target identifier"))
+        .transpose()?;
     unsafe {
         add_context_menu(
             identifier.as_ptr(),
-            target_identifier
-                .map(|string| string.as_ptr())
-                .unwrap_or(ptr::null()),
+            target_identifier.as_deref().as_ptr_opt(),
             render_callback,
         )
     };
+    let name = registry::name(&identifier);
     let revert = move || unsafe { remove_context_menu(identifier.as_ptr()) };
-    revert.into()
+    Ok(registry::track(
+        RegistrationKind::QuickAccessContextMenu,
+        name,
+        Some(render_callback as usize),
+        revert,
+    ))
 }
 
 /// Removes a previously registered shortcut context menu callback.
-pub fn remove_quick_access_context_menu(identifier: impl AsRef<str>) {
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn remove_quick_access_context_menu(identifier: impl IntoCStr) {
+    unwrap_or_panic(try_remove_quick_access_context_menu(identifier))
+}
+
+/// Fallible version of [`remove_quick_access_context_menu`].
+pub fn try_remove_quick_access_context_menu(identifier: impl IntoCStr) -> Result<(), Error> {
     let QuickAccessApi {
         remove_context_menu,
         ..
-    } = AddonApi::get().quick_access;
-    let identifier = str_to_c(identifier, "failed to convert shortcut identifier");
-    unsafe { remove_context_menu(identifier.as_ptr()) }
+    } = AddonApi::try_get()?.quick_access;
+    let identifier = try_ident_to_c(identifier, "shortcut identifier")?;
+    unsafe { remove_context_menu(identifier.as_ptr()) };
+    registry::untrack(
+        RegistrationKind::QuickAccessContextMenu,
+        Some(&identifier.to_string_lossy()),
+        None,
+    );
+    Ok(())
 }
 
 /// Adds a new shortcut icon to the quick access with the given texture identifiers.
@@ -122,11 +204,11 @@ pub fn remove_quick_access_context_menu(identifier: impl AsRef<str>) {
 /// Returns a [`Revertible`] to remove the shortcut.
 #[deprecated = "use add_quick_access"]
 pub fn add_shortcut(
-    identifier: impl AsRef<str>,
-    texture_identifier: impl AsRef<str>,
-    texture_hover_identifier: impl AsRef<str>,
-    keybind_identifier: impl AsRef<str>,
-    tooltip_text: impl AsRef<str>,
+    identifier: impl IntoCStr,
+    texture_identifier: impl IntoCStr,
+    texture_hover_identifier: impl IntoCStr,
+    keybind_identifier: impl IntoCStr,
+    tooltip_text: impl IntoCStr,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
     add_quick_access(
         identifier,
@@ -139,7 +221,7 @@ pub fn add_shortcut(
 
 /// Removes a previously registered shortcut from the quick access.
 #[deprecated = "use remove_quick_access"]
-pub fn remove_shortcut(identifier: impl AsRef<str>) {
+pub fn remove_shortcut(identifier: impl IntoCStr) {
     remove_quick_access(identifier)
 }
 
@@ -148,7 +230,7 @@ pub fn remove_shortcut(identifier: impl AsRef<str>) {
 /// Returns a [`Revertible`] to remove the shortcut.
 #[deprecated = "use add_quick_access_context_menu"]
 pub fn add_simple_shortcut(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     render_callback: RawGuiRender,
 ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
     add_quick_access_context_menu(identifier, None::<&str>, render_callback)
@@ -156,6 +238,6 @@ pub fn add_simple_shortcut(
 
 /// Removes a previously registered simple shortcut callback.
 #[deprecated = "use remove_quick_access_context_menu"]
-pub fn remove_simple_shortcut(identifier: impl AsRef<str>) {
+pub fn remove_simple_shortcut(identifier: impl IntoCStr) {
     remove_quick_access_context_menu(identifier)
 }
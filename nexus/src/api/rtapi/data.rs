@@ -1,4 +1,7 @@
-use crate::data_link::{get_resource, read_resource};
+use crate::{
+    data_link::{get_resource, read_resource},
+    Global, Identifier,
+};
 use bitfields::bitfield;
 use std::ffi::c_char;
 
@@ -86,16 +89,21 @@ impl RealTimeData {
     pub const SIG: i32 = 0x2501A02C;
 
     /// RealTime API data link identifier.
+    ///
+    /// Needs to be passed as [`Global`] if namespacing is enabled.
     pub const LINK: &str = "RTAPI";
 
+    /// RealTime API data link identifier as global [`Identifier`].
+    const LINK_IDENT: Global<Identifier<'static>> = Global(crate::c_ident!("RTAPI"));
+
     #[inline]
     pub fn get_ptr() -> *const Self {
-        get_resource(Self::LINK)
+        get_resource(Self::LINK_IDENT)
     }
 
     #[inline]
     pub fn read() -> Option<Self> {
-        unsafe { read_resource(Self::LINK) }
+        unsafe { read_resource(Self::LINK_IDENT) }
     }
 }
 
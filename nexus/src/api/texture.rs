@@ -1,15 +1,19 @@
 //! Texture loading.
 
 use crate::{
-    util::{path_to_c, str_to_c},
-    AddonApi, TextureApi,
+    dispatch::{self, Dispatch},
+    identifier::strip_namespace,
+    panic,
+    util::{str_from_c, try_c_to_str, try_ident_to_c, try_path_to_c, try_to_c, unwrap_or_panic},
+    win::{ID3D11ShaderResourceView, HMODULE},
+    AddonApi, Error, Global, IntoCStr, TextureApi,
 };
 use std::{
     ffi::{c_char, c_void},
     mem,
     path::Path,
+    sync::{atomic::AtomicBool, Arc, Mutex},
 };
-use windows::Win32::{Foundation::HMODULE, Graphics::Direct3D11::ID3D11ShaderResourceView};
 
 /// A loaded texture.
 #[derive(Debug, Clone)]
@@ -32,7 +36,9 @@ impl Texture {
     #[inline]
     pub fn resource_ptr(&self) -> *const c_void {
         // ShaderResourceView is a IUnknown, which is is a NonNull<c_void>
-        unsafe { mem::transmute_copy::<Option<ID3D11ShaderResourceView>, *const c_void>(&self.resource) }
+        unsafe {
+            mem::transmute_copy::<Option<ID3D11ShaderResourceView>, *const c_void>(&self.resource)
+        }
     }
 
     /// Returns the associated [`imgui::TextureId`].
@@ -111,119 +117,220 @@ pub type RawTextureLoadFromMemory = unsafe extern "C-unwind" fn(
 );
 
 /// Attempts to retrieve a texture by its identifier.
-pub fn get_texture(identifier: impl AsRef<str>) -> Option<Texture> {
-    let TextureApi { get, .. } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
-    unsafe { get(identifier.as_ptr()).as_ref().cloned() }
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn get_texture(identifier: impl IntoCStr) -> Option<Texture> {
+    unwrap_or_panic(try_get_texture(identifier))
+}
+
+/// Fallible version of [`get_texture`].
+pub fn try_get_texture(identifier: impl IntoCStr) -> Result<Option<Texture>, Error> {
+    let TextureApi { get, .. } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    Ok(unsafe { get(identifier.as_ptr()).as_ref().cloned() })
 }
 
 /// Attempts to retrieve a texture or creates it from the given file path.
+///
+/// Panics if the identifier contains a nul byte or the path is not valid UTF-8.
+#[track_caller]
 pub fn get_texture_or_create_from_file(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     file: impl AsRef<Path>,
 ) -> Option<Texture> {
+    unwrap_or_panic(try_get_texture_or_create_from_file(identifier, file))
+}
+
+/// Fallible version of [`get_texture_or_create_from_file`].
+pub fn try_get_texture_or_create_from_file(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+) -> Result<Option<Texture>, Error> {
     let TextureApi {
         get_or_create_from_file,
         ..
-    } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
-    let file = path_to_c(file, "failed to convert texture file");
-    unsafe {
+    } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
+    let file = try_path_to_c(file, "texture file path")?;
+    Ok(unsafe {
         get_or_create_from_file(identifier.as_ptr(), file.as_ptr())
             .as_ref()
             .cloned()
-    }
+    })
 }
 
 /// Attempts to retrieve a texture or creates it from the given resource.
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
 pub fn get_texture_or_create_from_resource(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     resource_id: u32,
     module: HMODULE,
 ) -> Option<Texture> {
+    unwrap_or_panic(try_get_texture_or_create_from_resource(
+        identifier,
+        resource_id,
+        module,
+    ))
+}
+
+/// Fallible version of [`get_texture_or_create_from_resource`].
+pub fn try_get_texture_or_create_from_resource(
+    identifier: impl IntoCStr,
+    resource_id: u32,
+    module: HMODULE,
+) -> Result<Option<Texture>, Error> {
     let TextureApi {
         get_or_create_from_resource,
         ..
-    } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
-    unsafe {
+    } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
+    Ok(unsafe {
         get_or_create_from_resource(identifier.as_ptr(), resource_id, module)
             .as_ref()
             .cloned()
-    }
+    })
 }
 
 /// Attempts to retrieve a texture or creates it from the given URL.
+///
+/// Panics if an argument contains a nul byte.
+#[track_caller]
 pub fn get_texture_or_create_from_url(
-    identifier: impl AsRef<str>,
-    remote: impl AsRef<str>,
-    endpoint: impl AsRef<str>,
+    identifier: impl IntoCStr,
+    remote: impl IntoCStr,
+    endpoint: impl IntoCStr,
 ) -> Option<Texture> {
+    unwrap_or_panic(try_get_texture_or_create_from_url(
+        identifier, remote, endpoint,
+    ))
+}
+
+/// Fallible version of [`get_texture_or_create_from_url`].
+pub fn try_get_texture_or_create_from_url(
+    identifier: impl IntoCStr,
+    remote: impl IntoCStr,
+    endpoint: impl IntoCStr,
+) -> Result<Option<Texture>, Error> {
     let TextureApi {
         get_or_create_from_url,
         ..
-    } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
-    let remote = str_to_c(remote, "failed to convert texture url remote");
-    let endpoint = str_to_c(endpoint, "failed to convert texture url endpoint");
-    unsafe {
+    } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
+    let remote = try_to_c(remote, "texture url remote")?;
+    let endpoint = try_to_c(endpoint, "texture url endpoint")?;
+    Ok(unsafe {
         get_or_create_from_url(identifier.as_ptr(), remote.as_ptr(), endpoint.as_ptr())
             .as_ref()
             .cloned()
-    }
+    })
 }
 
 /// Attempts to retrieve a texture or creates it from the given memory.
+///
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
 pub fn get_texture_or_create_from_memory(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     memory: impl AsRef<[u8]>,
 ) -> Option<Texture> {
+    unwrap_or_panic(try_get_texture_or_create_from_memory(identifier, memory))
+}
+
+/// Fallible version of [`get_texture_or_create_from_memory`].
+pub fn try_get_texture_or_create_from_memory(
+    identifier: impl IntoCStr,
+    memory: impl AsRef<[u8]>,
+) -> Result<Option<Texture>, Error> {
     let TextureApi {
         get_or_create_from_memory,
         ..
-    } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
+    } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
     let memory = memory.as_ref();
-    unsafe {
+    Ok(unsafe {
         get_or_create_from_memory(identifier.as_ptr(), memory.as_ptr().cast(), memory.len())
             .as_ref()
             .cloned()
-    }
+    })
 }
 
 /// Loads a texture from the given file path.
 ///
 /// You can create a [`RawTextureReceiveCallback`] using the [`texture_receive`] macro.
+/// Panics if the identifier contains a nul byte or the path is not valid UTF-8.
+#[track_caller]
 pub fn load_texture_from_file(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     file: impl AsRef<Path>,
     callback: Option<RawTextureReceiveCallback>,
 ) {
-    let TextureApi { load_from_file, .. } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
-    let file = path_to_c(file, "foo");
+    unwrap_or_panic(try_load_texture_from_file(identifier, file, callback))
+}
+
+/// Fallible version of [`load_texture_from_file`].
+pub fn try_load_texture_from_file(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+    callback: Option<RawTextureReceiveCallback>,
+) -> Result<(), Error> {
+    let TextureApi { load_from_file, .. } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
+    let file = try_path_to_c(file, "texture file path")?;
     unsafe {
         load_from_file(
             identifier.as_ptr(),
             file.as_ptr(),
             callback.unwrap_or(dummy_receive_texture),
         )
-    }
+    };
+    Ok(())
 }
 
 /// Loads a texture from the given resource.
 ///
 /// You can create a [`RawTextureReceiveCallback`] using the [`texture_receive`] macro.
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
 pub fn load_texture_from_resource(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     resource_id: u32,
     module: HMODULE,
     callback: Option<RawTextureReceiveCallback>,
 ) {
+    unwrap_or_panic(try_load_texture_from_resource(
+        identifier,
+        resource_id,
+        module,
+        callback,
+    ))
+}
+
+/// Fallible version of [`load_texture_from_resource`].
+pub fn try_load_texture_from_resource(
+    identifier: impl IntoCStr,
+    resource_id: u32,
+    module: HMODULE,
+    callback: Option<RawTextureReceiveCallback>,
+) -> Result<(), Error> {
     let TextureApi {
         load_from_resource, ..
-    } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
+    } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
     unsafe {
         load_from_resource(
             identifier.as_ptr(),
@@ -231,12 +338,14 @@ pub fn load_texture_from_resource(
             module,
             callback.unwrap_or(dummy_receive_texture),
         )
-    }
+    };
+    Ok(())
 }
 
 /// Loads a texture from the given URL.
 ///
 /// You can create a [`RawTextureReceiveCallback`] using the [`texture_receive`] macro.
+/// Panics if an argument contains a nul byte.
 ///
 /// # Usage
 /// ```no_run
@@ -249,16 +358,31 @@ pub fn load_texture_from_resource(
 ///     Some(receive_texture),
 /// )
 /// ```
+#[track_caller]
 pub fn load_texture_from_url(
-    identifier: impl AsRef<str>,
-    remote: impl AsRef<str>,
-    endpoint: impl AsRef<str>,
+    identifier: impl IntoCStr,
+    remote: impl IntoCStr,
+    endpoint: impl IntoCStr,
     callback: Option<RawTextureReceiveCallback>,
 ) {
-    let TextureApi { load_from_url, .. } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
-    let remote = str_to_c(remote, "failed to convert texture url remote");
-    let endpoint = str_to_c(endpoint, "failed to convert texture url endpoint");
+    unwrap_or_panic(try_load_texture_from_url(
+        identifier, remote, endpoint, callback,
+    ))
+}
+
+/// Fallible version of [`load_texture_from_url`].
+pub fn try_load_texture_from_url(
+    identifier: impl IntoCStr,
+    remote: impl IntoCStr,
+    endpoint: impl IntoCStr,
+    callback: Option<RawTextureReceiveCallback>,
+) -> Result<(), Error> {
+    let TextureApi { load_from_url, .. } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
+    let remote = try_to_c(remote, "texture url remote")?;
+    let endpoint = try_to_c(endpoint, "texture url endpoint")?;
     unsafe {
         load_from_url(
             identifier.as_ptr(),
@@ -266,21 +390,35 @@ pub fn load_texture_from_url(
             endpoint.as_ptr(),
             callback.unwrap_or(dummy_receive_texture),
         )
-    }
+    };
+    Ok(())
 }
 
 /// Loads a texture from the given memory.
-/// ///
+///
 /// You can create a [`RawTextureReceiveCallback`] using the [`texture_receive`] macro.
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
 pub fn load_texture_from_memory(
-    identifier: impl AsRef<str>,
+    identifier: impl IntoCStr,
     data: impl AsRef<[u8]>,
     callback: Option<RawTextureReceiveCallback>,
 ) {
+    unwrap_or_panic(try_load_texture_from_memory(identifier, data, callback))
+}
+
+/// Fallible version of [`load_texture_from_memory`].
+pub fn try_load_texture_from_memory(
+    identifier: impl IntoCStr,
+    data: impl AsRef<[u8]>,
+    callback: Option<RawTextureReceiveCallback>,
+) -> Result<(), Error> {
     let TextureApi {
         load_from_memory, ..
-    } = AddonApi::get().texture;
-    let identifier = str_to_c(identifier, "failed to convert texture identifier");
+    } = AddonApi::try_get()?.texture;
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    #[cfg(feature = "debug")]
+    crate::debug::record_texture(&identifier);
     let data = data.as_ref();
     unsafe {
         load_from_memory(
@@ -289,6 +427,183 @@ pub fn load_texture_from_memory(
             data.len(),
             callback.unwrap_or(dummy_receive_texture),
         )
+    };
+    Ok(())
+}
+
+/// Closure receiving a loaded texture.
+type TextureReceiveFn = Option<Box<dyn FnOnce(&str, Option<&Texture>) + Send>>;
+
+/// Pending texture receive closures.
+static TEXTURE_FNS: Dispatch<String, TextureReceiveFn> = Dispatch::new();
+
+/// Loads a texture from the given file path and passes it to the closure.
+///
+/// Unlike [`load_texture_from_file`] the closure can capture state.
+/// Panics if the identifier contains a nul byte or the path is not valid UTF-8.
+#[track_caller]
+pub fn load_texture_from_file_fn(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) {
+    unwrap_or_panic(try_load_texture_from_file_fn(identifier, file, callback))
+}
+
+/// Fallible version of [`load_texture_from_file_fn`].
+pub fn try_load_texture_from_file_fn(
+    identifier: impl IntoCStr,
+    file: impl AsRef<Path>,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    let id = insert_texture_fn(try_c_to_str(&identifier, "texture identifier")?, callback);
+    try_load_texture_from_file(Global(&*identifier), file, Some(texture_trampoline))
+        .map_err(|err| remove_texture_fn(id, err))
+}
+
+/// Loads a texture from the given resource and passes it to the closure.
+///
+/// Unlike [`load_texture_from_resource`] the closure can capture state.
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn load_texture_from_resource_fn(
+    identifier: impl IntoCStr,
+    resource_id: u32,
+    module: HMODULE,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) {
+    unwrap_or_panic(try_load_texture_from_resource_fn(
+        identifier,
+        resource_id,
+        module,
+        callback,
+    ))
+}
+
+/// Fallible version of [`load_texture_from_resource_fn`].
+pub fn try_load_texture_from_resource_fn(
+    identifier: impl IntoCStr,
+    resource_id: u32,
+    module: HMODULE,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    let id = insert_texture_fn(try_c_to_str(&identifier, "texture identifier")?, callback);
+    try_load_texture_from_resource(
+        Global(&*identifier),
+        resource_id,
+        module,
+        Some(texture_trampoline),
+    )
+    .map_err(|err| remove_texture_fn(id, err))
+}
+
+/// Loads a texture from the given URL and passes it to the closure.
+///
+/// Unlike [`load_texture_from_url`] the closure can capture state.
+/// Panics if an argument contains a nul byte.
+///
+/// # Usage
+/// ```no_run
+/// # use nexus::texture::*;
+/// use std::sync::{Arc, Mutex};
+/// let icon = Arc::new(Mutex::new(None));
+/// let receiver = icon.clone();
+/// load_texture_from_url_fn(
+///     "TEX_DUNGEON_ICON",
+///     "https://render.guildwars2.com",
+///     "/file/943538394A94A491C8632FBEF6203C2013443555/102478.png",
+///     move |_id, texture| *receiver.lock().unwrap() = texture.map(Texture::id),
+/// )
+/// ```
+#[track_caller]
+pub fn load_texture_from_url_fn(
+    identifier: impl IntoCStr,
+    remote: impl IntoCStr,
+    endpoint: impl IntoCStr,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) {
+    unwrap_or_panic(try_load_texture_from_url_fn(
+        identifier, remote, endpoint, callback,
+    ))
+}
+
+/// Fallible version of [`load_texture_from_url_fn`].
+pub fn try_load_texture_from_url_fn(
+    identifier: impl IntoCStr,
+    remote: impl IntoCStr,
+    endpoint: impl IntoCStr,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    let id = insert_texture_fn(try_c_to_str(&identifier, "texture identifier")?, callback);
+    try_load_texture_from_url(
+        Global(&*identifier),
+        remote,
+        endpoint,
+        Some(texture_trampoline),
+    )
+    .map_err(|err| remove_texture_fn(id, err))
+}
+
+/// Loads a texture from the given memory and passes it to the closure.
+///
+/// Unlike [`load_texture_from_memory`] the closure can capture state.
+/// Panics if the identifier contains a nul byte.
+#[track_caller]
+pub fn load_texture_from_memory_fn(
+    identifier: impl IntoCStr,
+    data: impl AsRef<[u8]>,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) {
+    unwrap_or_panic(try_load_texture_from_memory_fn(identifier, data, callback))
+}
+
+/// Fallible version of [`load_texture_from_memory_fn`].
+pub fn try_load_texture_from_memory_fn(
+    identifier: impl IntoCStr,
+    data: impl AsRef<[u8]>,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) -> Result<(), Error> {
+    let identifier = try_ident_to_c(identifier, "texture identifier")?;
+    let id = insert_texture_fn(try_c_to_str(&identifier, "texture identifier")?, callback);
+    try_load_texture_from_memory(Global(&*identifier), data, Some(texture_trampoline))
+        .map_err(|err| remove_texture_fn(id, err))
+}
+
+fn insert_texture_fn(
+    identifier: &str,
+    callback: impl FnOnce(&str, Option<&Texture>) + Send + 'static,
+) -> u64 {
+    TEXTURE_FNS.insert(
+        identifier.into(),
+        Arc::new(Mutex::new(Some(Box::new(callback)))),
+        |_| {},
+        |_| {},
+    )
+}
+
+/// Removes a closure again if the texture failed to load.
+fn remove_texture_fn(id: u64, err: Error) -> Error {
+    TEXTURE_FNS.remove(id, |_| {});
+    err
+}
+
+extern "C-unwind" fn texture_trampoline(identifier: *const c_char, texture: *const Texture) {
+    let Some(identifier) = (unsafe { str_from_c(identifier) }) else {
+        return;
+    };
+    let texture = unsafe { texture.as_ref() };
+    for callback in TEXTURE_FNS.remove_key(identifier) {
+        let callback = dispatch::lock(&callback).take();
+        if let Some(callback) = callback {
+            // closure is consumed, nothing to disable
+            let disabled = AtomicBool::new(false);
+            panic::catch("texture receive callback", &disabled, || {
+                callback(strip_namespace(identifier), texture)
+            });
+        }
     }
 }
 
@@ -316,10 +631,14 @@ macro_rules! texture_receive {
             identifier: *const ::std::ffi::c_char,
             texture: *const $crate::texture::Texture,
         ) {
-            let identifier = unsafe { $crate::__macro::str_from_c(identifier) }
-                .expect("invalid identifier in texture callback");
-            let texture = unsafe { texture.as_ref() };
-            __CALLBACK(identifier, texture)
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            $crate::__macro::catch("texture receive callback", &__DISABLED, || {
+                let identifier = unsafe { $crate::__macro::str_from_c(identifier) }
+                    .expect("invalid identifier in texture callback");
+                let texture = unsafe { texture.as_ref() };
+                __CALLBACK($crate::__macro::strip_namespace(identifier), texture)
+            });
         }
 
         __keybind_callback_wrapper
@@ -1,13 +1,26 @@
 //! Update management.
 
-use crate::{util::str_to_c, AddonApi};
+use crate::{
+    util::{try_str_to_c, unwrap_or_panic},
+    AddonApi, Error,
+};
 use std::ffi::c_char;
 
 pub type RawRequestUpdate = unsafe extern "C-unwind" fn(signature: i32, update_url: *const c_char);
 
 /// Requests an update to be downloaded **without** performing a version check.
+///
+/// Panics if the url contains a nul byte or update requests are not supported.
+#[track_caller]
 pub fn request_update(signature: i32, update_url: impl AsRef<str>) {
-    let AddonApi { request_update, .. } = AddonApi::get();
-    let update_url = str_to_c(update_url, "failed to convert update url");
-    unsafe { request_update(signature, update_url.as_ptr()) }
+    unwrap_or_panic(try_request_update(signature, update_url))
+}
+
+/// Fallible version of [`request_update`].
+pub fn try_request_update(signature: i32, update_url: impl AsRef<str>) -> Result<(), Error> {
+    let AddonApi { request_update, .. } =
+        AddonApi::try_get()?.require(AddonApi::has_request_update, "update requests")?;
+    let update_url = try_str_to_c(update_url, "update url")?;
+    unsafe { request_update(signature, update_url.as_ptr()) };
+    Ok(())
 }
@@ -25,10 +25,9 @@ use super::{
     },
     wnd_proc::{RawWndProcAddRem, RawWndProcSendToGame},
 };
-use windows::{
-    core::InterfaceRef,
-    Win32::Graphics::{Direct3D11::ID3D11Device, Dxgi::IDXGISwapChain},
-};
+#[cfg(windows)]
+use crate::win::ID3D11Device;
+use crate::win::{IDXGISwapChain, InterfaceRef};
 
 /// Nexus addon API (version 3).
 #[derive(Debug, Clone, Copy)]
@@ -198,6 +197,7 @@ impl AddonApi {
     pub const VERSION: i32 = 3;
 
     /// Retrieves the DirectX 11 device associated with the swap chain.
+    #[cfg(windows)]
     #[inline]
     pub fn get_d3d11_device(&self) -> Option<ID3D11Device> {
         let swap_chain = self.swap_chain.as_ref()?;
@@ -28,10 +28,9 @@ use super::{
     updater::RawRequestUpdate,
     wnd_proc::{RawWndProcAddRem, RawWndProcSendToGame},
 };
-use windows::{
-    core::InterfaceRef,
-    Win32::Graphics::{Direct3D11::ID3D11Device, Dxgi::IDXGISwapChain},
-};
+#[cfg(windows)]
+use crate::win::ID3D11Device;
+use crate::win::{IDXGISwapChain, InterfaceRef};
 
 /// Nexus addon API (version 4).
 #[derive(Debug, Clone, Copy)]
@@ -219,6 +218,7 @@ impl AddonApi {
     pub const VERSION: i32 = 4;
 
     /// Retrieves the DirectX 11 device associated with the swap chain.
+    #[cfg(windows)]
     #[inline]
     pub fn get_d3d11_device(&self) -> Option<ID3D11Device> {
         let swap_chain = self.swap_chain.as_ref()?;
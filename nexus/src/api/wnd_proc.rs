@@ -1,7 +1,11 @@
 //! Windows [WNDPROC](https://learn.microsoft.com/en-us/windows/win32/api/winuser/nc-winuser-wndproc).
 
-use crate::{revertible::Revertible, AddonApi, WndProcApi};
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use crate::{
+    registry::{self, RegistrationKind},
+    revertible::Revertible,
+    win::{HWND, LPARAM, LRESULT, WPARAM},
+    AddonApi, WndProcApi,
+};
 
 pub type RawWndProcCallback =
     extern "C-unwind" fn(h_wnd: HWND, u_msg: u32, w_param: WPARAM, l_param: LPARAM) -> u32;
@@ -28,13 +32,19 @@ pub fn register_wnd_proc(
     } = AddonApi::get().wnd_proc;
     unsafe { register(callback) };
     let revert = move || unsafe { deregister(callback) };
-    revert.into()
+    registry::track(
+        RegistrationKind::WndProc,
+        None,
+        Some(callback as usize),
+        revert,
+    )
 }
 
 /// Deregisters an already registered [WNDPROC](https://learn.microsoft.com/en-us/windows/win32/api/winuser/nc-winuser-wndproc) callback.
 pub fn deregister_wnd_proc(callback: RawWndProcCallback) {
     let WndProcApi { deregister, .. } = AddonApi::get().wnd_proc;
-    unsafe { deregister(callback) }
+    unsafe { deregister(callback) };
+    registry::untrack(RegistrationKind::WndProc, None, Some(callback as usize));
 }
 
 /// Sends a [WNDPROC](https://learn.microsoft.com/en-us/windows/win32/api/winuser/nc-winuser-wndproc) directly to the game, bypassing other hooks.
@@ -44,3 +54,45 @@ pub fn send_wnd_proc_to_game(h_wnd: HWND, u_msg: u32, w_param: WPARAM, l_param:
     } = AddonApi::get().wnd_proc;
     unsafe { send_to_game_only(h_wnd, u_msg, w_param, l_param) }
 }
+
+/// Macro to wrap a [WNDPROC](https://learn.microsoft.com/en-us/windows/win32/api/winuser/nc-winuser-wndproc) callback.
+///
+/// Generates a [`RawWndProcCallback`] wrapper around the passed callback.
+/// The callback returns `0` to consume the message or the message to pass it on.
+/// Messages are passed on if the callback panics.
+///
+/// # Usage
+/// ```no_run
+/// # use nexus::wnd_proc::*;
+/// let callback: RawWndProcCallback = wnd_proc_callback!(|_h_wnd, u_msg, _w_param, _l_param| u_msg);
+/// register_wnd_proc(callback).revert_on_unload();
+/// ```
+#[macro_export]
+macro_rules! wnd_proc_callback {
+    ( $callback:expr $(,)? ) => {{
+        const __CALLBACK: fn(
+            $crate::win::HWND,
+            ::std::primitive::u32,
+            $crate::win::WPARAM,
+            $crate::win::LPARAM,
+        ) -> ::std::primitive::u32 = $callback;
+
+        extern "C-unwind" fn __wnd_proc_callback_wrapper(
+            h_wnd: $crate::win::HWND,
+            u_msg: ::std::primitive::u32,
+            w_param: $crate::win::WPARAM,
+            l_param: $crate::win::LPARAM,
+        ) -> ::std::primitive::u32 {
+            static __DISABLED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            $crate::__macro::catch("wnd proc callback", &__DISABLED, || {
+                __CALLBACK(h_wnd, u_msg, w_param, l_param)
+            })
+            .unwrap_or(u_msg)
+        }
+
+        __wnd_proc_callback_wrapper
+    }};
+}
+
+pub use wnd_proc_callback;
@@ -0,0 +1,221 @@
+//! Crash reports written on panic.
+
+use crate::{addon::AddonDefinition, globals::try_addon_api, log::LogLevel, util::path_from_c};
+use std::{
+    backtrace::Backtrace,
+    cell::Cell,
+    collections::VecDeque,
+    ffi::CString,
+    fmt::Write as _,
+    fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Maximum number of log lines kept for crash reports.
+const RECENT_LOGS_CAPACITY: usize = 64;
+
+/// Recent log lines.
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Panic locations already reported, to avoid a report per frame.
+static REPORTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Whether the current thread holds the recent log lines.
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Records a log line for crash reports.
+///
+/// Skipped when called again while recording on the same thread,
+/// for example from the panic hook after a panic during recording.
+pub fn record_log(level: LogLevel, channel: &str, message: &str) {
+    if RECORDING.with(|recording| recording.replace(true)) {
+        return;
+    }
+    let _guard = RecordingGuard;
+    let mut logs = RECENT_LOGS.lock().unwrap_or_else(PoisonError::into_inner);
+    if logs.len() >= RECENT_LOGS_CAPACITY {
+        logs.pop_front();
+    }
+    logs.push_back(format!("[{level:?}] {channel}: {message}"));
+}
+
+/// Resets the recording flag, even when unwinding.
+struct RecordingGuard;
+
+impl Drop for RecordingGuard {
+    #[inline]
+    fn drop(&mut self) {
+        RECORDING.with(|recording| recording.set(false));
+    }
+}
+
+/// Writes a crash report into the addon directory.
+///
+/// Only the first panic at each location is reported.
+/// Returns the path of the written report.
+pub fn write_report(
+    definition: &AddonDefinition,
+    addon_name: &str,
+    panic: &str,
+    location: Option<String>,
+) -> Option<PathBuf> {
+    if let Some(location) = location {
+        let mut reported = REPORTED.lock().unwrap_or_else(PoisonError::into_inner);
+        if reported.contains(&location) {
+            return None;
+        }
+        reported.push(location);
+    }
+
+    let api = try_addon_api()?;
+    let name = CString::new(addon_name).ok()?;
+    let dir = unsafe { path_from_c((api.path.get_addon_dir)(name.as_ptr())) }?;
+
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let (date, time) = format_timestamp(secs);
+    let report = generate_report(definition, addon_name, panic, &date, &time);
+
+    fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!("crash_{date}_{}.txt", time.replace(':', "-")));
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
+
+fn generate_report(
+    definition: &AddonDefinition,
+    addon_name: &str,
+    panic: &str,
+    date: &str,
+    time: &str,
+) -> String {
+    let version = definition.version;
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "{addon_name} {}.{}.{}.{} crash report",
+        version.major, version.minor, version.build, version.revision
+    );
+    let _ = writeln!(report, "Time: {date} {time} UTC");
+    let _ = writeln!(report, "Addon API version: {}", definition.api_version);
+    let game_build = game_build().map_or_else(|| "unknown".into(), |build| build.to_string());
+    let _ = writeln!(report, "Game build: {game_build}");
+
+    let _ = writeln!(report, "\n{panic}");
+    let _ = writeln!(report, "\nBacktrace:\n{}", Backtrace::force_capture());
+
+    let _ = writeln!(report, "Recent log:");
+    if RECORDING.with(Cell::get) {
+        // locking again on the same thread would deadlock
+        let _ = writeln!(report, "unavailable, panicked while recording");
+    } else {
+        for line in RECENT_LOGS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+        {
+            let _ = writeln!(report, "{line}");
+        }
+    }
+    report
+}
+
+/// Returns the game build from the RealTime API, if available.
+#[cfg(feature = "rtapi")]
+fn game_build() -> Option<u32> {
+    crate::rtapi::RealTimeApi::get()
+        .and_then(|rtapi| rtapi.read_game())
+        .map(|game| game.game_build)
+}
+
+/// Returns the game build from the RealTime API, if available.
+#[cfg(not(feature = "rtapi"))]
+fn game_build() -> Option<u32> {
+    None
+}
+
+/// Formats seconds since the Unix epoch as UTC date and time.
+fn format_timestamp(secs: u64) -> (String, String) {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (
+        format!("{year:04}-{month:02}-{day:02}"),
+        format!("{:02}:{:02}:{:02}", rem / 3600, rem % 3600 / 60, rem % 60),
+    )
+}
+
+/// Clears recent log lines and reported locations.
+pub fn reset() {
+    RECENT_LOGS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    REPORTED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(secs: u64) -> String {
+        let (date, time) = format_timestamp(secs);
+        format!("{date} {time}")
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(timestamp(86399), "1970-01-01 23:59:59");
+        assert_eq!(timestamp(86400), "1970-01-02 00:00:00");
+    }
+
+    #[test]
+    fn year_boundary() {
+        assert_eq!(timestamp(946684799), "1999-12-31 23:59:59");
+        assert_eq!(timestamp(946684800), "2000-01-01 00:00:00");
+    }
+
+    #[test]
+    fn leap_years() {
+        assert_eq!(timestamp(951827696), "2000-02-29 12:34:56");
+        assert_eq!(timestamp(951868800), "2000-03-01 00:00:00");
+        assert_eq!(timestamp(1709251199), "2024-02-29 23:59:59");
+
+        // not a leap year, divisible by 100 but not 400
+        assert_eq!(timestamp(4107542399), "2100-02-28 23:59:59");
+        assert_eq!(timestamp(4107542400), "2100-03-01 00:00:00");
+    }
+
+    #[test]
+    fn record_reentrant() {
+        RECORDING.with(|recording| recording.set(true));
+        record_log(LogLevel::Info, "Test", "skipped");
+        RECORDING.with(|recording| recording.set(false));
+        record_log(LogLevel::Info, "Test", "recorded");
+
+        let logs = RECENT_LOGS.lock().unwrap_or_else(PoisonError::into_inner);
+        assert!(!logs.iter().any(|line| line.contains("skipped")));
+        assert!(logs.iter().any(|line| line == "[Info] Test: recorded"));
+    }
+}
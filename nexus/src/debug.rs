@@ -0,0 +1,352 @@
+//! Developer diagnostics.
+//!
+//! Renders an ImGui window listing everything the addon registered through this crate.
+//! Requires the `debug` feature.
+//!
+//! ```no_run
+//! nexus::debug::register_window().revert_on_unload();
+//! ```
+//!
+//! Event hit counts are recorded for subscriptions made with closures or the [`event_subscribe!`](crate::event::event_subscribe) macro.
+//! Raw callbacks not wrapped by the macro are listed without hit counts.
+
+use crate::{
+    globals::addon_name,
+    gui::{register_render_fn, RenderThread, RenderType},
+    keybind::Keybind,
+    registry::{registrations, Registration, RegistrationKind},
+    revertible::Revertible,
+    texture::get_texture,
+    Global,
+};
+use imgui::{CollapsingHeader, Image, Ui, Window};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::CStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+/// Maximum size of texture previews.
+const PREVIEW_SIZE: f32 = 128.0;
+
+/// Whether the window registered via [`register_window`] is open.
+static OPEN: AtomicBool = AtomicBool::new(true);
+
+/// Bound keybind strings.
+static KEYBINDS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Statistics of events subscribed with closures.
+static EVENTS: Mutex<BTreeMap<String, EventEntry>> = Mutex::new(BTreeMap::new());
+
+/// Statistics of events subscribed with raw callbacks, by callback address.
+static CALLBACKS: Mutex<BTreeMap<usize, CallbackEntry>> = Mutex::new(BTreeMap::new());
+
+/// Identifiers of loaded textures.
+static TEXTURES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Statistics of a subscribed event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventStats {
+    /// How often the event was received.
+    pub hits: u64,
+
+    /// Size of the last received payload, if known.
+    ///
+    /// Payload sizes are only known for typed subscriptions, a missing payload has size `0`.
+    pub last_payload_size: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct EventEntry {
+    stats: EventStats,
+    payload_size: Option<usize>,
+}
+
+/// Statistics of a raw callback, shared by all events it is subscribed to.
+#[derive(Debug, Default)]
+struct CallbackEntry {
+    identifiers: BTreeSet<String>,
+    stats: EventStats,
+    payload_size: Option<usize>,
+}
+
+/// Returns the statistics of all events subscribed with closures or the [`event_subscribe!`](crate::event::event_subscribe) macro.
+///
+/// Hits of a raw callback subscribed to multiple events are counted for each of them.
+pub fn event_stats() -> BTreeMap<String, EventStats> {
+    let mut stats: BTreeMap<_, _> = EVENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|(identifier, entry)| (identifier.clone(), entry.stats.clone()))
+        .collect();
+    for entry in CALLBACKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .values()
+        .filter(|entry| entry.stats.hits > 0)
+    {
+        for identifier in &entry.identifiers {
+            let stats = stats.entry(identifier.clone()).or_default();
+            stats.hits += entry.stats.hits;
+            stats.last_payload_size = entry.stats.last_payload_size;
+        }
+    }
+    stats
+}
+
+/// Returns the keybind strings bound to keybind identifiers.
+pub fn keybinds() -> BTreeMap<String, String> {
+    KEYBINDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Returns the identifiers of all loaded textures.
+pub fn textures() -> BTreeSet<String> {
+    TEXTURES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Records the keybind string bound to a keybind identifier.
+pub(crate) fn record_keybind(identifier: &CStr, keybind: String) {
+    KEYBINDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(identifier.to_string_lossy().into_owned(), keybind);
+}
+
+/// Records the [`Keybind`] bound to a keybind identifier.
+pub(crate) fn record_keybind_struct(identifier: &CStr, keybind: &Keybind) {
+    let mut string = String::new();
+    for (modifier, name) in [
+        (keybind.alt, "ALT+"),
+        (keybind.ctrl, "CTRL+"),
+        (keybind.shift, "SHIFT+"),
+    ] {
+        if modifier {
+            string.push_str(name);
+        }
+    }
+    string.push_str(&format!("0x{:X}", keybind.key));
+    record_keybind(identifier, string)
+}
+
+/// Records the payload size of a typed event subscription.
+pub(crate) fn record_event_payload(identifier: &CStr, payload_size: usize) {
+    EVENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(identifier.to_string_lossy().into_owned())
+        .or_default()
+        .payload_size = Some(payload_size);
+}
+
+/// Records a raw callback subscribed to an event.
+pub(crate) fn record_event_callback(identifier: &CStr, callback: usize) {
+    let identifier = identifier.to_string_lossy().into_owned();
+    let payload_size = EVENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&identifier)
+        .and_then(|entry| entry.payload_size);
+    let mut callbacks = CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner);
+    let entry = callbacks.entry(callback).or_default();
+    entry.identifiers.insert(identifier);
+    entry.payload_size = payload_size.or(entry.payload_size);
+}
+
+/// Records an event received by a raw callback.
+///
+/// Called for every received event, so this must not allocate.
+pub(crate) fn record_callback_hit(callback: usize, payload_null: bool) {
+    let mut callbacks = CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(entry) = callbacks.get_mut(&callback) {
+        entry.stats.hits += 1;
+        entry.stats.last_payload_size = if payload_null {
+            Some(0)
+        } else {
+            entry.payload_size
+        };
+    }
+}
+
+/// Records an event received by closures.
+pub(crate) fn record_event(identifier: &str, payload_null: bool) {
+    let mut events = EVENTS.lock().unwrap_or_else(PoisonError::into_inner);
+    let entry = events.entry(identifier.into()).or_default();
+    entry.stats.hits += 1;
+    entry.stats.last_payload_size = if payload_null {
+        Some(0)
+    } else {
+        entry.payload_size
+    };
+}
+
+/// Records a loaded texture.
+pub(crate) fn record_texture(identifier: &CStr) {
+    TEXTURES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(identifier.to_string_lossy().into_owned());
+}
+
+/// Clears all recorded information.
+pub(crate) fn reset() {
+    OPEN.store(true, Ordering::Relaxed);
+    KEYBINDS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    EVENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    CALLBACKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+    TEXTURES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Registers a render callback showing the diagnostics window while it is open.
+///
+/// Returns a [`Revertible`] to revert the register.
+pub fn register_window() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    register_render_fn(RenderType::Render, |_ui, thread| {
+        let mut open = OPEN.load(Ordering::Relaxed);
+        if open {
+            render_window(thread, &mut open);
+            OPEN.store(open, Ordering::Relaxed);
+        }
+    })
+}
+
+/// Returns whether the window registered via [`register_window`] is open.
+#[inline]
+pub fn is_window_open() -> bool {
+    OPEN.load(Ordering::Relaxed)
+}
+
+/// Opens or closes the window registered via [`register_window`].
+#[inline]
+pub fn set_window_open(open: bool) {
+    OPEN.store(open, Ordering::Relaxed)
+}
+
+/// Renders the diagnostics window.
+pub fn render_window(thread: RenderThread, open: &mut bool) {
+    let ui = thread.ui();
+    let title = format!("{} Debug", addon_name().unwrap_or("Addon"));
+    Window::new(title)
+        .opened(open)
+        .build(ui, || render_contents(thread));
+}
+
+/// Renders the diagnostics without a window, for example to embed them in the addon options.
+pub fn render_contents(thread: RenderThread) {
+    let ui = thread.ui();
+    let registrations = registrations();
+    let of_kind = |kind| {
+        registrations
+            .iter()
+            .filter(move |registration| registration.kind == kind)
+    };
+
+    if CollapsingHeader::new("Render callbacks").build(ui) {
+        for render_type in [
+            RenderType::PreRender,
+            RenderType::Render,
+            RenderType::PostRender,
+            RenderType::OptionsRender,
+        ] {
+            let name = format!("{render_type:?}");
+            let (closures, callbacks): (Vec<_>, Vec<_>) = of_kind(RegistrationKind::Render)
+                .filter(|registration| registration.name.as_deref() == Some(&name))
+                .partition(|registration| registration.closure);
+            ui.text(format!(
+                "{name}: {} callbacks, {} closures",
+                callbacks.len(),
+                closures.len()
+            ));
+        }
+        render_list(ui, of_kind(RegistrationKind::WndProc));
+    }
+
+    if CollapsingHeader::new("Keybinds").build(ui) {
+        let keybinds = keybinds();
+        for registration in of_kind(RegistrationKind::Keybind) {
+            let keybind = registration
+                .name
+                .as_ref()
+                .and_then(|name| keybinds.get(name))
+                .map(String::as_str)
+                .unwrap_or("?");
+            ui.text(format!("{registration}: {keybind}"));
+        }
+    }
+
+    if CollapsingHeader::new("Events").build(ui) {
+        let stats = event_stats();
+        for registration in of_kind(RegistrationKind::Event) {
+            match registration.name.as_ref().and_then(|name| stats.get(name)) {
+                Some(stats) => {
+                    let size = match stats.last_payload_size {
+                        Some(size) => format!("{size} bytes"),
+                        None => "unknown".into(),
+                    };
+                    ui.text(format!(
+                        "{registration}: {} hits, last payload {size}",
+                        stats.hits
+                    ));
+                }
+                None => ui.text(registration.to_string()),
+            }
+        }
+    }
+
+    if CollapsingHeader::new("Textures").build(ui) {
+        for identifier in textures() {
+            match get_texture(Global(identifier.as_str())) {
+                Some(texture) => {
+                    ui.text(format!(
+                        "{identifier}: {}x{}",
+                        texture.width, texture.height
+                    ));
+                    if ui.is_item_hovered() {
+                        let factor = PREVIEW_SIZE / texture.width.max(texture.height).max(1) as f32;
+                        ui.tooltip(|| {
+                            Image::new(texture.id(), texture.size_resized(factor.min(1.0)))
+                                .build(ui)
+                        });
+                    }
+                }
+                None => ui.text_disabled(format!("{identifier}: loading")),
+            }
+        }
+    }
+
+    if CollapsingHeader::new("Fonts").build(ui) {
+        render_list(ui, of_kind(RegistrationKind::Font));
+    }
+
+    if CollapsingHeader::new("Quick access").build(ui) {
+        render_list(ui, of_kind(RegistrationKind::QuickAccess));
+        render_list(ui, of_kind(RegistrationKind::QuickAccessContextMenu));
+    }
+}
+
+fn render_list<'a>(ui: &Ui, registrations: impl Iterator<Item = &'a Registration>) {
+    for registration in registrations {
+        ui.text(registration.to_string());
+    }
+}
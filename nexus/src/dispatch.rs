@@ -0,0 +1,263 @@
+//! Dispatch tables for closure callbacks.
+//!
+//! Nexus only accepts plain function pointers as callbacks.
+//! Closures are stored in a table and invoked from a single extern trampoline per key.
+
+use crate::{on_unload, panic};
+use std::{
+    borrow::Borrow,
+    convert::Infallible,
+    mem,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// Shared handle to a stored closure.
+pub type Handle<F> = Arc<Mutex<F>>;
+
+/// Entry in a [`Dispatch`] table.
+struct Entry<K, F: ?Sized> {
+    id: u64,
+    key: K,
+    callback: Handle<F>,
+    disabled: Arc<AtomicBool>,
+}
+
+/// Dispatch table mapping keys to closures.
+pub struct Dispatch<K, F: ?Sized> {
+    entries: Mutex<Vec<Entry<K, F>>>,
+
+    /// Serializes registering and deregistering keys with Nexus.
+    ///
+    /// Held across the calls into Nexus instead of the entry lock,
+    /// so trampolines invoked synchronously by Nexus can still access the entries.
+    registration: Mutex<()>,
+
+    next_id: AtomicU64,
+    cleanup: AtomicBool,
+}
+
+impl<K, F> Dispatch<K, F>
+where
+    K: PartialEq + Clone + Send + 'static,
+    F: ?Sized + Send + 'static,
+{
+    /// Creates a new empty dispatch table.
+    pub const fn new() -> Self {
+        Self {
+            entries: Mutex::new(Vec::new()),
+            registration: Mutex::new(()),
+            next_id: AtomicU64::new(0),
+            cleanup: AtomicBool::new(false),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Entry<K, F>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_registration(&self) -> MutexGuard<'_, ()> {
+        self.registration
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Inserts a new closure for the key.
+    ///
+    /// The `register` function is invoked if this is the first closure for the key.
+    /// The `deregister` function is invoked for remaining keys on addon unload.
+    /// Both must not insert into or remove from the same table.
+    ///
+    /// Returns the id of the entry.
+    pub fn insert(
+        &'static self,
+        key: K,
+        callback: Handle<F>,
+        register: impl FnOnce(&K),
+        deregister: fn(&K),
+    ) -> u64 {
+        let result = self.try_insert(
+            key,
+            callback,
+            |key| {
+                register(key);
+                Ok::<_, Infallible>(())
+            },
+            deregister,
+        );
+        match result {
+            Ok(id) => id,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Inserts a new closure for the key with a fallible `register` function.
+    ///
+    /// The closure is removed again if `register` fails.
+    /// See [`Dispatch::insert`].
+    pub fn try_insert<E>(
+        &'static self,
+        key: K,
+        callback: Handle<F>,
+        register: impl FnOnce(&K) -> Result<(), E>,
+        deregister: fn(&K),
+    ) -> Result<u64, E> {
+        let _registration = self.lock_registration();
+        self.insert_registered(key, callback, register, deregister)
+    }
+
+    /// Inserts a new closure for the key, replacing all present closures for the key.
+    ///
+    /// See [`Dispatch::insert`].
+    pub fn replace(
+        &'static self,
+        key: K,
+        callback: Handle<F>,
+        register: impl FnOnce(&K),
+        deregister: fn(&K),
+    ) -> u64 {
+        let _registration = self.lock_registration();
+        self.lock().retain(|entry| entry.key != key);
+        let result = self.insert_registered(
+            key,
+            callback,
+            |key| {
+                register(key);
+                Ok::<_, Infallible>(())
+            },
+            deregister,
+        );
+        match result {
+            Ok(id) => id,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Inserts a new closure while the registration lock is held.
+    fn insert_registered<E>(
+        &'static self,
+        key: K,
+        callback: Handle<F>,
+        register: impl FnOnce(&K) -> Result<(), E>,
+        deregister: fn(&K),
+    ) -> Result<u64, E> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let is_first = {
+            let mut entries = self.lock();
+            let is_first = !entries.iter().any(|entry| entry.key == key);
+            entries.push(Entry {
+                id,
+                key: key.clone(),
+                callback,
+                disabled: Arc::new(AtomicBool::new(false)),
+            });
+            is_first
+        };
+        if !self.cleanup.swap(true, Ordering::Relaxed) {
+            on_unload(move || {
+                let _registration = self.lock_registration();
+                self.cleanup.store(false, Ordering::Relaxed);
+                for key in self.clear() {
+                    deregister(&key)
+                }
+            });
+        }
+        if is_first {
+            if let Err(err) = register(&key) {
+                self.lock().retain(|entry| entry.id != id);
+                return Err(err);
+            }
+        }
+        Ok(id)
+    }
+
+    /// Removes the entry with the given id.
+    ///
+    /// The `deregister` function is invoked if this was the last closure for the key.
+    pub fn remove(&self, id: u64, deregister: impl FnOnce(&K)) {
+        let _registration = self.lock_registration();
+        let removed = {
+            let mut entries = self.lock();
+            entries
+                .iter()
+                .position(|entry| entry.id == id)
+                .map(|index| entries.remove(index).key)
+                .filter(|key| !entries.iter().any(|entry| entry.key == *key))
+        };
+        if let Some(key) = removed {
+            deregister(&key);
+        }
+    }
+
+    /// Removes all closures for the key and deregisters it.
+    ///
+    /// Reverts the registration of the trampoline for the key, if still present.
+    pub fn remove_all(&self, key: &K, deregister: impl FnOnce(&K)) {
+        let _registration = self.lock_registration();
+        let removed = {
+            let mut entries = self.lock();
+            let len = entries.len();
+            entries.retain(|entry| entry.key != *key);
+            entries.len() != len
+        };
+        if removed {
+            deregister(key);
+        }
+    }
+
+    /// Removes all closures for the key.
+    pub fn remove_key<Q>(&self, key: &Q) -> Vec<Handle<F>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + PartialEq,
+    {
+        let mut removed = Vec::new();
+        self.lock().retain(|entry| {
+            let matches = entry.key.borrow() == key;
+            if matches {
+                removed.push(entry.callback.clone());
+            }
+            !matches
+        });
+        removed
+    }
+
+    /// Invokes the closures for the key, catching panics.
+    ///
+    /// The closures are cloned out of the table, so they can register or remove other closures.
+    pub fn call<Q>(&self, key: &Q, kind: &str, mut invoke: impl FnMut(&mut F))
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + PartialEq,
+    {
+        let entries: Vec<_> = self
+            .lock()
+            .iter()
+            .filter(|entry| entry.key.borrow() == key)
+            .map(|entry| (entry.callback.clone(), entry.disabled.clone()))
+            .collect();
+        for (callback, disabled) in entries {
+            panic::catch(kind, &disabled, || invoke(&mut lock(&callback)));
+        }
+    }
+
+    /// Removes all closures, returning the previously present keys.
+    pub fn clear(&self) -> Vec<K> {
+        let entries = mem::take(&mut *self.lock());
+        let mut keys = Vec::new();
+        for entry in entries {
+            if !keys.contains(&entry.key) {
+                keys.push(entry.key);
+            }
+        }
+        keys
+    }
+}
+
+/// Locks the closure handle, recovering from poisoning.
+#[inline]
+pub fn lock<F: ?Sized>(handle: &Handle<F>) -> MutexGuard<'_, F> {
+    handle.lock().unwrap_or_else(PoisonError::into_inner)
+}
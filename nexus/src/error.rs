@@ -0,0 +1,98 @@
+//! Errors.
+
+use std::{ffi::NulError, fmt};
+
+/// Error returned by fallible functions of this crate.
+///
+/// Functions passing strings to Nexus, using optional Addon API functions, subscribing closures, submitting tasks or parsing filters have a `try_` variant returning an [`Error`].
+/// The variants without prefix are conveniences panicking on error instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The Addon API is not initialized, before load or after unload.
+    NotInitialized,
+
+    /// A string contains an interior nul byte and can not be passed to Nexus.
+    Nul {
+        /// Description of the string.
+        what: &'static str,
+
+        /// Position of the nul byte.
+        position: usize,
+    },
+
+    /// A string or path is not valid UTF-8.
+    NonUtf8 {
+        /// Description of the string or path.
+        what: &'static str,
+    },
+
+    /// A task queue is full and can not accept further tasks.
+    QueueFull {
+        /// Maximum number of pending tasks.
+        capacity: usize,
+    },
+
+    /// A task queue does not accept tasks while the addon unloads.
+    QueueClosed,
+
+    /// Closures are subscribed to too many distinct events at the same time.
+    TooManySubscriptions {
+        /// Maximum number of distinct events.
+        capacity: usize,
+    },
+
+    /// A function is missing in the Addon API revision passed by the Loader.
+    Unsupported {
+        /// Description of the function.
+        what: &'static str,
+
+        /// Addon API version passed by the Loader.
+        version: i32,
+    },
+
+    /// Log filter directives are invalid.
+    #[cfg(feature = "log_filter")]
+    LogFilter {
+        /// Reason the directives were rejected.
+        reason: String,
+    },
+}
+
+impl Error {
+    /// Creates a new error for a string containing an interior nul byte.
+    #[inline]
+    pub(crate) fn nul(err: NulError, what: &'static str) -> Self {
+        Self::Nul {
+            what,
+            position: err.nul_position(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInitialized => write!(f, "addon api not initialized"),
+            Self::Nul { what, position } => {
+                write!(f, "{what} contains a nul byte at position {position}")
+            }
+            Self::NonUtf8 { what } => write!(f, "{what} is not valid UTF-8"),
+            Self::QueueFull { capacity } => {
+                write!(f, "task queue is full with {capacity} pending tasks")
+            }
+            Self::QueueClosed => write!(f, "task queue is closed during addon unload"),
+            Self::TooManySubscriptions { capacity } => write!(
+                f,
+                "closures are subscribed to more than {capacity} distinct events"
+            ),
+            Self::Unsupported { what, version } => {
+                write!(f, "{what} not supported by Addon API version {version}")
+            }
+            #[cfg(feature = "log_filter")]
+            Self::LogFilter { reason } => write!(f, "invalid log filter: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
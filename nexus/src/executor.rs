@@ -0,0 +1,453 @@
+//! Background task execution bound to the addon lifecycle.
+//!
+//! Threads spawned by an addon must not outlive it, otherwise they crash the game after a hot-unload.
+//! Tasks spawned via this module are cancelled and joined on addon unload.
+//! Unloading blocks until all blocking tasks finished, see [`spawn_blocking`].
+//!
+//! ```no_run
+//! use nexus::executor::{spawn, spawn_blocking, spawn_local_render};
+//!
+//! let contents = spawn_blocking(|| std::fs::read_to_string("settings.json").ok());
+//!
+//! spawn(async move {
+//!     if let Some(Some(contents)) = contents.await {
+//!         spawn_local_render(async move {
+//!             // polled on the render thread
+//!             let _ = contents;
+//!         });
+//!     }
+//! });
+//! ```
+
+use crate::{
+    globals::addon_name,
+    log::{try_log, LogLevel},
+    panic::{catch, is_addon_disabled},
+    queue::MainThreadQueue,
+};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    mem,
+    panic::Location,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, PoisonError, TryLockError,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+/// Number of worker threads polling spawned futures.
+pub const WORKER_COUNT: usize = 2;
+
+/// State of the executor.
+static STATE: Mutex<State> = Mutex::new(State::new());
+
+/// Signals workers about new tasks or shutdown.
+static CONDVAR: Condvar = Condvar::new();
+
+/// Whether the executor is shutting down.
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Id of the next task.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Queue polling tasks spawned via [`spawn_local_render`].
+static RENDER_QUEUE: MainThreadQueue = MainThreadQueue::new(usize::MAX);
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+struct State {
+    queue: VecDeque<Arc<Task>>,
+    tasks: Vec<Arc<Task>>,
+    workers: Vec<thread::JoinHandle<()>>,
+    blocking: Vec<Blocking>,
+    shutdown: bool,
+}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            tasks: Vec::new(),
+            workers: Vec::new(),
+            blocking: Vec::new(),
+            shutdown: false,
+        }
+    }
+}
+
+/// Thread running a blocking task.
+struct Blocking {
+    handle: thread::JoinHandle<()>,
+    location: &'static Location<'static>,
+}
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Spawned future.
+struct Task {
+    id: u64,
+    future: Mutex<Option<BoxFuture>>,
+    cancelled: AtomicBool,
+    disabled: AtomicBool,
+    schedule: fn(Arc<Task>),
+}
+
+impl Task {
+    fn poll(self: &Arc<Self>) {
+        let mut slot = self.future.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(future) = slot.as_mut() else {
+            return;
+        };
+        let waker = Waker::from(self.clone());
+        let mut cx = Context::from_waker(&waker);
+        let result = catch("async task", &self.disabled, || {
+            future.as_mut().poll(&mut cx)
+        });
+        if !matches!(result, Some(Poll::Pending)) {
+            let future = slot.take();
+            drop(slot);
+            drop(future);
+            self.remove();
+            return;
+        }
+        drop(slot);
+
+        // cancelled while being polled
+        if self.cancelled.load(Ordering::Acquire) {
+            self.drop_future();
+        }
+    }
+
+    /// Cancels the task without waiting for a poll in progress.
+    ///
+    /// The future is dropped by the poll instead, so a task may cancel itself.
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        let slot = match self.future.try_lock() {
+            Ok(slot) => Some(slot),
+            Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        if let Some(mut slot) = slot {
+            let future = slot.take();
+            drop(slot);
+            drop(future);
+        }
+        self.remove();
+    }
+
+    fn drop_future(&self) {
+        let future = self
+            .future
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        drop(future);
+        self.remove();
+    }
+
+    fn remove(&self) {
+        lock().tasks.retain(|task| task.id != self.id);
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        (self.schedule)(self)
+    }
+}
+
+/// Output shared between a task and its [`JoinHandle`].
+struct Join<T> {
+    output: Option<T>,
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+/// Marks the task as finished when the task completes, panics or is cancelled.
+struct Finish<T>(Arc<Mutex<Join<T>>>);
+
+impl<T> Finish<T> {
+    fn complete(&self, output: T) {
+        lock_join(&self.0).output = Some(output);
+    }
+}
+
+impl<T> Drop for Finish<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut join = lock_join(&self.0);
+            join.finished = true;
+            join.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake()
+        }
+    }
+}
+
+fn lock_join<T>(join: &Mutex<Join<T>>) -> MutexGuard<'_, Join<T>> {
+    join.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Handle to a spawned task.
+///
+/// Awaiting the handle returns the output of the task, or [`None`] if the task panicked or was cancelled.
+/// Dropping the handle detaches the task.
+#[must_use]
+pub struct JoinHandle<T> {
+    join: Arc<Mutex<Join<T>>>,
+    task: Option<Arc<Task>>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Checks whether the task finished.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        lock_join(&self.join).finished
+    }
+
+    /// Cancels the task, dropping its future.
+    ///
+    /// A task currently being polled is dropped once the poll returns.
+    /// Blocking tasks can not be cancelled, see [`is_cancelled`].
+    #[inline]
+    pub fn cancel(&self) {
+        if let Some(task) = &self.task {
+            task.cancel()
+        }
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut join = lock_join(&self.join);
+        if join.finished {
+            Poll::Ready(join.output.take())
+        } else {
+            join.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Spawns a future polled on the worker threads.
+///
+/// Returns a [`JoinHandle`] to await or cancel the task.
+pub fn spawn<T>(future: impl Future<Output = T> + Send + 'static) -> JoinHandle<T>
+where
+    T: Send + 'static,
+{
+    ensure_workers();
+    spawn_task(future, schedule_worker)
+}
+
+/// Spawns a future polled on the render thread, at the start of a frame.
+///
+/// Returns a [`JoinHandle`] to await or cancel the task.
+pub fn spawn_local_render<T>(future: impl Future<Output = T> + Send + 'static) -> JoinHandle<T>
+where
+    T: Send + 'static,
+{
+    spawn_task(future, schedule_render)
+}
+
+/// Runs a blocking closure on a separate thread.
+///
+/// Returns a [`JoinHandle`] to await the result.
+///
+/// # Unload
+/// **Unloading the addon blocks until every blocking task returned, without any timeout.**
+/// Threads must not outlive the addon, so a closure that never returns hangs the game on unload.
+/// Blocking tasks can not be cancelled, long running closures must check [`is_cancelled`] periodically and return early.
+/// Every blocking task still running on unload is logged with the location it was spawned at.
+#[track_caller]
+pub fn spawn_blocking<T>(f: impl FnOnce() -> T + Send + 'static) -> JoinHandle<T>
+where
+    T: Send + 'static,
+{
+    let join = Arc::new(Mutex::new(Join {
+        output: None,
+        finished: false,
+        waker: None,
+    }));
+    let finish = Finish(join.clone());
+    let mut state = lock();
+    if state.shutdown {
+        drop(state);
+        drop(finish);
+    } else {
+        state
+            .blocking
+            .retain(|blocking| !blocking.handle.is_finished());
+        let handle = thread::Builder::new()
+            .name(thread_name("blocking"))
+            .spawn(move || {
+                // the closure runs once, a panic finishes the task regardless of the policy
+                let disabled = AtomicBool::new(false);
+                if let Some(output) = catch("blocking task", &disabled, f) {
+                    finish.complete(output)
+                }
+            })
+            .expect("failed to spawn blocking thread");
+        state.blocking.push(Blocking {
+            handle,
+            location: Location::caller(),
+        });
+    }
+    JoinHandle { join, task: None }
+}
+
+/// Checks whether spawned tasks are being cancelled due to addon unload.
+///
+/// This is also the case once the addon is disabled by [`PanicPolicy::DisableAddon`](crate::panic::PanicPolicy::DisableAddon).
+#[inline]
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::Acquire) || is_addon_disabled()
+}
+
+fn spawn_task<T>(
+    future: impl Future<Output = T> + Send + 'static,
+    schedule: fn(Arc<Task>),
+) -> JoinHandle<T>
+where
+    T: Send + 'static,
+{
+    let join = Arc::new(Mutex::new(Join {
+        output: None,
+        finished: false,
+        waker: None,
+    }));
+    let finish = Finish(join.clone());
+    let future = async move {
+        let output = future.await;
+        finish.complete(output);
+    };
+    let task = Arc::new(Task {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        future: Mutex::new(Some(Box::pin(future))),
+        cancelled: AtomicBool::new(false),
+        disabled: AtomicBool::new(false),
+        schedule,
+    });
+    {
+        let mut state = lock();
+        if state.shutdown {
+            drop(state);
+            task.cancel();
+            return JoinHandle { join, task: None };
+        }
+        state.tasks.push(task.clone());
+    }
+    schedule(task.clone());
+    JoinHandle {
+        join,
+        task: Some(task),
+    }
+}
+
+fn schedule_worker(task: Arc<Task>) {
+    let mut state = lock();
+    if !state.shutdown {
+        state.queue.push_back(task);
+        CONDVAR.notify_one();
+    }
+}
+
+fn schedule_render(task: Arc<Task>) {
+    if !lock().shutdown {
+        // rejected once the addon unloads, the task is cancelled by the shutdown
+        let _ = RENDER_QUEUE.try_push(move |_ui, _thread| task.poll());
+    }
+}
+
+fn ensure_workers() {
+    let mut state = lock();
+    if state.workers.is_empty() && !state.shutdown {
+        for _ in 0..WORKER_COUNT {
+            let handle = thread::Builder::new()
+                .name(thread_name("worker"))
+                .spawn(work)
+                .expect("failed to spawn worker thread");
+            state.workers.push(handle);
+        }
+    }
+}
+
+fn work() {
+    loop {
+        let task = {
+            let mut state = lock();
+            loop {
+                if state.shutdown {
+                    return;
+                }
+                if let Some(task) = state.queue.pop_front() {
+                    break task;
+                }
+                state = CONDVAR.wait(state).unwrap_or_else(PoisonError::into_inner);
+            }
+        };
+        task.poll();
+    }
+}
+
+fn thread_name(kind: &str) -> String {
+    format!("{} {kind}", addon_name().unwrap_or("addon"))
+}
+
+/// Cancels all tasks and joins the threads of the executor.
+///
+/// Tasks spawned afterwards are cancelled immediately, until [`reset`] is called.
+pub(crate) fn shutdown() {
+    CANCELLED.store(true, Ordering::Release);
+    let (tasks, workers, blocking) = {
+        let mut state = lock();
+        state.shutdown = true;
+        state.queue.clear();
+        CONDVAR.notify_all();
+        (
+            mem::take(&mut state.tasks),
+            mem::take(&mut state.workers),
+            mem::take(&mut state.blocking),
+        )
+    };
+
+    // tasks being polled are dropped by the worker once the poll returns
+    for task in tasks {
+        task.cancel();
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    // blocking tasks can not be cancelled and are waited for without timeout
+    for Blocking { handle, location } in blocking {
+        if !handle.is_finished() {
+            if let Some(addon_name) = addon_name() {
+                let _ = try_log(
+                    LogLevel::Warning,
+                    addon_name,
+                    format!("waiting for blocking task spawned at {location} to finish"),
+                );
+            }
+        }
+        let _ = handle.join();
+    }
+
+    RENDER_QUEUE.clear();
+}
+
+/// Allows spawning tasks again after [`shutdown`].
+pub(crate) fn reset() {
+    lock().shutdown = false;
+    CANCELLED.store(false, Ordering::Release);
+}
@@ -1,57 +1,111 @@
 use crate::{
-    api::AddonApi,
-    log::{log, LogLevel},
+    addon::AddonDefinition,
+    api::{compat::RawAddonApi, AddonApi},
+    crash,
+    gui::RenderThread,
+    log::{try_log, LogLevel},
 };
 use std::{
-    fmt, mem, panic, ptr,
-    sync::{Mutex, OnceLock},
+    mem::{self, ManuallyDrop},
+    panic, ptr,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, Ordering},
+        Mutex, PoisonError, RwLock,
+    },
 };
 
 #[cfg(feature = "log")]
 use crate::logger::NexusLogger;
 
-static ADDON_API: OnceLock<&'static AddonApi> = OnceLock::new();
+static RAW_ADDON_API: RwLock<Option<RawAddonApi>> = RwLock::new(None);
 
-static IMGUI_CTX: OnceLock<ContextWrapper> = OnceLock::new();
+static ADDON_API: AtomicPtr<AddonApi> = AtomicPtr::new(ptr::null_mut());
 
-static IMGUI_UI: OnceLock<UiWrapper> = OnceLock::new();
+static ADDON_NAME: RwLock<Option<&'static str>> = RwLock::new(None);
+
+/// ImGui context, owned by Nexus.
+static IMGUI_CTX: AtomicPtr<ManuallyDrop<imgui::Context>> = AtomicPtr::new(ptr::null_mut());
+
+/// ImGui frame handle, never ended by the addon.
+static IMGUI_UI: AtomicPtr<ManuallyDrop<imgui::Ui<'static>>> = AtomicPtr::new(ptr::null_mut());
+
+/// Whether the addon is currently unloading.
+static UNLOADING: AtomicBool = AtomicBool::new(false);
+
+/// Restores the panic hook present before initialization.
+static RESTORE_PANIC_HOOK: Mutex<Option<Box<dyn FnOnce() + Send>>> = Mutex::new(None);
 
 /// Initializes globals.
 ///
-/// Any calls after the initial one will result in a panic.
+/// Calling this again before [`deinit`] will result in a panic.
 /// A call to this is inserted automatically by the [`export`](crate::export) macro.
 ///
+/// Older Addon API revisions are normalized into the current [`AddonApi`] layout.
+/// Panics write a crash report into the addon directory.
+///
 /// # Safety
-/// The passed pointer must be a valid [`AddonApi`] with `'static` lifetime.
+/// The passed pointer must be a valid Addon API struct of the version requested in the definition,
+/// which stays valid until [`deinit`].
 pub unsafe fn init(
     api: *const AddonApi,
+    definition: &'static AddonDefinition,
     addon_name: &'static str,
     _log_filter: Option<&'static str>,
 ) {
-    let api = api.as_ref().expect("no addon api supplied");
-    ADDON_API
-        .set(api)
-        .expect("addon api initialized multiple times");
+    assert!(!api.is_null(), "no addon api supplied");
+    let api_version = definition.api_version;
+    let raw = RawAddonApi::from_ptr(api.cast(), api_version)
+        .unwrap_or_else(|| panic!("unsupported addon api version {api_version}"));
+    {
+        let mut guard = RAW_ADDON_API
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        assert!(guard.is_none(), "addon api initialized multiple times");
+        *guard = Some(raw);
+    }
+    let api = match raw {
+        RawAddonApi::V6(api) => (api as *const AddonApi).cast_mut(),
+        _ => Box::into_raw(Box::new(raw.normalize())),
+    };
+    ADDON_API.store(api, Ordering::Release);
+    *ADDON_NAME.write().unwrap_or_else(PoisonError::into_inner) = Some(addon_name);
 
-    // panic hook
+    // panic hook, previous hook is restored on unload
+    // panics caught by callback wrappers are logged by the wrapper
+    let previous_hook = panic::take_hook();
     panic::set_hook(Box::new(move |info| {
-        log(LogLevel::Critical, addon_name, info.to_string())
+        let message = info.to_string();
+        if !crate::panic::record_panic(info.location()) {
+            let _ = try_log(LogLevel::Critical, addon_name, &message);
+        }
+        let location = info.location().map(ToString::to_string);
+        if let Some(path) = crash::write_report(definition, addon_name, &message, location) {
+            let _ = try_log(
+                LogLevel::Critical,
+                addon_name,
+                format!("crash report written to {}", path.display()),
+            );
+        }
     }));
+    *RESTORE_PANIC_HOOK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) =
+        Some(Box::new(move || panic::set_hook(previous_hook)));
 
     // init logger
     #[cfg(feature = "log")]
     NexusLogger::set_logger(addon_name, _log_filter);
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    crate::log::start_sink();
 
     // setup imgui
+    let api = &*api;
     imgui::sys::igSetCurrentContext(api.imgui_context);
     imgui::sys::igSetAllocatorFunctions(api.imgui_malloc, api.imgui_free, ptr::null_mut());
-    IMGUI_CTX
-        .set(imgui::Context::current().into())
-        .expect("imgui context initialized multiple times");
-    let ctx = &IMGUI_CTX.get().unwrap_unchecked().0;
-    IMGUI_UI
-        .set(imgui::Ui::from_ctx(ctx).into())
-        .expect("imgui ui initialized multiple times");
+    let ctx = Box::into_raw(Box::new(ManuallyDrop::new(imgui::Context::current())));
+    IMGUI_CTX.store(ctx, Ordering::Release);
+    let ui = Box::into_raw(Box::new(ManuallyDrop::new(imgui::Ui::from_ctx(&*ctx))));
+    IMGUI_UI.store(ui, Ordering::Release);
 }
 
 /// Actions to be performed on addon unload.
@@ -65,76 +119,153 @@ pub fn on_unload(action: impl FnOnce() + Send + 'static) {
 
 /// Cleans up during addon unload.
 ///
+/// Resets all globals, allowing [`init`] to be called again when the addon is reloaded.
 /// A call to this is inserted automatically by the [`export`](crate::export) macro.
 ///
 /// # Safety
 /// This may perform not thread-safe operations and leave globals in an invalid state.
+/// References to the [`AddonApi`] or [`imgui::Ui`] must not be used afterwards.
 pub unsafe fn deinit() {
-    // perform stored unload actions
-    let mut guard = UNLOAD_ACTIONS.lock().unwrap();
-    let vec: Vec<_> = mem::take(&mut guard);
-    for action in vec {
-        action();
+    UNLOADING.store(true, Ordering::Release);
+
+    // cancel and join background tasks first, they may register callbacks
+    crate::executor::shutdown();
+
+    // perform stored unload actions, including ones added by other actions
+    loop {
+        let vec: Vec<_> = mem::take(&mut *UNLOAD_ACTIONS.lock().unwrap());
+        if vec.is_empty() {
+            break;
+        }
+        for action in vec {
+            // a panicking action must not skip the remaining cleanup
+            crate::panic::catch_lifecycle("unload action", action);
+        }
+    }
+
+    // revert registrations leaked by the addon
+    crate::registry::revert_leaked();
+
+    // drop addon state
+    crate::state::deinit();
+
+    // allow spawning tasks again
+    crate::executor::reset();
+
+    // flush queued log records and reset logger
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    crate::log::shutdown_sink();
+    #[cfg(feature = "log")]
+    NexusLogger::unset_logger();
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    crate::log::reset_channels();
+
+    // reset diagnostics
+    #[cfg(feature = "debug")]
+    crate::debug::reset();
+
+    // reset fallbacks for older revisions
+    crate::api::compat::reset();
+
+    // reset identifier namespace
+    crate::identifier::reset();
+
+    // reset panic policy and crash reports
+    crate::panic::reset();
+    crash::reset();
+
+    // reset imgui, context and frame are owned by Nexus
+    let ui = IMGUI_UI.swap(ptr::null_mut(), Ordering::AcqRel);
+    if !ui.is_null() {
+        drop(Box::from_raw(ui));
+    }
+    let ctx = IMGUI_CTX.swap(ptr::null_mut(), Ordering::AcqRel);
+    if !ctx.is_null() {
+        drop(Box::from_raw(ctx));
     }
+
+    // reset addon api
+    let raw = RAW_ADDON_API
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    let api = ADDON_API.swap(ptr::null_mut(), Ordering::AcqRel);
+    ADDON_NAME
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if !api.is_null() && !matches!(raw, Some(RawAddonApi::V6(_))) {
+        // normalized api is owned by us
+        drop(Box::from_raw(api));
+    }
+
+    // restore panic hook
+    let restore_hook = RESTORE_PANIC_HOOK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if let Some(restore_hook) = restore_hook {
+        restore_hook();
+    }
+
+    UNLOADING.store(false, Ordering::Release);
+}
+
+/// Checks whether the addon is currently unloading.
+#[inline]
+pub(crate) fn is_unloading() -> bool {
+    UNLOADING.load(Ordering::Acquire)
 }
 
 /// Returns the Nexus [`AddonApi`] instance.
 ///
 /// Panics if called before initialization.
 #[inline]
+#[track_caller]
 pub fn addon_api() -> &'static AddonApi {
-    ADDON_API.get().expect("addon api not initialized")
+    let api = ADDON_API.load(Ordering::Acquire);
+    assert!(!api.is_null(), "addon api not initialized");
+    unsafe { &*api }
 }
 
-/// Returns an [`imgui::Ui`] for rendering a frame.
-///
-/// # Safety
-/// It is not safe to share [`imgui::Ui`] between threads.
+/// Returns the Nexus [`AddonApi`] instance, if initialized.
 #[inline]
-pub unsafe fn ui() -> &'static imgui::Ui<'static> {
-    &IMGUI_UI.get().expect("imgui not initialized").0
+pub fn try_addon_api() -> Option<&'static AddonApi> {
+    unsafe { ADDON_API.load(Ordering::Acquire).as_ref() }
 }
 
-/// Helper to store [`imgui::Ui`] as a global
-#[repr(transparent)]
-struct UiWrapper(pub imgui::Ui<'static>);
-
-impl From<imgui::Ui<'static>> for UiWrapper {
-    #[inline]
-    fn from(ui: imgui::Ui<'static>) -> Self {
-        Self(ui)
-    }
+/// Returns the name of the addon.
+#[inline]
+pub fn addon_name() -> Option<&'static str> {
+    *ADDON_NAME.read().unwrap_or_else(PoisonError::into_inner)
 }
 
-impl fmt::Debug for UiWrapper {
-    #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
-    }
+/// Returns the Addon API in the revision passed by the Loader.
+#[inline]
+pub fn raw_addon_api() -> Option<RawAddonApi> {
+    *RAW_ADDON_API.read().unwrap_or_else(PoisonError::into_inner)
 }
 
-unsafe impl Send for UiWrapper {}
-
-unsafe impl Sync for UiWrapper {}
-
-/// Helper to store [`imgui::Context`] as a global.
-#[repr(transparent)]
-struct ContextWrapper(pub imgui::Context);
-
-impl From<imgui::Context> for ContextWrapper {
-    #[inline]
-    fn from(ctx: imgui::Context) -> Self {
-        Self(ctx)
-    }
+/// Returns an [`imgui::Ui`] for rendering a frame.
+///
+/// Requires a [`RenderThread`] token proving the current thread is the render thread.
+/// Panics if called outside of a render callback.
+/// The returned reference must not be used after addon unload.
+#[inline]
+#[track_caller]
+pub fn ui(_thread: RenderThread) -> &'static imgui::Ui<'static> {
+    crate::gui::assert_rendering();
+    unsafe { ui_unchecked() }
 }
 
-impl fmt::Debug for ContextWrapper {
-    #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.0.fmt(f)
-    }
+/// Returns an [`imgui::Ui`] for rendering a frame, without requiring a [`RenderThread`] token.
+///
+/// # Safety
+/// It is not safe to share [`imgui::Ui`] between threads.
+/// The returned reference is invalidated on addon unload.
+#[inline]
+pub(crate) unsafe fn ui_unchecked() -> &'static imgui::Ui<'static> {
+    let ui = IMGUI_UI.load(Ordering::Acquire);
+    assert!(!ui.is_null(), "imgui not initialized");
+    &*ui
 }
-
-unsafe impl Send for ContextWrapper {}
-
-unsafe impl Sync for ContextWrapper {}
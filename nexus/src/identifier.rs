@@ -0,0 +1,383 @@
+//! Identifiers passed to Nexus.
+//!
+//! Nexus identifiers share a single namespace across all addons.
+//! When namespacing is enabled via the `namespace` field of the [`export`](crate::export) macro,
+//! identifiers are prefixed with the addon name, for example `"MY_ICON"` becomes `"My Addon::MY_ICON"`.
+//! Identifiers shared with Nexus or other addons can be exempted via [`Global`].
+
+use crate::Error;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    ffi::{CStr, CString, NulError},
+    fmt,
+    ops::Deref,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+/// Separator between namespace and identifier.
+const SEPARATOR: &str = "::";
+
+/// Namespace prefixed to identifiers, if enabled.
+static NAMESPACE: RwLock<Option<&'static str>> = RwLock::new(None);
+
+/// Maximum number of cached namespaced identifiers.
+///
+/// Bounds the memory used by addons passing many distinct dynamic identifiers.
+const NAMESPACED_CAPACITY: usize = 256;
+
+/// Namespaced identifiers, to avoid allocating on every call.
+static NAMESPACED: RwLock<BTreeMap<CString, Arc<CStr>>> = RwLock::new(BTreeMap::new());
+
+/// Identifier passed to Nexus.
+///
+/// Identifiers created from static C strings, usually via the [`c_ident`](crate::c_ident) macro, are passed to Nexus without allocation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identifier<'a> {
+    inner: Cow<'a, CStr>,
+}
+
+impl Identifier<'static> {
+    /// Creates a new identifier from a static C string.
+    #[inline]
+    pub const fn from_static(string: &'static CStr) -> Self {
+        Self {
+            inner: Cow::Borrowed(string),
+        }
+    }
+
+    /// Creates a new identifier from a string.
+    ///
+    /// This allocates once, the identifier can then be reused without allocation.
+    #[inline]
+    pub fn new(string: impl Into<String>) -> Result<Self, Error> {
+        CString::new(string.into())
+            .map(|string| Self {
+                inner: Cow::Owned(string),
+            })
+            .map_err(|err| Error::nul(err, "identifier"))
+    }
+}
+
+impl<'a> Identifier<'a> {
+    /// Returns the identifier as C string.
+    #[inline]
+    pub fn as_c_str(&self) -> &CStr {
+        &self.inner
+    }
+
+    /// Returns the identifier as string, if it is valid UTF-8.
+    #[inline]
+    pub fn to_str(&self) -> Option<&str> {
+        self.inner.to_str().ok()
+    }
+
+    /// Converts the identifier into an owned identifier.
+    #[inline]
+    pub fn into_owned(self) -> Identifier<'static> {
+        Identifier {
+            inner: Cow::Owned(self.inner.into_owned()),
+        }
+    }
+}
+
+impl Deref for Identifier<'_> {
+    type Target = CStr;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_c_str()
+    }
+}
+
+impl AsRef<CStr> for Identifier<'_> {
+    #[inline]
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl From<Identifier<'_>> for CString {
+    #[inline]
+    fn from(identifier: Identifier<'_>) -> Self {
+        identifier.inner.into_owned()
+    }
+}
+
+impl fmt::Display for Identifier<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner.to_string_lossy(), f)
+    }
+}
+
+/// Conversion into a C string passed to Nexus.
+///
+/// C strings are passed as is, Rust strings are copied into a new allocation.
+pub trait IntoCStr {
+    /// Resulting C string.
+    type Output: Deref<Target = CStr> + Into<CString>;
+
+    /// Converts into a C string.
+    ///
+    /// Returns an error if the string contains an interior nul byte.
+    fn into_c_str(self) -> Result<Self::Output, NulError>;
+
+    /// Whether the identifier is exempt from namespacing.
+    #[inline]
+    fn is_global(&self) -> bool {
+        false
+    }
+}
+
+impl<'a> IntoCStr for Identifier<'a> {
+    type Output = Self;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self)
+    }
+}
+
+impl<'a> IntoCStr for &'a Identifier<'_> {
+    type Output = &'a CStr;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self.as_c_str())
+    }
+}
+
+impl<'a> IntoCStr for &'a CStr {
+    type Output = Self;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self)
+    }
+}
+
+impl IntoCStr for CString {
+    type Output = Self;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self)
+    }
+}
+
+impl<'a> IntoCStr for &'a CString {
+    type Output = &'a CStr;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self.as_c_str())
+    }
+}
+
+impl<'a> IntoCStr for Cow<'a, CStr> {
+    type Output = Self;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        Ok(self)
+    }
+}
+
+impl IntoCStr for &str {
+    type Output = CString;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        CString::new(self)
+    }
+}
+
+impl IntoCStr for String {
+    type Output = CString;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        CString::new(self)
+    }
+}
+
+impl IntoCStr for &String {
+    type Output = CString;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        CString::new(self.as_str())
+    }
+}
+
+impl IntoCStr for Cow<'_, str> {
+    type Output = CString;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        CString::new(self.into_owned())
+    }
+}
+
+/// Identifier exempt from namespacing.
+///
+/// Use this for identifiers shared with Nexus or other addons, like `EV_ACCOUNT_NAME`.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::{event::event_raise_notification, Global};
+///
+/// event_raise_notification(Global("EV_REQUEST_ACCOUNT_NAME"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Global<S>(pub S);
+
+impl<S: IntoCStr> IntoCStr for Global<S> {
+    type Output = S::Output;
+
+    #[inline]
+    fn into_c_str(self) -> Result<Self::Output, NulError> {
+        self.0.into_c_str()
+    }
+
+    #[inline]
+    fn is_global(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the namespace prefixed to identifiers, if enabled.
+#[inline]
+pub fn namespace() -> Option<&'static str> {
+    *NAMESPACE.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Enables namespacing of identifiers with the given namespace.
+///
+/// A call to this is inserted by the [`export`](crate::export) macro if namespacing is enabled.
+#[doc(hidden)]
+pub fn set_namespace(namespace: &'static str) {
+    *NAMESPACE.write().unwrap_or_else(PoisonError::into_inner) = Some(namespace);
+}
+
+/// Removes the namespace from an identifier received from Nexus.
+#[doc(hidden)]
+#[inline]
+pub fn strip_namespace(identifier: &str) -> &str {
+    namespace()
+        .and_then(|namespace| identifier.strip_prefix(namespace))
+        .and_then(|rest| rest.strip_prefix(SEPARATOR))
+        .unwrap_or(identifier)
+}
+
+/// C string of an identifier, prefixed with the namespace if enabled.
+pub enum NamespacedCStr<T> {
+    /// Identifier passed as is.
+    Plain(T),
+
+    /// Identifier prefixed with the namespace.
+    Namespaced(Arc<CStr>),
+}
+
+impl<T: Deref<Target = CStr>> Deref for NamespacedCStr<T> {
+    type Target = CStr;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Plain(string) => string,
+            Self::Namespaced(string) => string,
+        }
+    }
+}
+
+impl<T: Into<CString>> From<NamespacedCStr<T>> for CString {
+    #[inline]
+    fn from(string: NamespacedCStr<T>) -> Self {
+        match string {
+            NamespacedCStr::Plain(string) => string.into(),
+            NamespacedCStr::Namespaced(string) => string.as_ref().into(),
+        }
+    }
+}
+
+/// Prefixes the identifier with the namespace, unless disabled or global.
+///
+/// Namespaced identifiers are cached, so repeated calls do not allocate.
+/// Once the cache holds [`NAMESPACED_CAPACITY`] identifiers, further identifiers are namespaced without caching.
+pub fn apply_namespace<T: Deref<Target = CStr>>(
+    identifier: T,
+    global: bool,
+) -> Result<NamespacedCStr<T>, NulError> {
+    let Some(namespace) = namespace().filter(|_| !global) else {
+        return Ok(NamespacedCStr::Plain(identifier));
+    };
+    if let Some(namespaced) = NAMESPACED
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&*identifier)
+    {
+        return Ok(NamespacedCStr::Namespaced(namespaced.clone()));
+    }
+
+    // identifier may have been inserted by another thread meanwhile, the first insert wins
+    let mut bytes =
+        Vec::with_capacity(namespace.len() + SEPARATOR.len() + identifier.to_bytes().len());
+    bytes.extend_from_slice(namespace.as_bytes());
+    bytes.extend_from_slice(SEPARATOR.as_bytes());
+    bytes.extend_from_slice(identifier.to_bytes());
+    let namespaced: Arc<CStr> = CString::new(bytes)?.into();
+    let mut cache = NAMESPACED.write().unwrap_or_else(PoisonError::into_inner);
+    if cache.len() >= NAMESPACED_CAPACITY {
+        return Ok(NamespacedCStr::Namespaced(namespaced));
+    }
+    let namespaced = cache
+        .entry(CString::from(&*identifier))
+        .or_insert(namespaced)
+        .clone();
+    Ok(NamespacedCStr::Namespaced(namespaced))
+}
+
+/// Disables namespacing and clears cached identifiers.
+pub fn reset() {
+    *NAMESPACE.write().unwrap_or_else(PoisonError::into_inner) = None;
+    NAMESPACED
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Validates the bytes of a [`c_ident`](crate::c_ident) at compile time.
+#[doc(hidden)]
+pub const fn c_ident(bytes: &'static [u8]) -> &'static CStr {
+    let mut i = 0;
+    while i < bytes.len() - 1 {
+        if bytes[i] == 0 {
+            panic!("identifier contains a nul byte");
+        }
+        i += 1;
+    }
+    unsafe { CStr::from_bytes_with_nul_unchecked(bytes) }
+}
+
+/// Macro to create an [`Identifier`] from a string literal at compile time.
+///
+/// The identifier is validated at compile time and passed to Nexus without allocation.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::{c_ident, texture::get_texture, Identifier};
+///
+/// const TEX_ICON: Identifier = c_ident!("TEX_MY_ICON");
+///
+/// let texture = get_texture(&TEX_ICON);
+/// ```
+#[macro_export]
+macro_rules! c_ident {
+    ( $identifier:literal $(,)? ) => {{
+        const __IDENTIFIER: &::std::ffi::CStr =
+            $crate::__macro::c_ident(::std::concat!($identifier, "\0").as_bytes());
+        $crate::Identifier::from_static(__IDENTIFIER)
+    }};
+}
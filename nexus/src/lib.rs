@@ -24,33 +24,58 @@
 
 pub mod addon;
 mod api;
+mod crash;
+mod dispatch;
+mod error;
+pub mod executor;
 mod globals;
+mod identifier;
+pub mod panic;
+pub mod queue;
+pub mod registry;
 mod revertible;
+pub mod scheduler;
+pub mod state;
 mod util;
+pub mod win;
+
+#[cfg(feature = "debug")]
+pub mod debug;
 
 #[cfg(feature = "log")]
 mod logger;
 
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
 pub use self::{
-    addon::{AddonFlags, AddonLoad, AddonUnload, UpdateProvider},
+    addon::{
+        Addon, AddonContext, AddonFlags, AddonLoad, AddonUnload, UpdateProvider, UpdateSource,
+    },
     api::*,
+    error::Error,
     globals::{on_unload, ui},
-    revertible::Revertible,
+    identifier::{namespace, Global, Identifier, IntoCStr},
+    queue::{defer, try_defer},
+    revertible::{BoxRevertible, RevertGuard, Revertible, RevertibleSet},
 };
 pub use imgui;
 #[cfg(feature = "export")]
-pub use nexus_codegen::export;
+pub use nexus_codegen::{addon, export};
 
 /// Returns the Nexus [`AddonApi`] instance.
 ///
 /// Panics if called before initialization.
 #[inline]
 #[deprecated = "use AddonApi::get() instead"]
+#[track_caller]
 pub fn addon_api() -> &'static AddonApi {
     AddonApi::get()
 }
 
 /// Fields supported by the [`export`] macro.
+///
+/// Fields missing in the macro are read from `[package.metadata.nexus]` in the manifest, if present.
 pub struct SupportedFields {
     /// Raidcore addon id or random unique negative integer, if not on Raidcore.
     pub signature: i32,
@@ -64,6 +89,14 @@ pub struct SupportedFields {
     /// Unload function of the addon.
     pub unload: Option<AddonUnload>,
 
+    /// Constructor for the typed [`AddonState`](state::AddonState).
+    ///
+    /// The state is created before the load function and dropped after the unload function.
+    pub state: Option<fn() -> Box<dyn std::any::Any + Send>>,
+
+    /// Policy applied when a callback panics. Defaults to [`PanicPolicy::DisableCallback`](panic::PanicPolicy::DisableCallback).
+    pub panic_policy: Option<panic::PanicPolicy>,
+
     /// Information about the addon.
     pub flags: Option<AddonFlags>,
 
@@ -73,6 +106,27 @@ pub struct SupportedFields {
     /// Link to the update resource.
     pub update_link: Option<&'static str>,
 
+    /// What platform the addon is hosted on, together with the link to the update resource.
+    ///
+    /// Alternative to `provider` and `update_link`, mismatches result in a compile error.
+    /// Replaces `provider` and `update_link` from the manifest.
+    pub update_source: Option<UpdateSource>,
+
+    /// Revision of the addon version. Defaults to `0`.
+    ///
+    /// Major, minor and build are taken from `CARGO_PKG_VERSION`.
+    pub revision: Option<i16>,
+
+    /// Whether to prefix identifiers with the addon name. Defaults to `false`.
+    ///
+    /// Identifiers shared with Nexus or other addons can be exempted via [`Global`].
+    pub namespace: Option<bool>,
+
+    /// Minimum Addon API version requested from the Loader. Defaults to [`AddonApi::VERSION`].
+    ///
+    /// Older revisions are normalized, see [`compat`] for details.
+    pub min_api_version: Option<i32>,
+
     #[cfg(feature = "log")]
     /// Filter for the log. Same syntax as [env_logger](https://docs.rs/env_logger/latest/env_logger/#enabling-logging).
     pub log_filter: Option<&'static str>,
@@ -81,7 +135,12 @@ pub struct SupportedFields {
 #[doc(hidden)]
 pub mod __macro {
     pub use crate::{
+        addon::{load_addon, unload_addon},
+        event::record_event_hit,
         globals::{deinit, init},
+        identifier::{c_ident, set_namespace, strip_namespace},
+        panic::{catch, catch_lifecycle, catch_load},
+        state::init as init_state,
         util::str_from_c,
     };
 }
@@ -1,5 +1,13 @@
-use crate::log::{log as nexus_log, LogLevel};
+use crate::log::{flush_log, is_async_logging, try_log_target, LogLevel};
 use log::Log;
+use std::sync::{PoisonError, RwLock};
+
+/// Global logger forwarding to the current addon logger.
+///
+/// The [`log`] crate only allows setting a logger once, so the inner logger is swapped on hotload.
+static LOGGER: ForwardLogger = ForwardLogger {
+    inner: RwLock::new(None),
+};
 
 impl From<log::Level> for LogLevel {
     #[inline]
@@ -27,9 +35,65 @@ impl NexusLogger {
         #[cfg(feature = "log_filter")]
         let logger = filter::NexusLoggerFiltered::new(channel_name, filter);
 
-        let _ = log::set_boxed_logger(Box::new(logger));
+        *LOGGER.inner.write().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(logger));
+        Self::install();
+    }
+
+    /// Installs the global logger, buffering records for asynchronous logging until initialization.
+    pub(crate) fn install() {
+        let _ = log::set_logger(&LOGGER);
         log::set_max_level(log::LevelFilter::Trace);
     }
+
+    pub fn unset_logger() {
+        log::set_max_level(log::LevelFilter::Off);
+        LOGGER
+            .inner
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+
+        #[cfg(feature = "log_filter")]
+        filter::reset();
+    }
+}
+
+struct ForwardLogger {
+    inner: RwLock<Option<Box<dyn Log>>>,
+}
+
+impl Log for ForwardLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map_or_else(is_async_logging, |logger| logger.enabled(metadata))
+    }
+
+    fn log(&self, record: &log::Record) {
+        match self
+            .inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+        {
+            Some(logger) => logger.log(record),
+            None => {
+                // buffered until initialization
+                let _ = try_log_target(
+                    record.level().into(),
+                    record.target(),
+                    None,
+                    format!("{}", record.args()),
+                );
+            }
+        }
+    }
+
+    fn flush(&self) {
+        flush_log()
+    }
 }
 
 impl Log for NexusLogger {
@@ -39,44 +103,188 @@ impl Log for NexusLogger {
 
     fn log(&self, record: &log::Record) {
         let message = format!("{}", record.args());
-        nexus_log(record.level().into(), self.channel_name, message)
+        let _ = try_log_target(
+            record.level().into(),
+            record.target(),
+            Some(self.channel_name),
+            message,
+        );
     }
 
     fn flush(&self) {}
 }
 
 #[cfg(feature = "log_filter")]
-mod filter {
+pub(crate) mod filter {
     use super::NexusLogger;
+    use crate::{api::paths::try_get_addon_dir, log::LOG_FILTER_FILE, Error};
     use env_filter::{Builder, Filter};
     use log::Log;
+    use std::{
+        fs,
+        path::PathBuf,
+        sync::{PoisonError, RwLock},
+    };
+
+    /// Current log filter.
+    static FILTER: RwLock<Option<LogFilter>> = RwLock::new(None);
+
+    struct LogFilter {
+        directives: String,
+        default: Option<&'static str>,
+        filter: Filter,
+    }
+
+    /// Parses filter directives, allowing everything if empty.
+    pub fn parse(directives: &str) -> Result<Filter, Error> {
+        if directives.trim().is_empty() {
+            Ok(allow_all())
+        } else {
+            let mut builder = Builder::new();
+            builder
+                .try_parse(directives)
+                .map_err(|err| Error::LogFilter {
+                    reason: err.to_string(),
+                })?;
+            Ok(builder.build())
+        }
+    }
+
+    /// Creates a filter allowing everything.
+    fn allow_all() -> Filter {
+        Builder::new().filter_level(log::LevelFilter::Trace).build()
+    }
+
+    /// Path of the persisted log filter.
+    fn path() -> Option<PathBuf> {
+        let addon_name = crate::globals::addon_name()?;
+        let dir = try_get_addon_dir(addon_name).ok()??;
+        Some(dir.join(LOG_FILTER_FILE))
+    }
+
+    /// Initializes the log filter from the persisted directives or the default.
+    pub fn init(default: Option<&'static str>) {
+        let persisted = path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| contents.trim().to_string())
+            .and_then(|directives| Some((parse(&directives).ok()?, directives)));
+        let (filter, directives) = persisted.unwrap_or_else(|| {
+            let directives = default.unwrap_or_default();
+            let filter = parse(directives).unwrap_or_else(|_| allow_all());
+            (filter, directives.to_string())
+        });
+        *FILTER.write().unwrap_or_else(PoisonError::into_inner) = Some(LogFilter {
+            directives,
+            default,
+            filter,
+        });
+    }
+
+    /// Resets the log filter.
+    pub fn reset() {
+        FILTER
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        crate::log::reset_filter_edit();
+    }
+
+    /// Returns the current filter directives.
+    pub fn directives() -> String {
+        FILTER
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map(|filter| filter.directives.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the default filter directives.
+    pub fn default_directives() -> Option<&'static str> {
+        FILTER
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .and_then(|filter| filter.default)
+    }
+
+    /// Replaces the filter directives and persists them.
+    pub fn set(directives: &str) -> Result<(), Error> {
+        let directives = directives.trim();
+        let filter = parse(directives)?;
+        if let Some(current) = FILTER
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            current.directives = directives.into();
+            current.filter = filter;
+        }
+        if let Some(path) = path() {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            let _ = fs::write(path, directives);
+        }
+        Ok(())
+    }
+
+    /// Restores the default filter directives and removes the persisted ones.
+    pub fn restore_default() {
+        let default = default_directives();
+        if let Some(current) = FILTER
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            let directives = default.unwrap_or_default();
+            current.directives = directives.into();
+            current.filter = parse(directives).unwrap_or_else(|_| allow_all());
+        }
+        if let Some(path) = path() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Checks whether the current filter enables the metadata.
+    pub fn enabled(metadata: &log::Metadata) -> bool {
+        FILTER
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map_or(true, |filter| filter.filter.enabled(metadata))
+    }
+
+    /// Checks whether the current filter matches the record.
+    pub fn matches(record: &log::Record) -> bool {
+        FILTER
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .map_or(true, |filter| filter.filter.matches(record))
+    }
 
     #[derive(Debug)]
     pub struct NexusLoggerFiltered {
         logger: NexusLogger,
-        filter: Filter,
     }
 
     impl NexusLoggerFiltered {
         pub fn new(channel_name: &'static str, filter: Option<&'static str>) -> Self {
+            init(filter);
             Self {
                 logger: NexusLogger { channel_name },
-                filter: filter
-                    .map(|f| Builder::new().parse(f).build())
-                    .unwrap_or_else(|| {
-                        Builder::new().filter_level(log::LevelFilter::Trace).build()
-                    }),
             }
         }
     }
 
     impl Log for NexusLoggerFiltered {
         fn enabled(&self, metadata: &log::Metadata) -> bool {
-            self.filter.enabled(metadata)
+            enabled(metadata)
         }
 
         fn log(&self, record: &log::Record) {
-            if self.filter.matches(record) {
+            if matches(record) {
                 self.logger.log(record);
             }
         }
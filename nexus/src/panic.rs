@@ -0,0 +1,188 @@
+//! Panic containment for callbacks.
+//!
+//! Callbacks are invoked by Nexus through FFI.
+//! All callback wrappers generated by this crate catch panics before they unwind into Nexus.
+//! Caught panics are logged to the addon channel and handled according to the current [`PanicPolicy`].
+
+use crate::{
+    globals::addon_name,
+    log::{try_log, LogLevel},
+    registry,
+};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{catch_unwind, AssertUnwindSafe, Location},
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+/// Policy applied when a callback panics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u8)]
+pub enum PanicPolicy {
+    /// Keep invoking the callback.
+    Continue = 0,
+
+    /// Stop invoking the callback that panicked.
+    #[default]
+    DisableCallback = 1,
+
+    /// Stop invoking all callbacks of the addon until it is reloaded.
+    ///
+    /// All tracked registrations of the addon are reverted as well.
+    DisableAddon = 2,
+}
+
+impl PanicPolicy {
+    #[inline]
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Continue,
+            2 => Self::DisableAddon,
+            _ => Self::DisableCallback,
+        }
+    }
+}
+
+/// Current panic policy.
+static POLICY: AtomicU8 = AtomicU8::new(PanicPolicy::DisableCallback as u8);
+
+/// Whether all callbacks are disabled.
+static ADDON_DISABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Number of nested callbacks currently catching panics.
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+
+    /// Location of the last panic.
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Returns the current [`PanicPolicy`].
+#[inline]
+pub fn panic_policy() -> PanicPolicy {
+    PanicPolicy::from_u8(POLICY.load(Ordering::Relaxed))
+}
+
+/// Sets the [`PanicPolicy`] applied when a callback panics.
+///
+/// The policy is reset to the default on unload.
+#[inline]
+pub fn set_panic_policy(policy: PanicPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed)
+}
+
+/// Checks whether all callbacks of the addon were disabled by [`PanicPolicy::DisableAddon`].
+#[inline]
+pub fn is_addon_disabled() -> bool {
+    ADDON_DISABLED.load(Ordering::Relaxed)
+}
+
+/// Runs the callback, catching any panic.
+///
+/// Returns [`None`] if the callback is disabled or panicked.
+/// A call to this is inserted automatically by the callback macros.
+#[doc(hidden)]
+pub fn catch<R>(kind: &str, disabled: &AtomicBool, callback: impl FnOnce() -> R) -> Option<R> {
+    if disabled.load(Ordering::Relaxed) || is_addon_disabled() {
+        return None;
+    }
+
+    catch_counted(callback)
+        .map_err(|payload| match panic_policy() {
+            PanicPolicy::Continue => log_panic(kind, payload, "continuing"),
+            PanicPolicy::DisableCallback => {
+                disabled.store(true, Ordering::Relaxed);
+                log_panic(kind, payload, "callback disabled")
+            }
+            PanicPolicy::DisableAddon => {
+                log_panic(kind, payload, "addon disabled");
+                disable_addon()
+            }
+        })
+        .ok()
+}
+
+/// Runs the addon load, catching any panic.
+///
+/// A panicking load disables the addon and reverts everything registered until the panic.
+/// A call to this is inserted automatically by the [`export`](crate::export) macro.
+#[doc(hidden)]
+pub fn catch_load(callback: impl FnOnce()) {
+    if let Err(payload) = catch_counted(callback) {
+        log_panic("addon load", payload, "addon disabled");
+        disable_addon()
+    }
+}
+
+/// Runs the addon unload or an unload action, catching any panic.
+///
+/// Unlike [`catch`] this ignores the [`PanicPolicy`].
+/// A call to this is inserted automatically by the [`export`](crate::export) macro.
+#[doc(hidden)]
+pub fn catch_lifecycle(kind: &str, callback: impl FnOnce()) {
+    if let Err(payload) = catch_counted(callback) {
+        log_panic(kind, payload, "skipping remainder")
+    }
+}
+
+/// Disables all callbacks and reverts all tracked registrations of the addon.
+fn disable_addon() {
+    if !ADDON_DISABLED.swap(true, Ordering::Relaxed) {
+        registry::revert_all("reverted, addon disabled");
+    }
+}
+
+fn catch_counted<R>(callback: impl FnOnce() -> R) -> Result<R, Box<dyn Any + Send>> {
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let result = catch_unwind(AssertUnwindSafe(callback));
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+    result
+}
+
+/// Logs the caught panic to the addon channel.
+fn log_panic(kind: &str, payload: Box<dyn Any + Send>, action: &str) {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    let location = LOCATION
+        .with(|location| location.take())
+        .unwrap_or_else(|| "unknown location".into());
+
+    if let Some(addon_name) = addon_name() {
+        let _ = try_log(
+            LogLevel::Critical,
+            addon_name,
+            format!("{kind} panicked at {location}: {message}, {action}"),
+        );
+    }
+}
+
+/// Records the location of a panic.
+///
+/// Returns `true` if the panic is going to be caught by a callback wrapper.
+pub(crate) fn record_panic(location: Option<&Location>) -> bool {
+    LOCATION.with(|recorded| *recorded.borrow_mut() = location.map(ToString::to_string));
+    CATCHING.with(|catching| catching.get() > 0)
+}
+
+/// Resets the panic policy and enables callbacks again.
+pub(crate) fn reset() {
+    set_panic_policy(PanicPolicy::default());
+    ADDON_DISABLED.store(false, Ordering::Relaxed);
+}
@@ -0,0 +1,214 @@
+//! Main thread task queue.
+//!
+//! Nexus invokes events as well as texture and font callbacks on arbitrary threads,
+//! while ImGui state must only be touched during rendering.
+//! Tasks submitted via [`defer`] or a [`MainThreadQueue`] are run at the start of the next frame,
+//! during an internal [`RenderType::PreRender`] callback.
+//!
+//! ```no_run
+//! use nexus::{defer, texture::load_texture_from_file_fn};
+//!
+//! load_texture_from_file_fn("MY_TEXTURE", "my_texture.png", |identifier, texture| {
+//!     let identifier = identifier.to_string();
+//!     let loaded = texture.is_some();
+//!     defer(move |_ui, _thread| {
+//!         // update ui state on the render thread
+//!         let _ = (identifier, loaded);
+//!     });
+//! });
+//! ```
+//!
+//! No further frames are rendered once the addon unloads.
+//! Tasks still pending are dropped without being run by [`MainThreadQueue::flush`] during unload,
+//! tasks submitted afterwards until the unload finished are rejected with [`Error::QueueClosed`].
+
+use crate::{
+    globals::{addon_name, is_unloading, on_unload},
+    gui::{register_render_fn, RenderThread, RenderType},
+    log::{try_log, LogLevel},
+    panic::catch,
+    util::unwrap_or_panic,
+    AddonApi, Error,
+};
+use imgui::Ui;
+use std::{
+    collections::VecDeque,
+    mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// Capacity of the default queue used by [`defer`].
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Default queue used by [`defer`].
+static QUEUE: MainThreadQueue = MainThreadQueue::new(DEFAULT_CAPACITY);
+
+/// Task run on the render thread.
+type Task = Box<dyn FnOnce(&Ui, RenderThread) + Send>;
+
+/// Queue of tasks run on the render thread.
+///
+/// Tasks are run in submission order at the start of the next frame.
+/// They receive the [`RenderThread`] token in addition to the [`Ui`].
+/// The queue registers its render callback with the first submitted task.
+/// A panicking task is handled according to the [`PanicPolicy`](crate::panic::PanicPolicy),
+/// which may disable the queue until the addon is reloaded.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::queue::MainThreadQueue;
+///
+/// static QUEUE: MainThreadQueue = MainThreadQueue::new(64);
+///
+/// std::thread::spawn(|| {
+///     QUEUE.push(|ui, _thread| ui.text("Hello from another thread"));
+/// });
+/// ```
+pub struct MainThreadQueue {
+    tasks: Mutex<VecDeque<Task>>,
+    capacity: usize,
+    registered: AtomicBool,
+    disabled: AtomicBool,
+}
+
+impl MainThreadQueue {
+    /// Creates a new queue holding at most `capacity` pending tasks.
+    #[inline]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            tasks: Mutex::new(VecDeque::new()),
+            capacity,
+            registered: AtomicBool::new(false),
+            disabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the maximum number of pending tasks.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of pending tasks.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks whether no tasks are pending.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Submits a task to be run on the render thread.
+    ///
+    /// Panics if the queue is full, closed or the addon is not loaded.
+    #[inline]
+    #[track_caller]
+    pub fn push(&'static self, task: impl FnOnce(&Ui, RenderThread) + Send + 'static) {
+        unwrap_or_panic(self.try_push(task))
+    }
+
+    /// Fallible version of [`push`](Self::push).
+    ///
+    /// Returns [`Error::QueueFull`] if the queue is full and [`Error::QueueClosed`] while the addon unloads.
+    pub fn try_push(
+        &'static self,
+        task: impl FnOnce(&Ui, RenderThread) + Send + 'static,
+    ) -> Result<(), Error> {
+        if is_unloading() {
+            return Err(Error::QueueClosed);
+        }
+        AddonApi::try_get()?;
+        {
+            let mut tasks = self.lock();
+            if tasks.len() >= self.capacity {
+                return Err(Error::QueueFull {
+                    capacity: self.capacity,
+                });
+            }
+            tasks.push_back(Box::new(task));
+        }
+        if !self.registered.swap(true, Ordering::AcqRel) {
+            self.register();
+        }
+        Ok(())
+    }
+
+    /// Drops all pending tasks without running them.
+    #[inline]
+    pub fn clear(&self) {
+        // tasks may access the queue when dropped
+        let tasks = mem::take(&mut *self.lock());
+        drop(tasks);
+    }
+
+    /// Flushes the queue on addon unload.
+    ///
+    /// Drops all pending tasks without running them, as no further frames are rendered, and logs their number.
+    /// Enables the queue again if it was disabled by a panicking task.
+    /// Returns the number of dropped tasks.
+    ///
+    /// This is called automatically on unload for every queue a task was submitted to.
+    pub fn flush(&self) -> usize {
+        let tasks = mem::take(&mut *self.lock());
+        let dropped = tasks.len();
+        drop(tasks);
+        self.disabled.store(false, Ordering::Relaxed);
+        if dropped > 0 {
+            if let Some(addon_name) = addon_name() {
+                let _ = try_log(
+                    LogLevel::Debug,
+                    addon_name,
+                    format!("dropped {dropped} pending deferred tasks on unload"),
+                );
+            }
+        }
+        dropped
+    }
+
+    /// Registers the render callback draining the queue.
+    fn register(&'static self) {
+        let revertible =
+            register_render_fn(RenderType::PreRender, |ui, thread| self.run(ui, thread));
+        on_unload(move || {
+            revertible.revert();
+            self.flush();
+            self.registered.store(false, Ordering::Release);
+        });
+    }
+
+    /// Runs the tasks pending at the start of the frame.
+    ///
+    /// Tasks submitted by other tasks are run in the next frame.
+    /// Tasks of a disabled queue are dropped without being run.
+    fn run(&self, ui: &Ui, thread: RenderThread) {
+        let tasks = mem::take(&mut *self.lock());
+        for task in tasks {
+            catch("deferred task", &self.disabled, || task(ui, thread));
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Task>> {
+        self.tasks.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Submits a task to be run on the render thread at the start of the next frame.
+///
+/// Panics if more than [`DEFAULT_CAPACITY`] tasks are pending, while the addon unloads or if it is not loaded.
+#[inline]
+#[track_caller]
+pub fn defer(task: impl FnOnce(&Ui, RenderThread) + Send + 'static) {
+    QUEUE.push(task)
+}
+
+/// Fallible version of [`defer`].
+#[inline]
+pub fn try_defer(task: impl FnOnce(&Ui, RenderThread) + Send + 'static) -> Result<(), Error> {
+    QUEUE.try_push(task)
+}
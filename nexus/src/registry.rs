@@ -0,0 +1,229 @@
+//! Tracking of registrations made with Nexus.
+//!
+//! Every registration returning a [`Revertible`] is recorded until it is reverted.
+//! Closure registrations are recorded together with the trampoline registered with Nexus on their behalf.
+//! Registrations still present after the addon unload are reverted automatically and logged as leaked.
+//! This ensures no function pointers into the unloaded addon remain registered with Nexus when hotloading.
+
+use crate::{
+    globals::addon_name,
+    log::{try_log, LogLevel},
+    revertible::Revertible,
+};
+use std::{
+    ffi::CStr,
+    fmt, mem,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+/// Kind of registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RegistrationKind {
+    /// ImGui render callback.
+    Render,
+
+    /// [WNDPROC](https://learn.microsoft.com/en-us/windows/win32/api/winuser/nc-winuser-wndproc) callback.
+    WndProc,
+
+    /// Keybind handler.
+    Keybind,
+
+    /// Event subscription.
+    Event,
+
+    /// Font receive callback.
+    Font,
+
+    /// Quick access shortcut.
+    QuickAccess,
+
+    /// Quick access context menu.
+    QuickAccessContextMenu,
+}
+
+impl fmt::Display for RegistrationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Render => "render callback",
+            Self::WndProc => "wnd_proc callback",
+            Self::Keybind => "keybind",
+            Self::Event => "event subscription",
+            Self::Font => "font callback",
+            Self::QuickAccess => "quick access shortcut",
+            Self::QuickAccessContextMenu => "quick access context menu",
+        })
+    }
+}
+
+/// Registration currently present.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registration {
+    /// Kind of registration.
+    pub kind: RegistrationKind,
+
+    /// Identifier of the registration, if any.
+    pub name: Option<String>,
+
+    /// Whether the registration was made with a closure.
+    pub closure: bool,
+}
+
+impl fmt::Display for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name:?}")?;
+        }
+        if self.closure {
+            write!(f, " (closure)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Recorded registration.
+struct Entry {
+    id: u64,
+    registration: Registration,
+    callback: Option<usize>,
+    revert: Box<dyn FnOnce() + Send>,
+}
+
+/// Registrations currently present.
+static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+/// Id of the next registration.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn lock() -> MutexGuard<'static, Vec<Entry>> {
+    ENTRIES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns all registrations currently present.
+pub fn registrations() -> Vec<Registration> {
+    lock()
+        .iter()
+        .map(|entry| entry.registration.clone())
+        .collect()
+}
+
+/// Returns the identifier as name for a registration.
+#[inline]
+pub(crate) fn name(identifier: &CStr) -> Option<String> {
+    Some(identifier.to_string_lossy().into_owned())
+}
+
+/// Records a registration of a raw callback.
+///
+/// Returns a [`Revertible`] removing the record before reverting.
+pub(crate) fn track(
+    kind: RegistrationKind,
+    name: Option<String>,
+    callback: Option<usize>,
+    revert: impl Fn() + Send + Sync + Clone + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    insert(kind, name, false, callback, revert)
+}
+
+/// Records a registration of a closure.
+///
+/// Returns a [`Revertible`] removing the record before reverting.
+pub(crate) fn track_fn(
+    kind: RegistrationKind,
+    name: Option<String>,
+    revert: impl Fn() + Send + Sync + Clone + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    insert(kind, name, true, None, revert)
+}
+
+/// Records a registration of a trampoline dispatching to closures.
+///
+/// The record is removed when the trampoline is unregistered, see [`untrack`].
+pub(crate) fn track_trampoline(
+    kind: RegistrationKind,
+    name: Option<String>,
+    callback: usize,
+    revert: impl Fn() + Send + Sync + Clone + 'static,
+) {
+    insert(kind, name, false, Some(callback), revert).leak()
+}
+
+fn insert(
+    kind: RegistrationKind,
+    name: Option<String>,
+    closure: bool,
+    callback: Option<usize>,
+    revert: impl Fn() + Send + Sync + Clone + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    lock().push(Entry {
+        id,
+        registration: Registration {
+            kind,
+            name,
+            closure,
+        },
+        callback,
+        revert: Box::new(revert.clone()),
+    });
+    // registrations reverted in bulk are not reverted again
+    let revert = move || {
+        let mut entries = lock();
+        let len = entries.len();
+        entries.retain(|entry| entry.id != id);
+        let present = entries.len() != len;
+        drop(entries);
+        if present {
+            revert()
+        }
+    };
+    revert.into()
+}
+
+/// Removes records of raw callbacks unregistered manually.
+///
+/// Name and callback are only compared if passed.
+pub(crate) fn untrack(kind: RegistrationKind, name: Option<&str>, callback: Option<usize>) {
+    lock().retain(|entry| {
+        let matches = !entry.registration.closure
+            && entry.registration.kind == kind
+            && name.map_or(true, |name| {
+                entry.registration.name.as_deref() == Some(name)
+            })
+            && callback.map_or(true, |callback| entry.callback == Some(callback));
+        !matches
+    });
+}
+
+/// Removes records of closures replaced by a new registration.
+pub(crate) fn untrack_fn(kind: RegistrationKind, name: &str) {
+    lock().retain(|entry| {
+        let matches = entry.registration.closure
+            && entry.registration.kind == kind
+            && entry.registration.name.as_deref() == Some(name);
+        !matches
+    });
+}
+
+/// Reverts all registrations still present, logging each as leaked.
+pub(crate) fn revert_leaked() {
+    revert_all("leaked, reverting on unload")
+}
+
+/// Reverts all registrations still present, logging each with the reason.
+pub(crate) fn revert_all(reason: &str) {
+    let entries = mem::take(&mut *lock());
+    for entry in entries {
+        if let Some(addon_name) = addon_name() {
+            let _ = try_log(
+                LogLevel::Warning,
+                addon_name,
+                format!("{} {reason}", entry.registration),
+            );
+        }
+        (entry.revert)();
+    }
+}
@@ -1,4 +1,5 @@
 use crate::on_unload;
+use std::{fmt, mem};
 
 /// A revertible action.
 ///
@@ -10,6 +11,14 @@ use crate::on_unload;
 /// - Call [`leak`](Revertible::leak) or drop it (explicitly or implicitly) to discard and unregister manually.
 /// - Keep the [`Revertible`] and call [`revert`](Revertible::revert) later.
 /// - Turn it into a callable via [`into_inner`](Revertible::into_inner) and call it later.
+/// - Turn it into a [`RevertGuard`] via [`guard`](Revertible::guard) to revert when the guard is dropped.
+/// - Collect it in a [`RevertibleSet`] together with other revertibles.
+///
+/// Multiple revertibles can be combined via [`and`](Revertible::and) and [`chain`](Revertible::chain).
+/// Revertibles of different types can be stored as [`BoxRevertible`] via [`boxed`](Revertible::boxed).
+///
+/// Registrations still present after the addon unload are reverted and logged as leaked,
+/// see [`registry`](crate::registry).
 #[must_use]
 #[derive(Debug)]
 #[repr(transparent)]
@@ -39,7 +48,7 @@ where
 
     /// Leaves the action to not be reverted.
     ///
-    /// The reversion has to be performed manually.
+    /// The reversion has to be performed manually, otherwise it is reverted and logged as leaked on unload.
     /// Effectively the same as dropping the revertible.
     #[inline]
     pub fn leak(self) {}
@@ -55,6 +64,44 @@ where
     pub fn revert_on_unload(self) {
         on_unload(self.into_inner())
     }
+
+    /// Turns the revertible into a [`RevertGuard`] reverting when dropped.
+    #[inline]
+    pub fn guard(self) -> RevertGuard<F> {
+        RevertGuard::new(self)
+    }
+
+    /// Erases the type of the revertible.
+    #[inline]
+    pub fn boxed(self) -> BoxRevertible {
+        Revertible::new(Box::new(self.revert))
+    }
+
+    /// Combines the revertible with another one.
+    ///
+    /// Reverting the combination reverts the other revertible first.
+    #[inline]
+    pub fn and<G>(self, other: Revertible<G>) -> Revertible<impl FnOnce() + Send + 'static>
+    where
+        G: FnOnce() + Send + 'static,
+    {
+        Revertible::new(move || {
+            other.revert();
+            self.revert();
+        })
+    }
+
+    /// Appends an action performed after reverting.
+    #[inline]
+    pub fn chain<G>(self, action: G) -> Revertible<impl FnOnce() + Send + 'static>
+    where
+        G: FnOnce() + Send + 'static,
+    {
+        Revertible::new(move || {
+            self.revert();
+            action();
+        })
+    }
 }
 
 impl<F> From<F> for Revertible<F>
@@ -66,3 +113,200 @@ where
         Self::new(revert)
     }
 }
+
+/// A revertible action with erased type.
+pub type BoxRevertible = Revertible<Box<dyn FnOnce() + Send>>;
+
+/// A guard reverting the action when dropped.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::gui::{register_render, render, RenderType};
+///
+/// let guard = register_render(RenderType::Render, render!(|ui| {
+///     ui.text("Hello World");
+/// }))
+/// .guard();
+///
+/// // unregistered when the guard is dropped
+/// drop(guard);
+/// ```
+#[must_use]
+#[derive(Debug)]
+pub struct RevertGuard<F>
+where
+    F: FnOnce() + Send + 'static,
+{
+    revertible: Option<Revertible<F>>,
+}
+
+impl<F> RevertGuard<F>
+where
+    F: FnOnce() + Send + 'static,
+{
+    /// Creates a new guard for the revertible.
+    #[inline]
+    pub const fn new(revertible: Revertible<F>) -> Self {
+        Self {
+            revertible: Some(revertible),
+        }
+    }
+
+    /// Disarms the guard, returning the revertible without reverting.
+    #[inline]
+    pub fn disarm(mut self) -> Revertible<F> {
+        self.revertible.take().expect("guard already disarmed")
+    }
+
+    /// Reverts the action.
+    ///
+    /// Effectively the same as dropping the guard.
+    #[inline]
+    pub fn revert(self) {}
+}
+
+impl<F> Drop for RevertGuard<F>
+where
+    F: FnOnce() + Send + 'static,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(revertible) = self.revertible.take() {
+            revertible.revert()
+        }
+    }
+}
+
+/// A set of revertible actions.
+///
+/// Actions are reverted in reverse order of insertion, when the set is cleared or dropped.
+/// This allows enabling and disabling features at runtime.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::{
+///     gui::{register_render, render, RenderType},
+///     keybind::{register_keybind_with_string, keybind_handler},
+///     RevertibleSet,
+/// };
+///
+/// let mut feature = RevertibleSet::new();
+/// feature.push(register_render(RenderType::Render, render!(|ui| {
+///     ui.text("Hello World");
+/// })));
+/// feature.push(register_keybind_with_string(
+///     "MY_KEYBIND",
+///     keybind_handler!(|_id, _is_release| {}),
+///     "ALT+SHIFT+X",
+/// ));
+///
+/// // feature toggled off in settings
+/// feature.clear();
+/// ```
+#[must_use]
+#[derive(Default)]
+pub struct RevertibleSet {
+    revertibles: Vec<BoxRevertible>,
+}
+
+impl RevertibleSet {
+    /// Creates a new empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            revertibles: Vec::new(),
+        }
+    }
+
+    /// Adds a revertible to the set.
+    #[inline]
+    pub fn push<F>(&mut self, revertible: Revertible<F>)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.revertibles.push(revertible.boxed())
+    }
+
+    /// Returns the number of revertibles in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.revertibles.len()
+    }
+
+    /// Checks whether the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.revertibles.is_empty()
+    }
+
+    /// Reverts all actions in reverse order, leaving the set empty.
+    pub fn clear(&mut self) {
+        for revertible in mem::take(&mut self.revertibles).into_iter().rev() {
+            revertible.revert()
+        }
+    }
+
+    /// Leaves all actions to not be reverted.
+    ///
+    /// The reversions have to be performed manually, otherwise they are reverted and logged as leaked on unload.
+    #[inline]
+    pub fn leak(mut self) {
+        self.revertibles.clear()
+    }
+
+    /// Turns the set into a single revertible.
+    #[inline]
+    pub fn into_revertible(mut self) -> BoxRevertible {
+        let revertibles = mem::take(&mut self.revertibles);
+        Revertible::new(Box::new(move || {
+            for revertible in revertibles.into_iter().rev() {
+                revertible.revert()
+            }
+        }))
+    }
+
+    /// Submits the set to be reverted on unload.
+    #[inline]
+    pub fn revert_on_unload(self) {
+        self.into_revertible().revert_on_unload()
+    }
+}
+
+impl<F> Extend<Revertible<F>> for RevertibleSet
+where
+    F: FnOnce() + Send + 'static,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = Revertible<F>>>(&mut self, iter: I) {
+        for revertible in iter {
+            self.push(revertible)
+        }
+    }
+}
+
+impl<F> FromIterator<Revertible<F>> for RevertibleSet
+where
+    F: FnOnce() + Send + 'static,
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Revertible<F>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl fmt::Debug for RevertibleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RevertibleSet")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl Drop for RevertibleSet {
+    #[inline]
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
@@ -0,0 +1,199 @@
+//! Frame-based timers.
+//!
+//! Timers are checked at the start of every frame, during an internal [`RenderType::PreRender`] callback.
+//! Their precision is therefore bound to the frame rate.
+//! Callbacks receive the [`RenderThread`] token in addition to the [`Ui`].
+//!
+//! ```no_run
+//! use nexus::scheduler::{after, every};
+//! use std::time::Duration;
+//!
+//! every(Duration::from_millis(500), |_ui, _thread| {
+//!     // poll twice a second
+//! })
+//! .revert_on_unload();
+//!
+//! after(Duration::from_secs(5), |_ui, _thread| {
+//!     // hide alert
+//! })
+//! .leak();
+//! ```
+//!
+//! Pending timers are cancelled on unload.
+
+use crate::{
+    globals::on_unload,
+    gui::{register_render_fn, RenderThread, RenderType},
+    panic::catch,
+    revertible::Revertible,
+};
+use imgui::Ui;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+/// Pending timers.
+static TIMERS: Mutex<Vec<Timer>> = Mutex::new(Vec::new());
+
+/// Whether the render callback checking the timers is registered.
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Id of the next timer.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+type TimerFn = dyn FnMut(&Ui, RenderThread) + Send;
+
+struct Timer {
+    id: u64,
+    due: Due,
+    interval: Option<Duration>,
+    callback: Arc<Mutex<TimerFn>>,
+    disabled: Arc<AtomicBool>,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Due {
+    Frame,
+    Instant(Instant),
+}
+
+fn lock() -> MutexGuard<'static, Vec<Timer>> {
+    TIMERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs the callback once after the given duration passed.
+///
+/// Returns a [`Revertible`] to cancel the timer.
+pub fn after(
+    duration: Duration,
+    callback: impl FnOnce(&Ui, RenderThread) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    insert(
+        Due::Instant(Instant::now() + duration),
+        None,
+        once(callback),
+    )
+}
+
+/// Runs the callback repeatedly, every time the given interval passed.
+///
+/// Returns a [`Revertible`] to cancel the timer.
+pub fn every(
+    interval: Duration,
+    callback: impl FnMut(&Ui, RenderThread) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    insert(
+        Due::Instant(Instant::now() + interval),
+        Some(interval),
+        callback,
+    )
+}
+
+/// Runs the callback once at the start of the next frame.
+///
+/// Returns a [`Revertible`] to cancel the timer.
+pub fn next_frame(
+    callback: impl FnOnce(&Ui, RenderThread) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    insert(Due::Frame, None, once(callback))
+}
+
+/// Wraps a callback to be run once.
+fn once(
+    callback: impl FnOnce(&Ui, RenderThread) + Send + 'static,
+) -> impl FnMut(&Ui, RenderThread) + Send + 'static {
+    let mut callback = Some(callback);
+    move |ui, thread| {
+        if let Some(callback) = callback.take() {
+            callback(ui, thread)
+        }
+    }
+}
+
+fn insert(
+    due: Due,
+    interval: Option<Duration>,
+    callback: impl FnMut(&Ui, RenderThread) + Send + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let cancelled = Arc::new(AtomicBool::new(false));
+    lock().push(Timer {
+        id,
+        due,
+        interval,
+        callback: Arc::new(Mutex::new(callback)),
+        disabled: Arc::new(AtomicBool::new(false)),
+        cancelled: cancelled.clone(),
+    });
+    if !REGISTERED.swap(true, Ordering::AcqRel) {
+        register();
+    }
+    let revert = move || {
+        cancelled.store(true, Ordering::Relaxed);
+        lock().retain(|timer| timer.id != id);
+    };
+    revert.into()
+}
+
+/// Registers the render callback checking the timers.
+fn register() {
+    let revertible = register_render_fn(RenderType::PreRender, run);
+    on_unload(move || {
+        revertible.revert();
+        lock().clear();
+        REGISTERED.store(false, Ordering::Release);
+    });
+}
+
+/// Runs all due timers.
+///
+/// Timers added by other timers are checked in the next frame.
+fn run(ui: &Ui, thread: RenderThread) {
+    let now = Instant::now();
+    let due = {
+        let mut timers = lock();
+        let mut due = Vec::new();
+        timers.retain_mut(|timer| {
+            let is_due = match timer.due {
+                Due::Frame => true,
+                Due::Instant(instant) => instant <= now,
+            };
+            if !is_due {
+                return true;
+            }
+            due.push((
+                timer.callback.clone(),
+                timer.disabled.clone(),
+                timer.cancelled.clone(),
+            ));
+            match timer.interval {
+                Some(interval) => {
+                    // skip missed intervals instead of running them in a burst
+                    let next = match timer.due {
+                        Due::Instant(instant) => instant + interval,
+                        Due::Frame => now + interval,
+                    };
+                    timer.due = Due::Instant(if next <= now { now + interval } else { next });
+                    true
+                }
+                None => false,
+            }
+        });
+        due
+    };
+    for (callback, disabled, cancelled) in due {
+        // timers may be cancelled by previous ones
+        if cancelled.load(Ordering::Relaxed) {
+            continue;
+        }
+        catch("timer callback", &disabled, || {
+            let mut callback = callback.lock().unwrap_or_else(PoisonError::into_inner);
+            callback(ui, thread)
+        });
+    }
+}
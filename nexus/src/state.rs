@@ -0,0 +1,199 @@
+//! Typed addon state.
+//!
+//! The state is created by the [`export`](crate::export) macro on load and dropped on unload.
+//!
+//! The state is guarded by a single lock shared by all threads.
+//! While one thread accesses the state, others block until it is released.
+//! The wrappers like [`AddonState::render`] hold the state for the whole callback,
+//! so a background thread accessing the state may wait for a render callback to finish.
+//! Keep accesses from other threads short, for example via [`AddonState::with`],
+//! and move data needed for longer work out of the state first.
+//!
+//! # Usage
+//! ```no_run
+//! # mod main {
+//! use nexus::{
+//!     gui::{register_render_fn, RenderType},
+//!     state::AddonState,
+//! };
+//!
+//! #[derive(Default)]
+//! struct MyState {
+//!     clicks: usize,
+//! }
+//!
+//! nexus::export! {
+//!     signature: -0x12345678,
+//!     state: MyState::default,
+//!     load: || {
+//!         register_render_fn(
+//!             RenderType::Render,
+//!             AddonState::render(|ui, state: &mut MyState| {
+//!                 if ui.button("Click me") {
+//!                     state.clicks += 1;
+//!                 }
+//!             }),
+//!         )
+//!         .revert_on_unload();
+//!     },
+//! }
+//! # }
+//! ```
+
+use crate::gui::RenderThread;
+use imgui::Ui;
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// Storage for the addon state.
+static STATE: Mutex<Option<Box<dyn Any + Send>>> = Mutex::new(None);
+
+thread_local! {
+    /// Whether the current thread holds the addon state.
+    static BORROWED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Exclusive access to the typed addon state.
+///
+/// Holding this blocks access to the state from other threads, so it should be dropped quickly.
+/// Accessing the state again on the same thread while holding this returns [`None`] instead of deadlocking.
+pub struct AddonState<T: 'static> {
+    guard: MutexGuard<'static, Option<Box<dyn Any + Send>>>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Send + 'static> AddonState<T> {
+    /// Returns the addon state.
+    ///
+    /// Panics if the state is not initialized, of a different type or already borrowed on the current thread.
+    #[inline]
+    #[track_caller]
+    pub fn get() -> Self {
+        Self::try_get().expect("addon state not initialized or already borrowed")
+    }
+
+    /// Attempts to return the addon state.
+    ///
+    /// Returns [`None`] if the state is not initialized, of a different type or already borrowed on the current thread.
+    pub fn try_get() -> Option<Self> {
+        // locking again on the same thread would deadlock
+        if BORROWED.with(Cell::get) {
+            return None;
+        }
+        let guard = STATE.lock().unwrap_or_else(PoisonError::into_inner);
+        if guard.as_ref().is_some_and(|state| state.is::<T>()) {
+            BORROWED.with(|borrowed| borrowed.set(true));
+            Some(Self {
+                guard,
+                _phantom: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Runs the function with the addon state.
+    ///
+    /// Returns [`None`] if the state is not initialized, of a different type or already borrowed on the current thread.
+    #[inline]
+    pub fn with<R>(f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        Self::try_get().map(|mut state| f(&mut state))
+    }
+
+    /// Wraps a render closure to receive the addon state.
+    ///
+    /// The closure is skipped while the state is not available.
+    /// The state is held for the whole closure, blocking other threads accessing it during the render.
+    #[inline]
+    pub fn render(
+        mut callback: impl FnMut(&Ui, &mut T) + Send + 'static,
+    ) -> impl FnMut(&Ui, RenderThread) + Send + 'static {
+        move |ui, _thread| {
+            Self::with(|state| callback(ui, state));
+        }
+    }
+
+    /// Wraps a keybind handler closure to receive the addon state.
+    ///
+    /// The closure is skipped while the state is not available.
+    #[inline]
+    pub fn keybind(
+        mut handler: impl FnMut(&str, bool, &mut T) + Send + 'static,
+    ) -> impl FnMut(&str, bool) + Send + 'static {
+        move |identifier, is_release| {
+            Self::with(|state| handler(identifier, is_release, state));
+        }
+    }
+
+    /// Wraps an event closure to receive the addon state.
+    ///
+    /// The closure is skipped while the state is not available.
+    #[inline]
+    pub fn event<E>(
+        mut callback: impl FnMut(Option<&E>, &mut T) + Send + 'static,
+    ) -> impl FnMut(Option<&E>) + Send + 'static {
+        move |data| {
+            Self::with(|state| callback(data, state));
+        }
+    }
+}
+
+impl<T: 'static> Deref for AddonState<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // type is checked on creation
+        self.guard
+            .as_ref()
+            .and_then(|state| state.downcast_ref())
+            .expect("addon state changed")
+    }
+}
+
+impl<T: 'static> DerefMut for AddonState<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard
+            .as_mut()
+            .and_then(|state| state.downcast_mut())
+            .expect("addon state changed")
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for AddonState<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: 'static> Drop for AddonState<T> {
+    #[inline]
+    fn drop(&mut self) {
+        BORROWED.with(|borrowed| borrowed.set(false));
+    }
+}
+
+/// Initializes the addon state.
+///
+/// A call to this is inserted automatically by the [`export`](crate::export) macro.
+#[doc(hidden)]
+pub fn init<T: Send + 'static>(create: impl FnOnce() -> T) {
+    let state = Box::new(create());
+    let mut guard = STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    assert!(guard.is_none(), "addon state initialized multiple times");
+    *guard = Some(state);
+}
+
+/// Drops the addon state.
+pub(crate) fn deinit() {
+    let state = STATE.lock().unwrap_or_else(PoisonError::into_inner).take();
+    drop(state);
+}
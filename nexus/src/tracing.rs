@@ -0,0 +1,366 @@
+//! Logging via [`tracing`](https://docs.rs/tracing).
+//!
+//! Requires the `tracing` feature.
+//! The [`NexusLayer`] forwards events to the Nexus log, prefixed with the context of the current spans.
+//! With the `log_filter` feature, the log filter of the addon is honoured, see [`set_log_filter`](crate::log::set_log_filter).
+//!
+//! ```no_run
+//! use nexus::tracing::NexusLayer;
+//! use tracing_subscriber::{layer::SubscriberExt, Registry};
+//!
+//! let subscriber = Registry::default().with(NexusLayer::new().with_span_timing(true));
+//! let _ = tracing::subscriber::set_global_default(subscriber);
+//!
+//! let span = tracing::info_span!("process", events = 1024).entered();
+//! tracing::debug!(skill = 12345, "processing combat events");
+//! drop(span); // logs "process{events=1024}: closed after ..."
+//! ```
+
+use crate::{
+    globals::addon_name,
+    log::{try_log_target, LogLevel},
+};
+use std::{
+    fmt::{self, Write},
+    time::{Duration, Instant},
+};
+use tracing_core::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+    Event, Level, Metadata, Subscriber,
+};
+use tracing_subscriber::{
+    layer::Context,
+    registry::{LookupSpan, Scope},
+    Layer,
+};
+
+impl From<Level> for LogLevel {
+    #[inline]
+    fn from(level: Level) -> Self {
+        match level {
+            Level::ERROR => Self::Critical,
+            Level::WARN => Self::Warning,
+            Level::INFO => Self::Info,
+            Level::DEBUG => Self::Debug,
+            Level::TRACE => Self::Trace,
+        }
+    }
+}
+
+/// [`Layer`] forwarding [`tracing`](https://docs.rs/tracing) events to the Nexus log.
+///
+/// Events are logged as `span{field=value}:span: message field=value`.
+/// The channel is chosen by the target of the event, see [`map_log_target`](crate::log::map_log_target).
+pub struct NexusLayer {
+    channel_name: Option<&'static str>,
+    span_timing: bool,
+
+    #[cfg(feature = "log_filter")]
+    filter: Option<env_filter::Filter>,
+}
+
+impl NexusLayer {
+    /// Creates a new layer logging to the channel of the addon name.
+    ///
+    /// With the `log_filter` feature, the current log filter of the addon is used.
+    pub fn new() -> Self {
+        Self {
+            channel_name: None,
+            span_timing: false,
+
+            #[cfg(feature = "log_filter")]
+            filter: None,
+        }
+    }
+
+    /// Sets the channel to log to, for targets without mapped channel.
+    ///
+    /// Defaults to the addon name, see [`map_log_target`](crate::log::map_log_target).
+    #[inline]
+    pub fn with_channel(mut self, channel_name: &'static str) -> Self {
+        self.channel_name = Some(channel_name);
+        self
+    }
+
+    /// Sets whether to log the time spent in a span when it closes.
+    ///
+    /// Span timings are logged at the level of the span. Defaults to `false`.
+    #[inline]
+    pub fn with_span_timing(mut self, span_timing: bool) -> Self {
+        self.span_timing = span_timing;
+        self
+    }
+
+    /// Sets separate filter directives, using the syntax of the `log_filter` field in the [`export`](crate::export) macro.
+    ///
+    /// Invalid directives are ignored, keeping the log filter of the addon.
+    /// Unlike the log filter of the addon, separate directives can not change,
+    /// so callsites they disable are skipped by `tracing` without checking them again.
+    #[cfg(feature = "log_filter")]
+    #[inline]
+    pub fn with_filter(mut self, directives: &str) -> Self {
+        self.filter = crate::logger::filter::parse(directives).ok();
+        self
+    }
+
+    fn log(&self, metadata: &Metadata, message: String) {
+        #[cfg(feature = "log_filter")]
+        if !filter::matches(self.filter.as_ref(), metadata, &message) {
+            return;
+        }
+
+        let _ = try_log_target(
+            (*metadata.level()).into(),
+            metadata.target(),
+            self.channel_name.or_else(addon_name),
+            message,
+        );
+    }
+}
+
+impl Default for NexusLayer {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for NexusLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NexusLayer")
+            .field("channel_name", &self.channel_name)
+            .field("span_timing", &self.span_timing)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Rendered fields of a span.
+struct SpanFields(String);
+
+/// Time spent in a span.
+struct SpanTiming {
+    busy: Duration,
+    entered: Option<Instant>,
+}
+
+impl<S> Layer<S> for NexusLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        #[cfg(feature = "log_filter")]
+        return match &self.filter {
+            // separate filter is fixed
+            Some(filter) => {
+                if filter::enabled(Some(filter), _metadata) {
+                    Interest::always()
+                } else {
+                    Interest::never()
+                }
+            }
+
+            // log filter of the addon may change at runtime
+            None => Interest::sometimes(),
+        };
+
+        #[cfg(not(feature = "log_filter"))]
+        Interest::always()
+    }
+
+    fn enabled(&self, _metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        #[cfg(feature = "log_filter")]
+        return filter::enabled(self.filter.as_ref(), _metadata);
+
+        #[cfg(not(feature = "log_filter"))]
+        true
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut fields = String::new();
+            attrs.record(&mut FieldVisitor::new(&mut fields));
+            let mut extensions = span.extensions_mut();
+            extensions.insert(SpanFields(fields));
+            if self.span_timing {
+                extensions.insert(SpanTiming {
+                    busy: Duration::ZERO,
+                    entered: None,
+                });
+            }
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+                values.record(&mut FieldVisitor::new(fields));
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                timing.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                if let Some(entered) = timing.entered.take() {
+                    timing.busy += entered.elapsed();
+                }
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut message = ctx.event_scope(event).map(render_scope).unwrap_or_default();
+        let mut fields = String::new();
+        event.record(&mut EventVisitor {
+            message: &mut message,
+            fields: FieldVisitor::new(&mut fields),
+        });
+        if !fields.is_empty() {
+            message.push(' ');
+            message.push_str(&fields);
+        }
+        self.log(event.metadata(), message)
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if !self.span_timing {
+            return;
+        }
+        if let Some(span) = ctx.span(&id) {
+            let busy = span
+                .extensions()
+                .get::<SpanTiming>()
+                .map(|timing| timing.busy);
+            if let Some(busy) = busy {
+                let mut message = render_scope(span.scope());
+                let _ = write!(message, "closed after {busy:?}");
+                self.log(span.metadata(), message)
+            }
+        }
+    }
+}
+
+/// Renders the spans of a scope from the root, as `span{field=value}:span: `.
+fn render_scope<S>(scope: Scope<'_, S>) -> String
+where
+    S: for<'a> LookupSpan<'a>,
+{
+    let mut result = String::new();
+    for span in scope.from_root() {
+        result.push_str(span.name());
+        if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+            if !fields.is_empty() {
+                let _ = write!(result, "{{{fields}}}");
+            }
+        }
+        result.push(':');
+    }
+    if !result.is_empty() {
+        result.push(' ');
+    }
+    result
+}
+
+/// Renders fields as `field=value`.
+struct FieldVisitor<'a> {
+    result: &'a mut String,
+}
+
+impl<'a> FieldVisitor<'a> {
+    #[inline]
+    fn new(result: &'a mut String) -> Self {
+        Self { result }
+    }
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &value)
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.result.is_empty() {
+            self.result.push(' ');
+        }
+        let _ = write!(self.result, "{}={value:?}", field.name());
+    }
+}
+
+/// Renders the message of an event and its remaining fields.
+struct EventVisitor<'a> {
+    message: &'a mut String,
+    fields: FieldVisitor<'a>,
+}
+
+impl Visit for EventVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.fields.record_str(field, value)
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            self.fields.record_debug(field, value)
+        }
+    }
+}
+
+#[cfg(feature = "log_filter")]
+mod filter {
+    use crate::logger::filter as addon_filter;
+    use env_filter::Filter;
+    use tracing_core::{Level, Metadata};
+
+    fn level(level: Level) -> log::Level {
+        match level {
+            Level::ERROR => log::Level::Error,
+            Level::WARN => log::Level::Warn,
+            Level::INFO => log::Level::Info,
+            Level::DEBUG => log::Level::Debug,
+            Level::TRACE => log::Level::Trace,
+        }
+    }
+
+    fn metadata<'a>(metadata: &Metadata<'a>) -> log::Metadata<'a> {
+        log::Metadata::builder()
+            .level(level(*metadata.level()))
+            .target(metadata.target())
+            .build()
+    }
+
+    pub fn enabled(filter: Option<&Filter>, metadata: &Metadata) -> bool {
+        let metadata = self::metadata(metadata);
+        match filter {
+            Some(filter) => filter.enabled(&metadata),
+            None => addon_filter::enabled(&metadata),
+        }
+    }
+
+    pub fn matches(filter: Option<&Filter>, metadata: &Metadata, message: &str) -> bool {
+        let matches = |record: &log::Record| match filter {
+            Some(filter) => filter.matches(record),
+            None => addon_filter::matches(record),
+        };
+        matches(
+            &log::Record::builder()
+                .metadata(self::metadata(metadata))
+                .args(format_args!("{message}"))
+                .build(),
+        )
+    }
+}
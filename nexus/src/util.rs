@@ -1,5 +1,9 @@
 #![allow(clippy::missing_safety_doc)]
 
+use crate::{
+    identifier::{apply_namespace, NamespacedCStr},
+    Error, IntoCStr,
+};
 use std::{
     ffi::{c_char, CStr, CString},
     path::{Path, PathBuf},
@@ -29,17 +33,49 @@ pub unsafe fn path_from_c(ptr: *const c_char) -> Option<PathBuf> {
 }
 
 /// Attempts to convert a string to a [`CString`].
-/// Panics with the given error message if the string contains an internal nul byte.
 #[inline]
-pub fn str_to_c(string: impl AsRef<str>, err_msg: &str) -> CString {
-    CString::new(string.as_ref()).expect(err_msg)
+pub fn try_str_to_c(string: impl AsRef<str>, what: &'static str) -> Result<CString, Error> {
+    CString::new(string.as_ref()).map_err(|err| Error::nul(err, what))
 }
 
-/// Attempts to convert a string to a [`CString`].
-/// Panics with the given error message if the string contains an internal nul byte.
+/// Attempts to convert a string to a C string, without allocation if possible.
+#[inline]
+pub fn try_to_c<S: IntoCStr>(string: S, what: &'static str) -> Result<S::Output, Error> {
+    string.into_c_str().map_err(|err| Error::nul(err, what))
+}
+
+/// Attempts to convert an identifier to a C string, prefixed with the namespace if enabled.
+#[inline]
+pub fn try_ident_to_c<S: IntoCStr>(
+    identifier: S,
+    what: &'static str,
+) -> Result<NamespacedCStr<S::Output>, Error> {
+    let global = identifier.is_global();
+    let identifier = try_to_c(identifier, what)?;
+    apply_namespace(identifier, global).map_err(|err| Error::nul(err, what))
+}
+
+/// Attempts to convert a C string to a [`prim@str`].
 #[inline]
-pub fn path_to_c(path: impl AsRef<Path>, err_msg: &str) -> CString {
-    str_to_c(path.as_ref().to_str().expect(err_msg), err_msg)
+pub fn try_c_to_str<'a>(string: &'a CStr, what: &'static str) -> Result<&'a str, Error> {
+    string.to_str().map_err(|_| Error::NonUtf8 { what })
+}
+
+/// Attempts to convert a path to a [`CString`].
+#[inline]
+pub fn try_path_to_c(path: impl AsRef<Path>, what: &'static str) -> Result<CString, Error> {
+    let string = path.as_ref().to_str().ok_or(Error::NonUtf8 { what })?;
+    try_str_to_c(string, what)
+}
+
+/// Unwraps the result, panicking with the error message.
+#[inline]
+#[track_caller]
+pub fn unwrap_or_panic<T>(result: Result<T, Error>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => panic!("{err}"),
+    }
 }
 
 /// Helper trait to handle `Option<&CStr>` and  `Option<CString>`.
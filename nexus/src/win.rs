@@ -0,0 +1,94 @@
+//! Windows types used by the Nexus API.
+//!
+//! On non-Windows targets opaque stand-ins with the same layout are provided.
+//! This allows addons to be compiled and tested on other platforms, for example via `nexus_test`.
+
+#[cfg(windows)]
+pub use windows::{
+    core::InterfaceRef,
+    Win32::{
+        Foundation::{HMODULE, HWND, LPARAM, LRESULT, WPARAM},
+        Graphics::{
+            Direct3D11::{ID3D11Device, ID3D11ShaderResourceView},
+            Dxgi::IDXGISwapChain,
+        },
+    },
+};
+
+#[cfg(not(windows))]
+pub use self::fallback::*;
+
+#[cfg(not(windows))]
+#[allow(clippy::upper_case_acronyms)]
+mod fallback {
+    use std::{ffi::c_void, fmt, marker::PhantomData, ptr::NonNull};
+
+    /// Module handle.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[repr(transparent)]
+    pub struct HMODULE(pub *mut c_void);
+
+    impl Default for HMODULE {
+        #[inline]
+        fn default() -> Self {
+            Self(std::ptr::null_mut())
+        }
+    }
+
+    /// Window handle.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[repr(transparent)]
+    pub struct HWND(pub *mut c_void);
+
+    impl Default for HWND {
+        #[inline]
+        fn default() -> Self {
+            Self(std::ptr::null_mut())
+        }
+    }
+
+    /// Message parameter.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[repr(transparent)]
+    pub struct WPARAM(pub usize);
+
+    /// Message parameter.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[repr(transparent)]
+    pub struct LPARAM(pub isize);
+
+    /// Message result.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    #[repr(transparent)]
+    pub struct LRESULT(pub isize);
+
+    /// Opaque DirectX 11 shader resource view.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct ID3D11ShaderResourceView(NonNull<c_void>);
+
+    /// Opaque DXGI swap chain.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[repr(transparent)]
+    pub struct IDXGISwapChain(NonNull<c_void>);
+
+    /// Borrowed interface pointer.
+    #[repr(transparent)]
+    pub struct InterfaceRef<'a, T>(NonNull<c_void>, PhantomData<&'a T>);
+
+    impl<T> Clone for InterfaceRef<'_, T> {
+        #[inline]
+        fn clone(&self) -> Self {
+            *self
+        }
+    }
+
+    impl<T> Copy for InterfaceRef<'_, T> {}
+
+    impl<T> fmt::Debug for InterfaceRef<'_, T> {
+        #[inline]
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("InterfaceRef").field(&self.0).finish()
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use crate::metadata::Metadata;
 use proc_macro2::{Span, TokenStream};
+use std::path::PathBuf;
 use syn::{parse::Parse, punctuated::Punctuated, Error, Expr, FieldValue, Member, Token};
 
 pub struct AddonInfo {
@@ -6,16 +8,23 @@ pub struct AddonInfo {
     pub name: Option<Expr>,
     pub load: Option<Expr>,
     pub unload: Option<Expr>,
+    pub state: Option<Expr>,
+    pub panic_policy: Option<Expr>,
     pub flags: Option<Expr>,
     pub provider: Option<Expr>,
     pub update_link: Option<Expr>,
+    pub update_source: Option<Expr>,
+    pub min_api_version: Option<Expr>,
+    pub revision: Option<Expr>,
+    pub namespace: Option<Expr>,
+    pub manifest: Option<PathBuf>,
 
     #[cfg(feature = "log_filter")]
     pub log_filter: Option<Expr>,
 }
 
 impl AddonInfo {
-    fn populate_from_fields(
+    pub fn populate_from_fields(
         mut self,
         span: Span,
         fields: impl IntoIterator<Item = FieldValue>,
@@ -32,9 +41,15 @@ impl AddonInfo {
                     "name" => self.name = Some(field.expr),
                     "load" => self.load = Some(field.expr),
                     "unload" => self.unload = Some(field.expr),
+                    "state" => self.state = Some(field.expr),
+                    "panic_policy" => self.panic_policy = Some(field.expr),
                     "flags" => self.flags = Some(field.expr),
                     "provider" => self.provider = Some(field.expr),
                     "update_link" => self.update_link = Some(field.expr),
+                    "update_source" => self.update_source = Some(field.expr),
+                    "min_api_version" => self.min_api_version = Some(field.expr),
+                    "revision" => self.revision = Some(field.expr),
+                    "namespace" => self.namespace = Some(field.expr),
 
                     #[cfg(feature = "log_filter")]
                     "log_filter" => self.log_filter = Some(field.expr),
@@ -54,10 +69,23 @@ impl AddonInfo {
             }
         }
 
+        // fields missing in the macro are taken from the manifest
+        let mut metadata = Metadata::read(span)?;
+        if !found_signature {
+            if let Some(signature) = metadata.signature.take() {
+                found_signature = true;
+                self.signature = signature;
+            }
+        }
+        self.populate_from_metadata(metadata);
+
         if !found_signature {
             return Err(Error::new(span, "missing signature field"));
         }
 
+        self.resolve_update_source()?;
+        self.validate_update()?;
+
         Ok(self)
     }
 }
@@ -69,9 +97,16 @@ impl Default for AddonInfo {
             name: None,
             load: None,
             unload: None,
+            state: None,
+            panic_policy: None,
             flags: None,
             provider: None,
             update_link: None,
+            update_source: None,
+            min_api_version: None,
+            revision: None,
+            namespace: None,
+            manifest: None,
 
             #[cfg(feature = "log_filter")]
             log_filter: None,
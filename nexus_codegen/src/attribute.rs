@@ -0,0 +1,51 @@
+use crate::addon::AddonInfo;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::Parser, parse_quote, punctuated::Punctuated, Error, FieldValue, ItemStruct, Member,
+    MetaNameValue, Token,
+};
+
+/// Fields generated by the addon attribute.
+const GENERATED_FIELDS: [&str; 3] = ["load", "unload", "state"];
+
+pub fn generate_addon(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let item: ItemStruct = syn::parse2(item)?;
+    if !item.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &item.generics,
+            "addon struct must not be generic",
+        ));
+    }
+
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(attr)?;
+    let mut fields = Vec::new();
+    for arg in args {
+        let ident = arg.path.require_ident()?.clone();
+        if GENERATED_FIELDS.contains(&ident.to_string().as_str()) {
+            return Err(Error::new_spanned(
+                &ident,
+                format!("{ident} is generated by the addon attribute"),
+            ));
+        }
+        fields.push(FieldValue {
+            attrs: Vec::new(),
+            member: Member::Named(ident),
+            colon_token: Some(Default::default()),
+            expr: arg.value,
+        });
+    }
+
+    // the addon is created via its required `Default` impl and stored as state by `load_addon`
+    let ty = &item.ident;
+    let mut info = AddonInfo::default().populate_from_fields(Span::call_site(), fields)?;
+    info.load = Some(parse_quote! { ::nexus::__macro::load_addon::<#ty> });
+    info.unload = Some(parse_quote! { ::nexus::__macro::unload_addon::<#ty> });
+    let export = info.generate_export();
+
+    Ok(quote! {
+        #item
+
+        #export
+    })
+}
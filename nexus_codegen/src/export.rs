@@ -37,12 +37,14 @@ impl AddonInfo {
             .parse::<i16>()
             .expect("crate version not number");
 
+        let revision = expr_or(&self.revision, || quote! { 0 });
+
         quote! {
             ::nexus::addon::AddonVersion {
                 major: #major,
                 minor: #minor,
                 build: #build,
-                revision: 0,
+                revision: #revision,
             }
         }
     }
@@ -71,6 +73,41 @@ impl AddonInfo {
             .unwrap_or_default()
     }
 
+    pub fn generate_state(&self) -> TokenStream {
+        self.state
+            .as_ref()
+            .map(|state| {
+                quote! {
+                    ::nexus::__macro::init_state(#state);
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn generate_panic_policy(&self) -> TokenStream {
+        self.panic_policy
+            .as_ref()
+            .map(|policy| {
+                quote! {
+                    ::nexus::panic::set_panic_policy(#policy);
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn generate_namespace(&self) -> TokenStream {
+        self.namespace
+            .as_ref()
+            .map(|namespace| {
+                quote! {
+                    if #namespace {
+                        ::nexus::__macro::set_namespace(self::__ADDON_NAME);
+                    }
+                }
+            })
+            .unwrap_or_default()
+    }
+
     pub fn generate_update_link(&self) -> TokenStream {
         self.update_link
             .as_ref()
@@ -93,9 +130,12 @@ impl AddonInfo {
         let log_filter = quote! { ::std::option::Option::None };
 
         let initfn = {
-            quote! { ::nexus::__macro::init(api, self::__ADDON_NAME, #log_filter); }
+            quote! { ::nexus::__macro::init(api, &self::__ADDON_DEF, self::__ADDON_NAME, #log_filter); }
         };
 
+        let panic_policy = self.generate_panic_policy();
+        let namespace = self.generate_namespace();
+        let state = self.generate_state();
         let load = self.generate_load();
         let unload = self.generate_unload();
 
@@ -105,16 +145,33 @@ impl AddonInfo {
             || quote! { ::nexus::addon::UpdateProvider::None },
         );
         let update_link = self.generate_update_link();
+        let manifest = self.generate_manifest_dependency();
+        let update_source = self.generate_update_source_check();
+        let api_version = expr_or(
+            &self.min_api_version,
+            || quote! { ::nexus::AddonApi::VERSION },
+        );
 
         quote! {
             mod __nexus_addon_export {
                 use super::*;
 
+                #manifest
+
+                #update_source
+
                 const __ADDON_NAME: &'static ::std::primitive::str = #name;
 
+                const __API_VERSION: ::std::primitive::i32 = #api_version;
+
+                const _: () = ::std::assert!(
+                    ::nexus::compat::is_supported_version(self::__API_VERSION),
+                    "unsupported min_api_version",
+                );
+
                 static __ADDON_DEF: ::nexus::addon::AddonDefinition = ::nexus::addon::AddonDefinition {
                     signature: #signature,
-                    api_version: ::nexus::AddonApi::VERSION,
+                    api_version: self::__API_VERSION,
                     name: #name_ptr,
                     version: #version,
                     author: #author,
@@ -132,12 +189,19 @@ impl AddonInfo {
                 }
 
                 unsafe extern "C-unwind" fn __load_wrapper(api: *const ::nexus::AddonApi) {
-                    #initfn
-                    #load
+                    ::nexus::__macro::catch_load(|| {
+                        #initfn
+                        #panic_policy
+                        #namespace
+                        #state
+                        #load
+                    });
                 }
 
                 unsafe extern "C-unwind" fn __unload_wrapper() {
-                    #unload
+                    ::nexus::__macro::catch_lifecycle("addon unload", || {
+                        #unload
+                    });
                     ::nexus::__macro::deinit();
                 }
             }
@@ -1,5 +1,8 @@
 mod addon;
+mod attribute;
 mod export;
+mod metadata;
+mod update;
 
 #[cfg(feature = "log_filter")]
 mod log_filter;
@@ -9,8 +12,32 @@ use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
 /// Creates addon exports for Raidcore Nexus.
+///
+/// Fields missing in the macro are read from `[package.metadata.nexus]` in the manifest of the crate.
+/// Supported are `signature`, `name`, `flags`, `provider`, `update_link`, `revision` and `namespace`.
+///
+/// ```toml
+/// [package.metadata.nexus]
+/// signature = "-0x12345678"
+/// name = "My Addon"
+/// flags = ["IsVolatile"]
+/// provider = "GitHub"
+/// update_link = "https://github.com/zerthox/nexus-rs"
+/// revision = 1
+/// ```
 #[proc_macro]
 pub fn export(input: TokenStream) -> TokenStream {
     let addon = parse_macro_input!(input as AddonInfo);
     addon.generate_export().into()
 }
+
+/// Creates addon exports for Raidcore Nexus from a struct implementing `Addon`.
+///
+/// Accepts the same fields as [`export!`] except for `load`, `unload` and `state`.
+/// The struct is created via its `Default` implementation on load, which `Addon` requires.
+#[proc_macro_attribute]
+pub fn addon(attr: TokenStream, item: TokenStream) -> TokenStream {
+    attribute::generate_addon(attr.into(), item.into())
+        .unwrap_or_else(|err| err.into_compile_error())
+        .into()
+}
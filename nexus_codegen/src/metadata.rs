@@ -0,0 +1,315 @@
+use crate::addon::AddonInfo;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use std::{env, fs, path::PathBuf};
+use syn::{Error, Expr, ExprLit, Lit, LitStr};
+use toml::{Table, Value};
+
+/// Known addon flags.
+const FLAGS: [&str; 4] = [
+    "None",
+    "IsVolatile",
+    "DisableHotloading",
+    "OnlyLoadDuringGameLaunchSequence",
+];
+
+/// Known update providers.
+const PROVIDERS: [&str; 5] = ["None", "Raidcore", "GitHub", "Direct", "Manual"];
+
+/// Addon information from `[package.metadata.nexus]` in the manifest.
+#[derive(Default)]
+pub struct Metadata {
+    pub manifest: Option<PathBuf>,
+    pub signature: Option<Expr>,
+    pub name: Option<Expr>,
+    pub flags: Option<Expr>,
+    pub provider: Option<Expr>,
+    pub update_link: Option<Expr>,
+    pub revision: Option<Expr>,
+    pub namespace: Option<Expr>,
+}
+
+impl Metadata {
+    /// Reads the metadata from the manifest of the crate invoking the macro.
+    pub fn read(span: Span) -> syn::Result<Self> {
+        let Ok(dir) = env::var("CARGO_MANIFEST_DIR") else {
+            return Ok(Self::default());
+        };
+        let path = PathBuf::from(dir).join("Cargo.toml");
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Ok(Self::default());
+        };
+        let metadata = Self::parse(&content, span)?;
+        Ok(Self {
+            manifest: Some(path),
+            ..metadata
+        })
+    }
+
+    /// Parses the metadata from the contents of a manifest.
+    ///
+    /// Unknown fields are ignored.
+    pub fn parse(content: &str, span: Span) -> syn::Result<Self> {
+        let manifest = content
+            .parse::<Table>()
+            .map_err(|err| Error::new(span, format!("failed to parse manifest: {err}")))?;
+
+        let mut metadata = Self::default();
+        let Some(table) = manifest
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("nexus"))
+        else {
+            return Ok(metadata);
+        };
+        let table = table
+            .as_table()
+            .ok_or_else(|| Error::new(span, "package.metadata.nexus must be a table"))?;
+
+        for (key, value) in table {
+            let err = |msg: &str| Error::new(span, format!("package.metadata.nexus.{key} {msg}"));
+            match key.as_str() {
+                "signature" => {
+                    let signature = match value {
+                        Value::Integer(int) => i32::try_from(*int).ok(),
+                        Value::String(string) => parse_signature(string),
+                        _ => None,
+                    }
+                    .ok_or_else(|| err("must be a 32-bit integer"))?;
+                    metadata.signature = Some(Expr::Verbatim(quote! { #signature }));
+                }
+                "name" => {
+                    let name = value.as_str().ok_or_else(|| err("must be a string"))?;
+                    metadata.name = Some(Expr::Verbatim(quote! { #name }));
+                }
+                "flags" => {
+                    let flags = match value {
+                        Value::String(flag) => vec![flag.as_str()],
+                        Value::Array(flags) => flags
+                            .iter()
+                            .map(|flag| flag.as_str().ok_or_else(|| err("must be strings")))
+                            .collect::<Result<_, _>>()?,
+                        _ => return Err(err("must be a string or array of strings")),
+                    };
+                    let flags = flags
+                        .into_iter()
+                        .map(|flag| {
+                            if FLAGS.contains(&flag) {
+                                let flag = format_ident!("{flag}");
+                                Ok(quote! { ::nexus::addon::AddonFlags::#flag.bits() })
+                            } else {
+                                Err(err(&format!("contains unknown flag {flag:?}")))
+                            }
+                        })
+                        .collect::<syn::Result<Vec<_>>>()?;
+                    metadata.flags = Some(Expr::Verbatim(quote! {
+                        ::nexus::addon::AddonFlags::from_bits_retain(0 #(| #flags)*)
+                    }));
+                }
+                "provider" => {
+                    let provider = value.as_str().ok_or_else(|| err("must be a string"))?;
+                    if !PROVIDERS.contains(&provider) {
+                        return Err(err(&format!("has unknown provider {provider:?}")));
+                    }
+                    let provider = format_ident!("{provider}");
+                    metadata.provider = Some(Expr::Verbatim(
+                        quote! { ::nexus::addon::UpdateProvider::#provider },
+                    ));
+                }
+                "update_link" => {
+                    let link = value.as_str().ok_or_else(|| err("must be a string"))?;
+                    metadata.update_link = Some(Expr::Lit(ExprLit {
+                        attrs: Vec::new(),
+                        lit: Lit::Str(LitStr::new(link, span)),
+                    }));
+                }
+                "revision" => {
+                    let revision = value
+                        .as_integer()
+                        .and_then(|int| i16::try_from(int).ok())
+                        .ok_or_else(|| err("must be a 16-bit integer"))?;
+                    metadata.revision = Some(Expr::Verbatim(quote! { #revision }));
+                }
+                "namespace" => {
+                    let namespace = value.as_bool().ok_or_else(|| err("must be a boolean"))?;
+                    metadata.namespace = Some(Expr::Verbatim(quote! { #namespace }));
+                }
+                // may be used by other tools
+                _ => {}
+            }
+        }
+
+        Ok(metadata)
+    }
+}
+
+/// Parses a signature string like `"-0x12345678"`.
+fn parse_signature(string: &str) -> Option<i32> {
+    let (negative, string) = match string.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, string),
+    };
+    let value = match string
+        .strip_prefix("0x")
+        .or_else(|| string.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => string.parse::<i64>().ok()?,
+    };
+    i32::try_from(if negative { -value } else { value }).ok()
+}
+
+impl AddonInfo {
+    /// Fills fields missing in the macro from the manifest metadata.
+    pub fn populate_from_metadata(&mut self, metadata: Metadata) {
+        self.manifest = metadata.manifest;
+        if self.name.is_none() {
+            self.name = metadata.name;
+        }
+        if self.flags.is_none() {
+            self.flags = metadata.flags;
+        }
+        // update source in the macro replaces provider and link of the manifest
+        if self.update_source.is_none() {
+            if self.provider.is_none() {
+                self.provider = metadata.provider;
+            }
+            if self.update_link.is_none() {
+                self.update_link = metadata.update_link;
+            }
+        }
+        if self.revision.is_none() {
+            self.revision = metadata.revision;
+        }
+        if self.namespace.is_none() {
+            self.namespace = metadata.namespace;
+        }
+    }
+
+    /// Generates a dependency on the manifest, so changes to the metadata are picked up.
+    pub fn generate_manifest_dependency(&self) -> TokenStream {
+        self.manifest
+            .as_ref()
+            .and_then(|path| path.to_str())
+            .map(|path| quote! { const _: &[u8] = ::std::include_bytes!(#path); })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn parse(content: &str) -> Metadata {
+        Metadata::parse(content, Span::call_site())
+            .unwrap_or_else(|err| panic!("failed to parse: {err}"))
+    }
+
+    fn parse_err(content: &str) -> String {
+        match Metadata::parse(content, Span::call_site()) {
+            Ok(_) => panic!("parsed invalid metadata"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn tokens(expr: &Option<Expr>) -> Option<String> {
+        expr.as_ref().map(|expr| expr.to_token_stream().to_string())
+    }
+
+    #[test]
+    fn missing() {
+        let metadata = parse("[package]\nname = \"addon\"");
+        assert!(metadata.manifest.is_none());
+        assert!(metadata.signature.is_none());
+        assert!(metadata.name.is_none());
+        assert!(metadata.flags.is_none());
+        assert!(metadata.provider.is_none());
+        assert!(metadata.update_link.is_none());
+        assert!(metadata.revision.is_none());
+        assert!(metadata.namespace.is_none());
+    }
+
+    #[test]
+    fn fields() {
+        let metadata = parse(
+            r#"
+            [package.metadata.nexus]
+            signature = "-0x1234"
+            name = "My Addon"
+            flags = ["IsVolatile", "DisableHotloading"]
+            provider = "GitHub"
+            update_link = "https://github.com/zerthox/nexus-rs"
+            revision = 3
+            namespace = true
+            "#,
+        );
+        assert_eq!(tokens(&metadata.signature).as_deref(), Some("- 4660i32"));
+        assert_eq!(tokens(&metadata.name).as_deref(), Some("\"My Addon\""));
+        assert_eq!(
+            tokens(&metadata.flags).as_deref(),
+            Some(
+                ":: nexus :: addon :: AddonFlags :: from_bits_retain (0 \
+                 | :: nexus :: addon :: AddonFlags :: IsVolatile . bits () \
+                 | :: nexus :: addon :: AddonFlags :: DisableHotloading . bits ())"
+            )
+        );
+        assert_eq!(
+            tokens(&metadata.provider).as_deref(),
+            Some(":: nexus :: addon :: UpdateProvider :: GitHub")
+        );
+        assert!(matches!(
+            &metadata.update_link,
+            Some(Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }))
+                if lit.value() == "https://github.com/zerthox/nexus-rs"
+        ));
+        assert_eq!(tokens(&metadata.revision).as_deref(), Some("3i16"));
+        assert_eq!(tokens(&metadata.namespace).as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let metadata = parse(
+            r#"
+            [package.metadata.nexus]
+            name = "My Addon"
+            icon = "icon.png"
+            "#,
+        );
+        assert_eq!(tokens(&metadata.name).as_deref(), Some("\"My Addon\""));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            parse_err("[package.metadata]\nnexus = 1"),
+            "package.metadata.nexus must be a table"
+        );
+        assert_eq!(
+            parse_err("[package.metadata.nexus]\nsignature = 4294967296"),
+            "package.metadata.nexus.signature must be a 32-bit integer"
+        );
+        assert_eq!(
+            parse_err("[package.metadata.nexus]\nflags = [\"Unknown\"]"),
+            "package.metadata.nexus.flags contains unknown flag \"Unknown\""
+        );
+        assert_eq!(
+            parse_err("[package.metadata.nexus]\nprovider = \"Unknown\""),
+            "package.metadata.nexus.provider has unknown provider \"Unknown\""
+        );
+        assert_eq!(
+            parse_err("[package.metadata.nexus]\nupdate_link = 1"),
+            "package.metadata.nexus.update_link must be a string"
+        );
+        assert!(parse_err("[package").starts_with("failed to parse manifest"));
+    }
+
+    #[test]
+    fn signature() {
+        assert_eq!(parse_signature("123"), Some(123));
+        assert_eq!(parse_signature("-0x12345678"), Some(-0x12345678));
+        assert_eq!(parse_signature("0XFF"), Some(0xFF));
+        assert_eq!(parse_signature("0x80000000"), None);
+        assert_eq!(parse_signature("abc"), None);
+    }
+}
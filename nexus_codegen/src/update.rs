@@ -0,0 +1,314 @@
+use crate::addon::AddonInfo;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, Expr, ExprLit, ExprPath, Lit, LitStr, Member, Path};
+
+/// Returns the last segment of a path as string.
+fn last_segment(path: &Path) -> Option<String> {
+    path.segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+/// Returns the string literal of an expression.
+fn lit_str(expr: &Expr) -> Option<&LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Some(lit),
+        _ => None,
+    }
+}
+
+/// Returns the name of the update provider variant, if it can be determined.
+fn provider_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(ExprPath { path, .. }) => last_segment(path),
+        Expr::Verbatim(tokens) => syn::parse2::<ExprPath>(tokens.clone())
+            .ok()
+            .and_then(|expr| last_segment(&expr.path)),
+        _ => None,
+    }
+}
+
+impl AddonInfo {
+    /// Converts the update source into update provider and link.
+    pub fn resolve_update_source(&mut self) -> syn::Result<()> {
+        let Some(source) = self.update_source.clone() else {
+            return Ok(());
+        };
+        if let Some(provider) = &self.provider {
+            return Err(Error::new_spanned(
+                provider,
+                "provider conflicts with update_source",
+            ));
+        }
+        if let Some(link) = &self.update_link {
+            return Err(Error::new_spanned(
+                link,
+                "update_link conflicts with update_source",
+            ));
+        }
+
+        let (variant, link) = match &source {
+            Expr::Path(expr) => {
+                let variant = last_segment(&expr.path);
+                if let Some(variant @ ("GitHub" | "Direct")) = variant.as_deref() {
+                    return Err(Error::new_spanned(
+                        &source,
+                        format!("{variant} update source requires a link"),
+                    ));
+                }
+                (variant, None)
+            }
+            Expr::Struct(expr) => {
+                let variant = last_segment(&expr.path);
+                let expected = match variant.as_deref() {
+                    Some("GitHub") => "repo",
+                    Some("Direct") => "url",
+                    _ => return Err(Error::new_spanned(&source, "update source has no link")),
+                };
+                let mut link = None;
+                for field in &expr.fields {
+                    match &field.member {
+                        Member::Named(ident) if ident == expected => {
+                            link = Some(lit_str(&field.expr).ok_or_else(|| {
+                                Error::new_spanned(&field.expr, "link must be a string literal")
+                            })?)
+                        }
+                        member => {
+                            return Err(Error::new_spanned(
+                                member,
+                                format!("expected field {expected}"),
+                            ))
+                        }
+                    }
+                }
+                let link = link.ok_or_else(|| {
+                    Error::new_spanned(&source, format!("missing field {expected}"))
+                })?;
+                (variant, Some(link.clone()))
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &source,
+                    "update_source must be an UpdateSource variant",
+                ))
+            }
+        };
+
+        let variant = match variant.as_deref() {
+            Some(variant @ ("None" | "Raidcore" | "GitHub" | "Direct" | "Manual")) => variant,
+            _ => return Err(Error::new_spanned(&source, "unknown update source")),
+        };
+        let provider = format_ident!("{variant}");
+        self.provider = Some(Expr::Verbatim(
+            quote! { ::nexus::addon::UpdateProvider::#provider },
+        ));
+        self.update_link = link.map(|link| {
+            Expr::Lit(ExprLit {
+                attrs: Vec::new(),
+                lit: Lit::Str(link),
+            })
+        });
+        Ok(())
+    }
+
+    /// Generates a type check for the update source.
+    pub fn generate_update_source_check(&self) -> TokenStream {
+        self.update_source
+            .as_ref()
+            .map(|source| quote! { const _: ::nexus::addon::UpdateSource = #source; })
+            .unwrap_or_default()
+    }
+
+    /// Validates the combination of update provider and link.
+    ///
+    /// Providers which can not be determined at compile time are not validated.
+    pub fn validate_update(&self) -> syn::Result<()> {
+        let provider = match &self.provider {
+            Some(expr) => match provider_name(expr) {
+                Some(name) => name,
+                None => return Ok(()),
+            },
+            None => "None".into(),
+        };
+
+        match (provider.as_str(), &self.update_link) {
+            ("GitHub" | "Direct", None) => Err(Error::new_spanned(
+                self.provider.as_ref(),
+                format!("{provider} provider requires an update link"),
+            )),
+            ("None", Some(link)) => Err(Error::new_spanned(
+                link,
+                "update link requires an update provider",
+            )),
+            (_, Some(link)) => match lit_str(link) {
+                Some(lit) if lit.value().is_empty() => {
+                    Err(Error::new_spanned(link, "update link must not be empty"))
+                }
+                Some(lit)
+                    if provider == "GitHub" && !lit.value().starts_with("https://github.com/") =>
+                {
+                    Err(Error::new_spanned(
+                        link,
+                        "GitHub update link must be a GitHub repository URL",
+                    ))
+                }
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Metadata;
+    use proc_macro2::Span;
+    use quote::ToTokens;
+
+    fn parse(fields: &str) -> syn::Result<AddonInfo> {
+        syn::parse_str(&format!("signature: -1, {fields}"))
+    }
+
+    fn parse_err(fields: &str) -> String {
+        match parse(fields) {
+            Ok(_) => panic!("accepted invalid update fields"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn with_manifest(update_source: Option<&str>, manifest: &str) -> syn::Result<AddonInfo> {
+        let mut info = AddonInfo {
+            update_source: update_source.map(syn::parse_str).transpose()?,
+            ..AddonInfo::default()
+        };
+        info.populate_from_metadata(Metadata::parse(manifest, Span::call_site())?);
+        info.resolve_update_source()?;
+        info.validate_update()?;
+        Ok(info)
+    }
+
+    fn tokens(expr: &Option<Expr>) -> Option<String> {
+        expr.as_ref().map(|expr| expr.to_token_stream().to_string())
+    }
+
+    #[test]
+    fn provider() {
+        assert!(parse("").is_ok());
+        assert!(parse("provider: UpdateProvider::Raidcore").is_ok());
+        assert!(parse(
+            "provider: UpdateProvider::GitHub, update_link: \"https://github.com/zerthox/nexus-rs\""
+        )
+        .is_ok());
+        assert!(
+            parse("provider: UpdateProvider::Direct, update_link: \"https://example.com\"").is_ok()
+        );
+
+        // not determined at compile time
+        assert!(parse("provider: provider()").is_ok());
+    }
+
+    #[test]
+    fn provider_invalid() {
+        assert_eq!(
+            parse_err("provider: UpdateProvider::GitHub"),
+            "GitHub provider requires an update link"
+        );
+        assert_eq!(
+            parse_err("update_link: \"https://example.com\""),
+            "update link requires an update provider"
+        );
+        assert_eq!(
+            parse_err("provider: UpdateProvider::Direct, update_link: \"\""),
+            "update link must not be empty"
+        );
+        assert_eq!(
+            parse_err("provider: UpdateProvider::GitHub, update_link: \"https://example.com\""),
+            "GitHub update link must be a GitHub repository URL"
+        );
+    }
+
+    #[test]
+    fn source() {
+        let info = parse(
+            "update_source: UpdateSource::GitHub { repo: \"https://github.com/zerthox/nexus-rs\" }",
+        )
+        .unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(
+            tokens(&info.provider).as_deref(),
+            Some(":: nexus :: addon :: UpdateProvider :: GitHub")
+        );
+        assert_eq!(
+            tokens(&info.update_link).as_deref(),
+            Some("\"https://github.com/zerthox/nexus-rs\"")
+        );
+
+        let info =
+            parse("update_source: UpdateSource::Raidcore").unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(
+            tokens(&info.provider).as_deref(),
+            Some(":: nexus :: addon :: UpdateProvider :: Raidcore")
+        );
+        assert!(info.update_link.is_none());
+    }
+
+    #[test]
+    fn source_invalid() {
+        assert_eq!(
+            parse_err("update_source: UpdateSource::Raidcore, provider: UpdateProvider::Raidcore"),
+            "provider conflicts with update_source"
+        );
+        assert_eq!(
+            parse_err("update_source: UpdateSource::None, update_link: \"https://example.com\""),
+            "update_link conflicts with update_source"
+        );
+        assert_eq!(
+            parse_err("update_source: UpdateSource::GitHub"),
+            "GitHub update source requires a link"
+        );
+        assert_eq!(
+            parse_err("update_source: UpdateSource::Direct { repo: \"https://example.com\" }"),
+            "expected field url"
+        );
+        assert_eq!(
+            parse_err("update_source: UpdateSource::Direct { url: LINK }"),
+            "link must be a string literal"
+        );
+        assert_eq!(
+            parse_err("update_source: UpdateSource::GitHub { repo: \"https://example.com\" }"),
+            "GitHub update link must be a GitHub repository URL"
+        );
+        assert_eq!(
+            parse_err("update_source: UpdateSource::Unknown"),
+            "unknown update source"
+        );
+    }
+
+    #[test]
+    fn manifest() {
+        let manifest = r#"
+            [package.metadata.nexus]
+            provider = "GitHub"
+            update_link = "https://example.com"
+        "#;
+        assert_eq!(
+            with_manifest(None, manifest)
+                .err()
+                .map(|err| err.to_string())
+                .as_deref(),
+            Some("GitHub update link must be a GitHub repository URL")
+        );
+
+        // update source replaces provider and link of the manifest
+        let info = with_manifest(Some("UpdateSource::Raidcore"), manifest)
+            .unwrap_or_else(|err| panic!("{err}"));
+        assert_eq!(
+            tokens(&info.provider).as_deref(),
+            Some(":: nexus :: addon :: UpdateProvider :: Raidcore")
+        );
+        assert!(info.update_link.is_none());
+    }
+}
@@ -7,7 +7,7 @@ use nexus::{
     paths::get_addon_dir,
     quick_access::add_quick_access,
     texture::{load_texture_from_file, texture_receive, Texture},
-    AddonFlags, UpdateProvider,
+    AddonFlags, UpdateSource,
 };
 use std::{cell::Cell, ffi::CStr};
 
@@ -17,8 +17,9 @@ nexus::export! {
     load,
     unload,
     flags: AddonFlags::None,
-    provider: UpdateProvider::GitHub,
-    update_link: "https://github.com/zerthox/nexus-rs",
+    update_source: UpdateSource::GitHub {
+        repo: "https://github.com/zerthox/nexus-rs"
+    },
     log_filter: "debug"
 }
 
@@ -0,0 +1,107 @@
+use crate::{
+    call::Call,
+    state::{string_from_c, with_state},
+};
+use nexus::event::RawEventConsumeUnknown;
+use std::{
+    ffi::{c_char, c_void},
+    ptr,
+};
+
+/// Invokes all callbacks subscribed to the event.
+pub fn dispatch(identifier: &str, event_data: *const c_void) {
+    let callbacks = with_state(|state| state.events.get(identifier).cloned().unwrap_or_default());
+    for callback in callbacks {
+        callback(event_data);
+    }
+}
+
+pub unsafe extern "C-unwind" fn raise(identifier: *const c_char, event_data: *const c_void) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::EventRaise {
+            identifier: identifier.clone(),
+        })
+    });
+    dispatch(&identifier, event_data)
+}
+
+pub unsafe extern "C-unwind" fn raise_notification(identifier: *const c_char) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::EventRaiseNotification {
+            identifier: identifier.clone(),
+        })
+    });
+    dispatch(&identifier, ptr::null())
+}
+
+pub unsafe extern "C-unwind" fn raise_targeted(
+    signature: i32,
+    identifier: *const c_char,
+    event_data: *const c_void,
+) {
+    let identifier = string_from_c(identifier);
+    let is_target = with_state(|state| {
+        state.record(Call::EventRaiseTargeted {
+            signature,
+            identifier: identifier.clone(),
+        });
+        state.signature == Some(signature)
+    });
+    if is_target {
+        dispatch(&identifier, event_data)
+    }
+}
+
+pub unsafe extern "C-unwind" fn raise_notification_targeted(
+    signature: i32,
+    identifier: *const c_char,
+) {
+    let identifier = string_from_c(identifier);
+    let is_target = with_state(|state| {
+        state.record(Call::EventRaiseNotificationTargeted {
+            signature,
+            identifier: identifier.clone(),
+        });
+        state.signature == Some(signature)
+    });
+    if is_target {
+        dispatch(&identifier, ptr::null())
+    }
+}
+
+pub unsafe extern "C-unwind" fn subscribe(
+    identifier: *const c_char,
+    consume_callback: RawEventConsumeUnknown,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::EventSubscribe {
+            identifier: identifier.clone(),
+        });
+        state
+            .events
+            .entry(identifier)
+            .or_default()
+            .push(consume_callback);
+    })
+}
+
+pub unsafe extern "C-unwind" fn unsubscribe(
+    identifier: *const c_char,
+    consume_callback: RawEventConsumeUnknown,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        if let Some(callbacks) = state.events.get_mut(&identifier) {
+            if let Some(index) = callbacks
+                .iter()
+                .position(|callback| *callback as usize == consume_callback as usize)
+            {
+                callbacks.remove(index);
+            }
+        }
+        state.record(Call::EventUnsubscribe { identifier });
+    })
+}
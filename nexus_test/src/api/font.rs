@@ -0,0 +1,151 @@
+use crate::{
+    call::Call,
+    state::{string_from_c, with_state, HostState},
+};
+use nexus::{font::RawFontReceive, imgui::sys::ImFontConfig, win::HMODULE};
+use std::{
+    ffi::{c_char, c_void},
+    path::PathBuf,
+};
+
+/// Delivers all pending fonts to their receive callbacks.
+///
+/// Every font is backed by the default ImGui font.
+pub fn deliver_pending() {
+    let pending = with_state(|state| {
+        let font = state.font;
+        let pending = std::mem::take(&mut state.pending_fonts);
+        pending
+            .into_iter()
+            .map(|(identifier, callback)| (state.intern(identifier), font, callback))
+            .collect::<Vec<_>>()
+    });
+    for (identifier, font, callback) in pending {
+        unsafe { callback(identifier, font) };
+    }
+}
+
+/// Adds or resizes a font and queues delivery to all its receivers.
+fn add_font(state: &mut HostState, identifier: String, font_size: f32, callback: RawFontReceive) {
+    let font = state.fonts.entry(identifier.clone()).or_default();
+    font.size = Some(font_size);
+    if !font
+        .receivers
+        .iter()
+        .any(|receiver| *receiver as usize == callback as usize)
+    {
+        font.receivers.push(callback);
+    }
+    let receivers = font.receivers.clone();
+    state.pending_fonts.extend(
+        receivers
+            .into_iter()
+            .map(|receiver| (identifier.clone(), receiver)),
+    );
+}
+
+pub unsafe extern "C-unwind" fn get(identifier: *const c_char, callback: RawFontReceive) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::FontGet {
+            identifier: identifier.clone(),
+        });
+        let font = state.fonts.entry(identifier.clone()).or_default();
+        font.receivers.push(callback);
+        if font.size.is_some() {
+            state.pending_fonts.push((identifier, callback));
+        }
+    })
+}
+
+pub unsafe extern "C-unwind" fn release(identifier: *const c_char, callback: RawFontReceive) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        if let Some(font) = state.fonts.get_mut(&identifier) {
+            font.receivers
+                .retain(|receiver| *receiver as usize != callback as usize);
+        }
+        state
+            .pending_fonts
+            .retain(|(id, receiver)| *id != identifier || *receiver as usize != callback as usize);
+        state.record(Call::FontRelease { identifier });
+    })
+}
+
+pub unsafe extern "C-unwind" fn add_from_file(
+    identifier: *const c_char,
+    font_size: f32,
+    filename: *const c_char,
+    callback: RawFontReceive,
+    _config: *mut ImFontConfig,
+) {
+    let identifier = string_from_c(identifier);
+    let file = PathBuf::from(string_from_c(filename));
+    with_state(|state| {
+        state.record(Call::FontAddFromFile {
+            identifier: identifier.clone(),
+            font_size,
+            file,
+        });
+        add_font(state, identifier, font_size, callback);
+    })
+}
+
+pub unsafe extern "C-unwind" fn add_from_resource(
+    identifier: *const c_char,
+    font_size: f32,
+    resource_id: u32,
+    _module: HMODULE,
+    callback: RawFontReceive,
+    _config: *mut ImFontConfig,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::FontAddFromResource {
+            identifier: identifier.clone(),
+            font_size,
+            resource_id,
+        });
+        add_font(state, identifier, font_size, callback);
+    })
+}
+
+pub unsafe extern "C-unwind" fn add_from_memory(
+    identifier: *const c_char,
+    font_size: f32,
+    _data: *const c_void,
+    size: usize,
+    callback: RawFontReceive,
+    _config: *mut ImFontConfig,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::FontAddFromMemory {
+            identifier: identifier.clone(),
+            font_size,
+            size,
+        });
+        add_font(state, identifier, font_size, callback);
+    })
+}
+
+pub unsafe extern "C-unwind" fn resize(identifier: *const c_char, font_size: f32) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::FontResize {
+            identifier: identifier.clone(),
+            font_size,
+        });
+        if let Some(font) = state.fonts.get_mut(&identifier) {
+            if font.size.is_some() {
+                font.size = Some(font_size);
+                let receivers = font.receivers.clone();
+                state.pending_fonts.extend(
+                    receivers
+                        .into_iter()
+                        .map(|receiver| (identifier.clone(), receiver)),
+                );
+            }
+        }
+    })
+}
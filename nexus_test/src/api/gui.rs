@@ -0,0 +1,56 @@
+use crate::{
+    call::Call,
+    state::{string_from_c, with_state},
+};
+use nexus::gui::{RawGuiRender, RenderType};
+use std::ffi::c_char;
+
+pub unsafe extern "C-unwind" fn register_render(
+    render_type: RenderType,
+    render_callback: RawGuiRender,
+) {
+    with_state(|state| {
+        state.record(Call::RegisterRender { render_type });
+        state.renders.push((render_type, render_callback));
+    })
+}
+
+pub unsafe extern "C-unwind" fn deregister_render(render_callback: RawGuiRender) {
+    with_state(|state| {
+        state.record(Call::DeregisterRender);
+        state
+            .renders
+            .retain(|(_, callback)| *callback as usize != render_callback as usize);
+    })
+}
+
+pub unsafe extern "C-unwind" fn send_alert(message: *const c_char) {
+    let message = string_from_c(message);
+    with_state(|state| {
+        state.record(Call::SendAlert {
+            message: message.clone(),
+        });
+        state.alerts.push(message);
+    })
+}
+
+pub unsafe extern "C-unwind" fn register_close_on_escape(
+    window_name: *const c_char,
+    is_visible: *mut bool,
+) {
+    let window_name = string_from_c(window_name);
+    with_state(|state| {
+        state.record(Call::RegisterCloseOnEscape {
+            window_name: window_name.clone(),
+        });
+        state.close_on_escape.insert(window_name, is_visible);
+    })
+}
+
+pub unsafe extern "C-unwind" fn deregister_close_on_escape(window_name: *const c_char) {
+    let window_name = string_from_c(window_name);
+    with_state(|state| {
+        state.close_on_escape.remove(&window_name);
+        state.record(Call::DeregisterCloseOnEscape { window_name });
+    })
+}
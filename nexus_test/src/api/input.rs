@@ -0,0 +1,183 @@
+use crate::{
+    call::Call,
+    state::{string_from_c, with_state, KeybindBinding, KeybindHandler},
+};
+use nexus::{
+    gamebind::GameBind,
+    keybind::{Keybind, RawKeybindHandler, RawKeybindHandlerOld},
+    win::{HWND, LPARAM, LRESULT, WPARAM},
+    wnd_proc::RawWndProcCallback,
+};
+use std::ffi::{c_char, CString};
+
+/// Invokes the handler of a registered keybind.
+///
+/// Returns `false` if the keybind is not registered.
+pub fn dispatch_keybind(identifier: &str, is_release: bool) -> bool {
+    let handler = with_state(|state| state.keybinds.get(identifier).map(|(handler, _)| *handler));
+    if let Some(handler) = handler {
+        let identifier = CString::new(identifier).expect("keybind identifier contains nul");
+        match handler {
+            KeybindHandler::New(handler) => handler(identifier.as_ptr(), is_release),
+            KeybindHandler::Old(handler) => {
+                if !is_release {
+                    handler(identifier.as_ptr())
+                }
+            }
+        }
+        true
+    } else {
+        false
+    }
+}
+
+/// Invokes the registered WNDPROC callbacks until one consumes the message.
+///
+/// Returns `true` if the message was consumed.
+pub fn dispatch_wnd_proc(h_wnd: HWND, u_msg: u32, w_param: WPARAM, l_param: LPARAM) -> bool {
+    let callbacks = with_state(|state| state.wnd_procs.clone());
+    callbacks
+        .into_iter()
+        .any(|callback| callback(h_wnd, u_msg, w_param, l_param) == 0)
+}
+
+pub unsafe extern "C-unwind" fn register_wnd_proc(wnd_proc_callback: RawWndProcCallback) {
+    with_state(|state| {
+        state.record(Call::RegisterWndProc);
+        state.wnd_procs.push(wnd_proc_callback);
+    })
+}
+
+pub unsafe extern "C-unwind" fn deregister_wnd_proc(wnd_proc_callback: RawWndProcCallback) {
+    with_state(|state| {
+        state.record(Call::DeregisterWndProc);
+        state
+            .wnd_procs
+            .retain(|callback| *callback as usize != wnd_proc_callback as usize);
+    })
+}
+
+pub unsafe extern "C-unwind" fn send_wnd_proc_to_game(
+    _h_wnd: HWND,
+    u_msg: u32,
+    _w_param: WPARAM,
+    _l_param: LPARAM,
+) -> LRESULT {
+    with_state(|state| state.record(Call::SendWndProcToGame { u_msg }));
+    LRESULT(0)
+}
+
+pub unsafe extern "C-unwind" fn keybind_invoke(identifier: *const c_char, is_release: bool) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::KeybindInvoke {
+            identifier: identifier.clone(),
+            is_release,
+        })
+    });
+    dispatch_keybind(&identifier, is_release);
+}
+
+pub unsafe extern "C-unwind" fn keybind_register_with_string(
+    identifier: *const c_char,
+    keybind_handler: RawKeybindHandler,
+    keybind: *const c_char,
+) {
+    register_with_string(identifier, KeybindHandler::New(keybind_handler), keybind)
+}
+
+pub unsafe extern "C-unwind" fn keybind_register_with_string_old(
+    identifier: *const c_char,
+    keybind_handler: RawKeybindHandlerOld,
+    keybind: *const c_char,
+) {
+    register_with_string(identifier, KeybindHandler::Old(keybind_handler), keybind)
+}
+
+unsafe fn register_with_string(
+    identifier: *const c_char,
+    keybind_handler: KeybindHandler,
+    keybind: *const c_char,
+) {
+    let identifier = string_from_c(identifier);
+    let keybind = string_from_c(keybind);
+    with_state(|state| {
+        state.record(Call::KeybindRegisterWithString {
+            identifier: identifier.clone(),
+            keybind: keybind.clone(),
+        });
+        state.keybinds.insert(
+            identifier,
+            (keybind_handler, KeybindBinding::String(keybind)),
+        );
+    })
+}
+
+pub unsafe extern "C-unwind" fn keybind_register_with_struct(
+    identifier: *const c_char,
+    keybind_handler: RawKeybindHandler,
+    keybind: Keybind,
+) {
+    register_with_struct(identifier, KeybindHandler::New(keybind_handler), keybind)
+}
+
+pub unsafe extern "C-unwind" fn keybind_register_with_struct_old(
+    identifier: *const c_char,
+    keybind_handler: RawKeybindHandlerOld,
+    keybind: Keybind,
+) {
+    register_with_struct(identifier, KeybindHandler::Old(keybind_handler), keybind)
+}
+
+unsafe fn register_with_struct(
+    identifier: *const c_char,
+    keybind_handler: KeybindHandler,
+    keybind: Keybind,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::KeybindRegisterWithStruct {
+            identifier: identifier.clone(),
+            keybind: keybind.clone(),
+        });
+        state.keybinds.insert(
+            identifier,
+            (keybind_handler, KeybindBinding::Struct(keybind)),
+        );
+    })
+}
+
+pub unsafe extern "C-unwind" fn keybind_deregister(identifier: *const c_char) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.keybinds.remove(&identifier);
+        state.record(Call::KeybindDeregister { identifier });
+    })
+}
+
+pub unsafe extern "C-unwind" fn gamebind_press_async(bind: GameBind) {
+    with_state(|state| state.record(Call::GameBindPressAsync { bind }))
+}
+
+pub unsafe extern "C-unwind" fn gamebind_release_async(bind: GameBind) {
+    with_state(|state| state.record(Call::GameBindReleaseAsync { bind }))
+}
+
+pub unsafe extern "C-unwind" fn gamebind_invoke_async(bind: GameBind, duration: i32) {
+    with_state(|state| state.record(Call::GameBindInvokeAsync { bind, duration }))
+}
+
+pub unsafe extern "C-unwind" fn gamebind_press(bind: GameBind) {
+    with_state(|state| state.record(Call::GameBindPress { bind }))
+}
+
+pub unsafe extern "C-unwind" fn gamebind_release(bind: GameBind) {
+    with_state(|state| state.record(Call::GameBindRelease { bind }))
+}
+
+pub unsafe extern "C-unwind" fn gamebind_is_bound(bind: GameBind) -> bool {
+    with_state(|state| {
+        state.record(Call::GameBindIsBound { bind });
+        state.bound_gamebinds.contains(&bind)
+    })
+}
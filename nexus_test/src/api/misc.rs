@@ -0,0 +1,182 @@
+use crate::{
+    call::{Call, LogEntry},
+    state::{string_from_c, with_state},
+};
+use nexus::{hook::HookStatus, log::LogLevel};
+use std::{
+    ffi::{c_char, c_void},
+    ptr,
+};
+
+pub unsafe extern "C-unwind" fn request_update(signature: i32, update_url: *const c_char) {
+    let update_url = string_from_c(update_url);
+    with_state(|state| {
+        state.record(Call::RequestUpdate {
+            signature,
+            update_url,
+        })
+    })
+}
+
+pub unsafe extern "C-unwind" fn log(
+    level: LogLevel,
+    channel: *const c_char,
+    message: *const c_char,
+) {
+    let channel = string_from_c(channel);
+    let message = string_from_c(message);
+    with_state(|state| {
+        state.record(Call::Log {
+            level,
+            channel: channel.clone(),
+            message: message.clone(),
+        });
+        state.logs.push(LogEntry {
+            level,
+            channel,
+            message,
+        });
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_game_dir() -> *const c_char {
+    with_state(|state| {
+        state.record(Call::GetGameDir);
+        let dir = state.game_dir.to_string_lossy().into_owned();
+        state.intern(dir)
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_addon_dir(name: *const c_char) -> *const c_char {
+    let name = string_from_c(name);
+    with_state(|state| {
+        let dir = state
+            .game_dir
+            .join("addons")
+            .join(&name)
+            .to_string_lossy()
+            .into_owned();
+        state.record(Call::GetAddonDir { name });
+        state.intern(dir)
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_common_dir() -> *const c_char {
+    with_state(|state| {
+        state.record(Call::GetCommonDir);
+        let dir = state
+            .game_dir
+            .join("addons")
+            .join("common")
+            .to_string_lossy()
+            .into_owned();
+        state.intern(dir)
+    })
+}
+
+pub unsafe extern "system-unwind" fn hook_create(
+    _target: *const c_void,
+    _detour: *const c_void,
+    _trampoline: *mut *const c_void,
+) -> HookStatus {
+    with_state(|state| state.record(Call::HookCreate));
+    HookStatus::ErrorNotInitialized
+}
+
+pub unsafe extern "system-unwind" fn hook_remove(_target: *const c_void) -> HookStatus {
+    with_state(|state| state.record(Call::HookRemove));
+    HookStatus::ErrorNotInitialized
+}
+
+pub unsafe extern "system-unwind" fn hook_enable(_target: *const c_void) -> HookStatus {
+    with_state(|state| state.record(Call::HookEnable));
+    HookStatus::ErrorNotInitialized
+}
+
+pub unsafe extern "system-unwind" fn hook_disable(_target: *const c_void) -> HookStatus {
+    with_state(|state| state.record(Call::HookDisable));
+    HookStatus::ErrorNotInitialized
+}
+
+pub unsafe extern "C-unwind" fn data_link_get(identifier: *const c_char) -> *const c_void {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        let resource = state
+            .resources
+            .get_mut(&identifier)
+            .map(|resource| resource.as_mut_ptr().cast_const().cast())
+            .unwrap_or(ptr::null());
+        state.record(Call::DataLinkGet { identifier });
+        resource
+    })
+}
+
+pub unsafe extern "C-unwind" fn data_link_share(
+    identifier: *const c_char,
+    resource_size: usize,
+) -> *mut c_void {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::DataLinkShare {
+            identifier: identifier.clone(),
+            size: resource_size,
+        });
+        state.share(identifier, resource_size).cast()
+    })
+}
+
+pub unsafe extern "C-unwind" fn translate(identifier: *const c_char) -> *const c_char {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::Translate {
+            identifier: identifier.clone(),
+        });
+        let language = state.language.clone();
+        let string = state
+            .translations
+            .get(&(identifier.clone(), language))
+            .cloned()
+            .unwrap_or(identifier);
+        state.intern(string)
+    })
+}
+
+pub unsafe extern "C-unwind" fn translate_to(
+    identifier: *const c_char,
+    language_identifier: *const c_char,
+) -> *const c_char {
+    let identifier = string_from_c(identifier);
+    let language_identifier = string_from_c(language_identifier);
+    with_state(|state| {
+        state.record(Call::TranslateTo {
+            identifier: identifier.clone(),
+            language_identifier: language_identifier.clone(),
+        });
+        let string = state
+            .translations
+            .get(&(identifier.clone(), language_identifier))
+            .cloned()
+            .unwrap_or(identifier);
+        state.intern(string)
+    })
+}
+
+pub unsafe extern "C-unwind" fn set_translation(
+    identifier: *const c_char,
+    language_identifier: *const c_char,
+    string: *const c_char,
+) {
+    let identifier = string_from_c(identifier);
+    let language_identifier = string_from_c(language_identifier);
+    let string = string_from_c(string);
+    with_state(|state| {
+        state.record(Call::SetTranslation {
+            identifier: identifier.clone(),
+            language_identifier: language_identifier.clone(),
+            string: string.clone(),
+        });
+        state
+            .translations
+            .insert((identifier, language_identifier), string);
+    })
+}
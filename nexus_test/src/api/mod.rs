@@ -0,0 +1,266 @@
+//! Fake [`AddonApi`] function tables.
+
+pub mod event;
+pub mod font;
+pub mod gui;
+pub mod input;
+pub mod misc;
+pub mod quick_access;
+pub mod texture;
+
+use crate::imgui::{imgui_free, imgui_malloc};
+use nexus::{
+    imgui::sys::ImGuiContext, v2, v3, v4, AddonApi, DataLinkApi, EventApi, FontApi, GameBindApi,
+    InputBindsApi, LocalizationApi, MinHookApi, PathApi, QuickAccessApi, RendererApi, TextureApi,
+    UiApi, WndProcApi,
+};
+
+/// Creates a fake [`AddonApi`] using the given ImGui context.
+pub fn create_api(imgui_context: *mut ImGuiContext) -> AddonApi {
+    AddonApi {
+        swap_chain: None,
+        imgui_context,
+        imgui_malloc: Some(imgui_malloc),
+        imgui_free: Some(imgui_free),
+        renderer: RendererApi {
+            register: gui::register_render,
+            deregister: gui::deregister_render,
+        },
+        request_update: misc::request_update,
+        log: misc::log,
+        ui: UiApi {
+            send_alert: gui::send_alert,
+            register_close_on_escape: gui::register_close_on_escape,
+            deregister_close_on_escape: gui::deregister_close_on_escape,
+        },
+        path: PathApi {
+            get_game_dir: misc::get_game_dir,
+            get_addon_dir: misc::get_addon_dir,
+            get_common_dir: misc::get_common_dir,
+        },
+        min_hook: MinHookApi {
+            create: misc::hook_create,
+            remove: misc::hook_remove,
+            enable: misc::hook_enable,
+            disable: misc::hook_disable,
+        },
+        event: EventApi {
+            raise: event::raise,
+            raise_notification: event::raise_notification,
+            raise_targeted: event::raise_targeted,
+            raise_notification_targeted: event::raise_notification_targeted,
+            subscribe: event::subscribe,
+            unsubscribe: event::unsubscribe,
+        },
+        wnd_proc: WndProcApi {
+            register: input::register_wnd_proc,
+            deregister: input::deregister_wnd_proc,
+            send_to_game_only: input::send_wnd_proc_to_game,
+        },
+        input_binds: InputBindsApi {
+            invoke: input::keybind_invoke,
+            register_with_string: input::keybind_register_with_string,
+            register_with_struct: input::keybind_register_with_struct,
+            deregister: input::keybind_deregister,
+        },
+        game_bind: GameBindApi {
+            press_async: input::gamebind_press_async,
+            release_async: input::gamebind_release_async,
+            invoke_async: input::gamebind_invoke_async,
+            press: input::gamebind_press,
+            release: input::gamebind_release,
+            is_bound: input::gamebind_is_bound,
+        },
+        data_link: DataLinkApi {
+            get: misc::data_link_get,
+            share: misc::data_link_share,
+        },
+        texture: TextureApi {
+            get: texture::get,
+            get_or_create_from_file: texture::get_or_create_from_file,
+            get_or_create_from_resource: texture::get_or_create_from_resource,
+            get_or_create_from_url: texture::get_or_create_from_url,
+            get_or_create_from_memory: texture::get_or_create_from_memory,
+            load_from_file: texture::load_from_file,
+            load_from_resource: texture::load_from_resource,
+            load_from_url: texture::load_from_url,
+            load_from_memory: texture::load_from_memory,
+        },
+        quick_access: QuickAccessApi {
+            add: quick_access::add,
+            remove: quick_access::remove,
+            notify: quick_access::notify,
+            add_context_menu: quick_access::add_context_menu,
+            remove_context_menu: quick_access::remove_context_menu,
+        },
+        localization: LocalizationApi {
+            translate: misc::translate,
+            translate_to: misc::translate_to,
+            set: misc::set_translation,
+        },
+        font: FontApi {
+            get: font::get,
+            release: font::release,
+            add_from_file: font::add_from_file,
+            add_from_resource: font::add_from_resource,
+            add_from_memory: font::add_from_memory,
+            resize: font::resize,
+        },
+    }
+}
+
+/// Creates a fake [`v4::AddonApi`] from the current revision.
+pub fn create_api_v4(api: &AddonApi) -> v4::AddonApi {
+    v4::AddonApi {
+        swap_chain: api.swap_chain,
+        imgui_context: api.imgui_context,
+        imgui_malloc: api.imgui_malloc,
+        imgui_free: api.imgui_free,
+        register_render: api.renderer.register,
+        deregister_render: api.renderer.deregister,
+        get_game_dir: api.path.get_game_dir,
+        get_addon_dir: api.path.get_addon_dir,
+        get_common_dir: api.path.get_common_dir,
+        hook_create: api.min_hook.create,
+        hook_remove: api.min_hook.remove,
+        hook_enable: api.min_hook.enable,
+        hook_disable: api.min_hook.disable,
+        log: api.log,
+        alert_notify: api.ui.send_alert,
+        event_raise: api.event.raise,
+        event_raise_notification: api.event.raise_notification,
+        event_raise_targeted: api.event.raise_targeted,
+        event_raise_notification_targeted: api.event.raise_notification_targeted,
+        event_subscribe: api.event.subscribe,
+        event_unsubscribe: api.event.unsubscribe,
+        register_wnd_proc: api.wnd_proc.register,
+        deregister_wnd_proc: api.wnd_proc.deregister,
+        send_wnd_proc_to_game_only: api.wnd_proc.send_to_game_only,
+        request_update: api.request_update,
+        keybind_register_with_string: api.input_binds.register_with_string,
+        keybind_register_with_struct: api.input_binds.register_with_struct,
+        keybind_deregister: api.input_binds.deregister,
+        get_resource: api.data_link.get,
+        share_resource: api.data_link.share,
+        get_texture: api.texture.get,
+        get_texture_or_create_from_file: api.texture.get_or_create_from_file,
+        get_texture_or_create_from_resource: api.texture.get_or_create_from_resource,
+        get_texture_or_create_from_url: api.texture.get_or_create_from_url,
+        get_texture_or_create_from_memory: api.texture.get_or_create_from_memory,
+        load_texture_from_file: api.texture.load_from_file,
+        load_texture_from_resource: api.texture.load_from_resource,
+        load_texture_from_url: api.texture.load_from_url,
+        load_texture_from_memory: api.texture.load_from_memory,
+        add_shortcut: api.quick_access.add,
+        remove_shortcut: api.quick_access.remove,
+        notify_shortcut: api.quick_access.notify,
+        add_simple_shortcut: quick_access::add_context_menu_old,
+        remove_simple_shortcut: api.quick_access.remove_context_menu,
+        translate: api.localization.translate,
+        translate_to: api.localization.translate_to,
+        get_font: api.font.get,
+        release_font: api.font.release,
+        add_font_from_file: api.font.add_from_file,
+        add_font_from_resource: api.font.add_from_resource,
+        add_font_from_memory: api.font.add_from_memory,
+    }
+}
+
+/// Creates a fake [`v3::AddonApi`] from the current revision.
+pub fn create_api_v3(api: &AddonApi) -> v3::AddonApi {
+    v3::AddonApi {
+        swap_chain: api.swap_chain,
+        imgui_context: api.imgui_context,
+        imgui_malloc: api.imgui_malloc,
+        imgui_free: api.imgui_free,
+        register_render: api.renderer.register,
+        deregister_render: api.renderer.deregister,
+        get_game_dir: api.path.get_game_dir,
+        get_addon_dir: api.path.get_addon_dir,
+        get_common_dir: api.path.get_common_dir,
+        hook_create: api.min_hook.create,
+        hook_remove: api.min_hook.remove,
+        hook_enable: api.min_hook.enable,
+        hook_disable: api.min_hook.disable,
+        log: api.log,
+        alert_notify: api.ui.send_alert,
+        event_raise: api.event.raise,
+        event_raise_notification: api.event.raise_notification,
+        event_raise_targeted: api.event.raise_targeted,
+        event_raise_notification_targeted: api.event.raise_notification_targeted,
+        event_subscribe: api.event.subscribe,
+        event_unsubscribe: api.event.unsubscribe,
+        register_wnd_proc: api.wnd_proc.register,
+        deregister_wnd_proc: api.wnd_proc.deregister,
+        send_wnd_proc_to_game_only: api.wnd_proc.send_to_game_only,
+        keybind_register_with_string: input::keybind_register_with_string_old,
+        keybind_register_with_struct: input::keybind_register_with_struct_old,
+        keybind_deregister: api.input_binds.deregister,
+        get_resource: api.data_link.get,
+        share_resource: api.data_link.share,
+        get_texture: api.texture.get,
+        get_texture_or_create_from_file: api.texture.get_or_create_from_file,
+        get_texture_or_create_from_resource: api.texture.get_or_create_from_resource,
+        get_texture_or_create_from_url: api.texture.get_or_create_from_url,
+        get_texture_or_create_from_memory: api.texture.get_or_create_from_memory,
+        load_texture_from_file: api.texture.load_from_file,
+        load_texture_from_resource: api.texture.load_from_resource,
+        load_texture_from_url: api.texture.load_from_url,
+        load_texture_from_memory: api.texture.load_from_memory,
+        add_shortcut: api.quick_access.add,
+        remove_shortcut: api.quick_access.remove,
+        notify_shortcut: api.quick_access.notify,
+        add_simple_shortcut: quick_access::add_context_menu_old,
+        remove_simple_shortcut: api.quick_access.remove_context_menu,
+        translate: api.localization.translate,
+        translate_to: api.localization.translate_to,
+    }
+}
+
+/// Creates a fake [`v2::AddonApi`] from the current revision.
+pub fn create_api_v2(api: &AddonApi) -> v2::AddonApi {
+    v2::AddonApi {
+        swap_chain: api.swap_chain,
+        imgui_context: api.imgui_context,
+        imgui_malloc: api.imgui_malloc,
+        imgui_free: api.imgui_free,
+        register_render: api.renderer.register,
+        deregister_render: api.renderer.deregister,
+        get_game_dir: api.path.get_game_dir,
+        get_addon_dir: api.path.get_addon_dir,
+        get_common_dir: api.path.get_common_dir,
+        hook_create: api.min_hook.create,
+        hook_remove: api.min_hook.remove,
+        hook_enable: api.min_hook.enable,
+        hook_disable: api.min_hook.disable,
+        log: api.log,
+        event_raise: api.event.raise,
+        event_raise_notification: api.event.raise_notification,
+        event_subscribe: api.event.subscribe,
+        event_unsubscribe: api.event.unsubscribe,
+        register_wnd_proc: api.wnd_proc.register,
+        deregister_wnd_proc: api.wnd_proc.deregister,
+        send_wnd_proc_to_game_only: api.wnd_proc.send_to_game_only,
+        keybind_register_with_string: input::keybind_register_with_string_old,
+        keybind_register_with_struct: input::keybind_register_with_struct_old,
+        keybind_deregister: api.input_binds.deregister,
+        get_resource: api.data_link.get,
+        share_resource: api.data_link.share,
+        get_texture: api.texture.get,
+        get_texture_or_create_from_file: api.texture.get_or_create_from_file,
+        get_texture_or_create_from_resource: api.texture.get_or_create_from_resource,
+        get_texture_or_create_from_url: api.texture.get_or_create_from_url,
+        get_texture_or_create_from_memory: api.texture.get_or_create_from_memory,
+        load_texture_from_file: api.texture.load_from_file,
+        load_texture_from_resource: api.texture.load_from_resource,
+        load_texture_from_url: api.texture.load_from_url,
+        load_texture_from_memory: api.texture.load_from_memory,
+        add_shortcut: api.quick_access.add,
+        remove_shortcut: api.quick_access.remove,
+        notify_shortcut: api.quick_access.notify,
+        add_simple_shortcut: quick_access::add_context_menu_old,
+        remove_simple_shortcut: api.quick_access.remove_context_menu,
+        translate: api.localization.translate,
+        translate_to: api.localization.translate_to,
+    }
+}
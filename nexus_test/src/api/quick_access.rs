@@ -0,0 +1,83 @@
+use crate::{
+    call::Call,
+    state::{opt_string_from_c, string_from_c, with_state, QuickAccessEntry},
+};
+use nexus::gui::RawGuiRender;
+use std::{ffi::c_char, ptr};
+
+pub unsafe extern "C-unwind" fn add(
+    identifier: *const c_char,
+    texture_identifier: *const c_char,
+    texture_hover_identifier: *const c_char,
+    keybind_identifier: *const c_char,
+    tooltip_text: *const c_char,
+) {
+    let entry = QuickAccessEntry {
+        identifier: string_from_c(identifier),
+        texture_identifier: string_from_c(texture_identifier),
+        texture_hover_identifier: string_from_c(texture_hover_identifier),
+        keybind_identifier: string_from_c(keybind_identifier),
+        tooltip_text: string_from_c(tooltip_text),
+    };
+    with_state(|state| {
+        state.record(Call::QuickAccessAdd {
+            identifier: entry.identifier.clone(),
+            texture_identifier: entry.texture_identifier.clone(),
+            texture_hover_identifier: entry.texture_hover_identifier.clone(),
+            keybind_identifier: entry.keybind_identifier.clone(),
+            tooltip_text: entry.tooltip_text.clone(),
+        });
+        state
+            .quick_access
+            .retain(|other| other.identifier != entry.identifier);
+        state.quick_access.push(entry);
+    })
+}
+
+pub unsafe extern "C-unwind" fn remove(identifier: *const c_char) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state
+            .quick_access
+            .retain(|entry| entry.identifier != identifier);
+        state.record(Call::QuickAccessRemove { identifier });
+    })
+}
+
+pub unsafe extern "C-unwind" fn notify(identifier: *const c_char) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| state.record(Call::QuickAccessNotify { identifier }))
+}
+
+pub unsafe extern "C-unwind" fn add_context_menu(
+    identifier: *const c_char,
+    target_identifier: *const c_char,
+    shortcut_render_callback: RawGuiRender,
+) {
+    let identifier = string_from_c(identifier);
+    let target_identifier = opt_string_from_c(target_identifier);
+    with_state(|state| {
+        state.record(Call::QuickAccessAddContextMenu {
+            identifier: identifier.clone(),
+            target_identifier: target_identifier.clone(),
+        });
+        state
+            .context_menus
+            .insert(identifier, (target_identifier, shortcut_render_callback));
+    })
+}
+
+pub unsafe extern "C-unwind" fn add_context_menu_old(
+    identifier: *const c_char,
+    shortcut_render_callback: RawGuiRender,
+) {
+    add_context_menu(identifier, ptr::null(), shortcut_render_callback)
+}
+
+pub unsafe extern "C-unwind" fn remove_context_menu(identifier: *const c_char) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.context_menus.remove(&identifier);
+        state.record(Call::QuickAccessRemoveContextMenu { identifier });
+    })
+}
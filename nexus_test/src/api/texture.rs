@@ -0,0 +1,173 @@
+use crate::{
+    call::Call,
+    state::{string_from_c, with_state},
+};
+use nexus::{
+    texture::{RawTextureReceiveCallback, Texture},
+    win::HMODULE,
+};
+use std::{
+    ffi::{c_char, c_void},
+    path::PathBuf,
+};
+
+/// Delivers all pending textures to their receive callbacks.
+pub fn deliver_pending() {
+    let pending = with_state(|state| {
+        let pending = std::mem::take(&mut state.pending_textures);
+        pending
+            .into_iter()
+            .map(|(identifier, callback)| {
+                let texture = state.texture(&identifier);
+                let identifier = state.intern(identifier);
+                (identifier, texture, callback)
+            })
+            .collect::<Vec<_>>()
+    });
+    for (identifier, texture, callback) in pending {
+        callback(identifier, texture);
+    }
+}
+
+pub unsafe extern "C-unwind" fn get(identifier: *const c_char) -> *const Texture {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        let texture = state.texture(&identifier);
+        state.record(Call::TextureGet { identifier });
+        texture
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_or_create_from_file(
+    identifier: *const c_char,
+    filename: *const c_char,
+) -> *const Texture {
+    let identifier = string_from_c(identifier);
+    let file = PathBuf::from(string_from_c(filename));
+    with_state(|state| {
+        state.record(Call::TextureGetOrCreateFromFile {
+            identifier: identifier.clone(),
+            file,
+        });
+        state.texture_or_create(identifier)
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_or_create_from_resource(
+    identifier: *const c_char,
+    resource_id: u32,
+    _module: HMODULE,
+) -> *const Texture {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::TextureGetOrCreateFromResource {
+            identifier: identifier.clone(),
+            resource_id,
+        });
+        state.texture_or_create(identifier)
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_or_create_from_url(
+    identifier: *const c_char,
+    remote: *const c_char,
+    endpoint: *const c_char,
+) -> *const Texture {
+    let identifier = string_from_c(identifier);
+    let remote = string_from_c(remote);
+    let endpoint = string_from_c(endpoint);
+    with_state(|state| {
+        state.record(Call::TextureGetOrCreateFromUrl {
+            identifier: identifier.clone(),
+            remote,
+            endpoint,
+        });
+        state.texture_or_create(identifier)
+    })
+}
+
+pub unsafe extern "C-unwind" fn get_or_create_from_memory(
+    identifier: *const c_char,
+    _data: *const c_void,
+    size: usize,
+) -> *const Texture {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::TextureGetOrCreateFromMemory {
+            identifier: identifier.clone(),
+            size,
+        });
+        state.texture_or_create(identifier)
+    })
+}
+
+pub unsafe extern "C-unwind" fn load_from_file(
+    identifier: *const c_char,
+    filename: *const c_char,
+    callback: RawTextureReceiveCallback,
+) {
+    let identifier = string_from_c(identifier);
+    let file = PathBuf::from(string_from_c(filename));
+    with_state(|state| {
+        state.record(Call::TextureLoadFromFile {
+            identifier: identifier.clone(),
+            file,
+        });
+        state.texture_or_create(identifier.clone());
+        state.pending_textures.push((identifier, callback));
+    })
+}
+
+pub unsafe extern "C-unwind" fn load_from_resource(
+    identifier: *const c_char,
+    resource_id: u32,
+    _module: HMODULE,
+    callback: RawTextureReceiveCallback,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::TextureLoadFromResource {
+            identifier: identifier.clone(),
+            resource_id,
+        });
+        state.texture_or_create(identifier.clone());
+        state.pending_textures.push((identifier, callback));
+    })
+}
+
+pub unsafe extern "C-unwind" fn load_from_url(
+    identifier: *const c_char,
+    remote: *const c_char,
+    endpoint: *const c_char,
+    callback: RawTextureReceiveCallback,
+) {
+    let identifier = string_from_c(identifier);
+    let remote = string_from_c(remote);
+    let endpoint = string_from_c(endpoint);
+    with_state(|state| {
+        state.record(Call::TextureLoadFromUrl {
+            identifier: identifier.clone(),
+            remote,
+            endpoint,
+        });
+        state.texture_or_create(identifier.clone());
+        state.pending_textures.push((identifier, callback));
+    })
+}
+
+pub unsafe extern "C-unwind" fn load_from_memory(
+    identifier: *const c_char,
+    _data: *const c_void,
+    size: usize,
+    callback: RawTextureReceiveCallback,
+) {
+    let identifier = string_from_c(identifier);
+    with_state(|state| {
+        state.record(Call::TextureLoadFromMemory {
+            identifier: identifier.clone(),
+            size,
+        });
+        state.texture_or_create(identifier.clone());
+        state.pending_textures.push((identifier, callback));
+    })
+}
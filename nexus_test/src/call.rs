@@ -0,0 +1,250 @@
+//! Recorded API calls.
+
+use nexus::{gamebind::GameBind, gui::RenderType, keybind::Keybind, log::LogLevel};
+use std::path::PathBuf;
+
+/// A call made by the addon into the [`AddonApi`](nexus::AddonApi).
+///
+/// String arguments are copied when the call is recorded.
+/// Callbacks and other raw pointers are omitted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    /// Render callback registered.
+    RegisterRender { render_type: RenderType },
+
+    /// Render callback deregistered.
+    DeregisterRender,
+
+    /// Update requested.
+    RequestUpdate { signature: i32, update_url: String },
+
+    /// Message logged.
+    Log {
+        level: LogLevel,
+        channel: String,
+        message: String,
+    },
+
+    /// Alert sent.
+    SendAlert { message: String },
+
+    /// Window registered to close on escape.
+    RegisterCloseOnEscape { window_name: String },
+
+    /// Window deregistered to close on escape.
+    DeregisterCloseOnEscape { window_name: String },
+
+    /// Game directory requested.
+    GetGameDir,
+
+    /// Addon directory requested.
+    GetAddonDir { name: String },
+
+    /// Common directory requested.
+    GetCommonDir,
+
+    /// MinHook create.
+    HookCreate,
+
+    /// MinHook remove.
+    HookRemove,
+
+    /// MinHook enable.
+    HookEnable,
+
+    /// MinHook disable.
+    HookDisable,
+
+    /// Event raised.
+    EventRaise { identifier: String },
+
+    /// Event without payload raised.
+    EventRaiseNotification { identifier: String },
+
+    /// Targeted event raised.
+    EventRaiseTargeted { signature: i32, identifier: String },
+
+    /// Targeted event without payload raised.
+    EventRaiseNotificationTargeted { signature: i32, identifier: String },
+
+    /// Event subscribed.
+    EventSubscribe { identifier: String },
+
+    /// Event unsubscribed.
+    EventUnsubscribe { identifier: String },
+
+    /// WNDPROC callback registered.
+    RegisterWndProc,
+
+    /// WNDPROC callback deregistered.
+    DeregisterWndProc,
+
+    /// WNDPROC sent to game.
+    SendWndProcToGame { u_msg: u32 },
+
+    /// Keybind invoked.
+    KeybindInvoke {
+        identifier: String,
+        is_release: bool,
+    },
+
+    /// Keybind registered with string.
+    KeybindRegisterWithString { identifier: String, keybind: String },
+
+    /// Keybind registered with struct.
+    KeybindRegisterWithStruct {
+        identifier: String,
+        keybind: Keybind,
+    },
+
+    /// Keybind deregistered.
+    KeybindDeregister { identifier: String },
+
+    /// Game bind pressed asynchronously.
+    GameBindPressAsync { bind: GameBind },
+
+    /// Game bind released asynchronously.
+    GameBindReleaseAsync { bind: GameBind },
+
+    /// Game bind invoked asynchronously.
+    GameBindInvokeAsync { bind: GameBind, duration: i32 },
+
+    /// Game bind pressed.
+    GameBindPress { bind: GameBind },
+
+    /// Game bind released.
+    GameBindRelease { bind: GameBind },
+
+    /// Game bind checked.
+    GameBindIsBound { bind: GameBind },
+
+    /// Shared resource requested.
+    DataLinkGet { identifier: String },
+
+    /// Shared resource created.
+    DataLinkShare { identifier: String, size: usize },
+
+    /// Texture requested.
+    TextureGet { identifier: String },
+
+    /// Texture requested or created from file.
+    TextureGetOrCreateFromFile { identifier: String, file: PathBuf },
+
+    /// Texture requested or created from resource.
+    TextureGetOrCreateFromResource {
+        identifier: String,
+        resource_id: u32,
+    },
+
+    /// Texture requested or created from URL.
+    TextureGetOrCreateFromUrl {
+        identifier: String,
+        remote: String,
+        endpoint: String,
+    },
+
+    /// Texture requested or created from memory.
+    TextureGetOrCreateFromMemory { identifier: String, size: usize },
+
+    /// Texture loaded from file.
+    TextureLoadFromFile { identifier: String, file: PathBuf },
+
+    /// Texture loaded from resource.
+    TextureLoadFromResource {
+        identifier: String,
+        resource_id: u32,
+    },
+
+    /// Texture loaded from URL.
+    TextureLoadFromUrl {
+        identifier: String,
+        remote: String,
+        endpoint: String,
+    },
+
+    /// Texture loaded from memory.
+    TextureLoadFromMemory { identifier: String, size: usize },
+
+    /// Quick access shortcut added.
+    QuickAccessAdd {
+        identifier: String,
+        texture_identifier: String,
+        texture_hover_identifier: String,
+        keybind_identifier: String,
+        tooltip_text: String,
+    },
+
+    /// Quick access shortcut removed.
+    QuickAccessRemove { identifier: String },
+
+    /// Quick access shortcut notified.
+    QuickAccessNotify { identifier: String },
+
+    /// Quick access context menu added.
+    QuickAccessAddContextMenu {
+        identifier: String,
+        target_identifier: Option<String>,
+    },
+
+    /// Quick access context menu removed.
+    QuickAccessRemoveContextMenu { identifier: String },
+
+    /// Translation requested.
+    Translate { identifier: String },
+
+    /// Translation to a language requested.
+    TranslateTo {
+        identifier: String,
+        language_identifier: String,
+    },
+
+    /// Translation set.
+    SetTranslation {
+        identifier: String,
+        language_identifier: String,
+        string: String,
+    },
+
+    /// Font requested.
+    FontGet { identifier: String },
+
+    /// Font receiver released.
+    FontRelease { identifier: String },
+
+    /// Font added from file.
+    FontAddFromFile {
+        identifier: String,
+        font_size: f32,
+        file: PathBuf,
+    },
+
+    /// Font added from resource.
+    FontAddFromResource {
+        identifier: String,
+        font_size: f32,
+        resource_id: u32,
+    },
+
+    /// Font added from memory.
+    FontAddFromMemory {
+        identifier: String,
+        font_size: f32,
+        size: usize,
+    },
+
+    /// Font resized.
+    FontResize { identifier: String, font_size: f32 },
+}
+
+/// A message logged by the addon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Log level.
+    pub level: LogLevel,
+
+    /// Log channel.
+    pub channel: String,
+
+    /// Log message.
+    pub message: String,
+}
@@ -0,0 +1,507 @@
+//! Fake Nexus host.
+
+use crate::{
+    api::{self, create_api, create_api_v2, create_api_v3, create_api_v4},
+    call::{Call, LogEntry},
+    imgui,
+    state::{
+        placeholder_texture, set_state, with_state, HostState, KeybindBinding, QuickAccessEntry,
+    },
+};
+use nexus::{
+    addon::{AddonDefinition, RawGetAddonDef},
+    data_link::NexusLink,
+    gamebind::GameBind,
+    gui::RenderType,
+    imgui::sys,
+    v2, v3, v4,
+    win::{HWND, LPARAM, WPARAM},
+    AddonApi,
+};
+use std::{
+    ffi::c_void,
+    path::{Path, PathBuf},
+    ptr,
+    sync::{Mutex, MutexGuard, OnceLock, PoisonError},
+};
+
+/// Lock serializing hosts, the fake API is backed by global state.
+static HOST_LOCK: Mutex<()> = Mutex::new(());
+
+/// Addon API revisions passed to addons, need a stable address for the lifetime of the process.
+static APIS: OnceLock<Apis> = OnceLock::new();
+
+/// Addon API in all supported revisions.
+#[derive(Debug)]
+struct Apis {
+    v2: v2::AddonApi,
+    v3: v3::AddonApi,
+    v4: v4::AddonApi,
+    v6: AddonApi,
+}
+
+impl Apis {
+    fn get() -> &'static Self {
+        APIS.get_or_init(|| {
+            let v6 = create_api(imgui::context().context);
+            Self {
+                v2: create_api_v2(&v6),
+                v3: create_api_v3(&v6),
+                v4: create_api_v4(&v6),
+                v6,
+            }
+        })
+    }
+
+    fn ptr(&self, version: i32) -> Option<*const AddonApi> {
+        match version {
+            2 => Some((&self.v2 as *const v2::AddonApi).cast()),
+            3 => Some((&self.v3 as *const v3::AddonApi).cast()),
+            4 => Some((&self.v4 as *const v4::AddonApi).cast()),
+            6 => Some(&self.v6),
+            _ => None,
+        }
+    }
+}
+
+unsafe impl Send for Apis {}
+
+unsafe impl Sync for Apis {}
+
+/// In-process fake Nexus host.
+///
+/// Only a single host can be active at a time.
+/// Creating a second host blocks until the first one is dropped.
+/// Tests using a host are therefore serialized.
+///
+/// Dropping the host unloads the addon if it is still loaded.
+/// When dropped during a panic, the addon stays loaded and its log is printed instead.
+#[derive(Debug)]
+pub struct TestHost {
+    definition: Option<AddonDefinition>,
+    loaded: bool,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TestHost {
+    /// Creates a new host with a game directory in the temporary directory.
+    pub fn new() -> Self {
+        Self::with_game_dir(
+            std::env::temp_dir()
+                .join("nexus_test")
+                .join(std::process::id().to_string()),
+        )
+    }
+
+    /// Creates a new host with the given game directory.
+    ///
+    /// The directory is not created.
+    pub fn with_game_dir(game_dir: impl Into<PathBuf>) -> Self {
+        let lock = HOST_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let ctx = imgui::context();
+        let mut state = HostState::new(game_dir.into(), ctx.font);
+
+        let link = state
+            .share(NexusLink::LINK.into(), std::mem::size_of::<NexusLink>())
+            .cast::<NexusLink>();
+        unsafe {
+            link.write(NexusLink {
+                width: imgui::DISPLAY_SIZE[0] as u32,
+                height: imgui::DISPLAY_SIZE[1] as u32,
+                scaling: 1.0,
+                is_moving: false,
+                is_camera_moving: false,
+                is_gameplay: true,
+                font: ctx.font,
+                font_big: ctx.font,
+                font_ui: ctx.font,
+            })
+        };
+
+        set_state(Some(state));
+        Self {
+            definition: None,
+            loaded: false,
+            _lock: lock,
+        }
+    }
+
+    /// Returns the [`AddonApi`] passed to addons requesting the current revision.
+    pub fn api() -> &'static AddonApi {
+        &Apis::get().v6
+    }
+
+    /// Loads an addon via its exported `GetAddonDef` function.
+    ///
+    /// See [`addon_def`](crate::addon_def) for retrieving the function.
+    /// The addon receives the Addon API revision it requests.
+    /// Panics if an addon is already loaded or the addon requests an unsupported API version.
+    ///
+    /// # Safety
+    /// The function must return a valid [`AddonDefinition`].
+    pub unsafe fn load(&mut self, get_addon_def: RawGetAddonDef) -> &AddonDefinition {
+        assert!(!self.loaded, "addon already loaded");
+
+        let definition = *get_addon_def()
+            .as_ref()
+            .expect("addon returned no definition");
+        let api = Apis::get()
+            .ptr(definition.api_version)
+            .unwrap_or_else(|| panic!("unsupported api version {}", definition.api_version));
+        with_state(|state| state.signature = Some(definition.signature));
+
+        sys::igSetCurrentContext(Self::api().imgui_context);
+        self.definition = Some(definition);
+        self.loaded = true;
+        (definition.load)(api);
+
+        self.definition.as_ref().unwrap_unchecked()
+    }
+
+    /// Unloads the addon.
+    ///
+    /// Does nothing if no addon is loaded.
+    pub fn unload(&mut self) {
+        if self.loaded {
+            self.loaded = false;
+            if let Some(unload) = self.definition.and_then(|definition| definition.unload) {
+                unsafe { unload() };
+            }
+        }
+    }
+
+    /// Checks whether an addon is loaded.
+    #[inline]
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Returns the definition of the last loaded addon.
+    #[inline]
+    pub fn definition(&self) -> Option<&AddonDefinition> {
+        self.definition.as_ref()
+    }
+
+    /// Renders a frame.
+    ///
+    /// Pending textures & fonts are delivered before the frame.
+    /// Then [`RenderType::PreRender`], [`RenderType::Render`], [`RenderType::OptionsRender`] and [`RenderType::PostRender`] callbacks are invoked in order.
+    /// Render callbacks are invoked within an ImGui frame.
+    pub fn render_frame(&mut self) {
+        struct FrameGuard;
+
+        impl Drop for FrameGuard {
+            fn drop(&mut self) {
+                unsafe { sys::igRender() }
+            }
+        }
+
+        api::texture::deliver_pending();
+        api::font::deliver_pending();
+
+        self.render(RenderType::PreRender);
+        unsafe { sys::igNewFrame() };
+        {
+            let _guard = FrameGuard;
+            self.render(RenderType::Render);
+            self.render(RenderType::OptionsRender);
+        }
+        self.render(RenderType::PostRender);
+    }
+
+    /// Invokes all render callbacks of the given type.
+    ///
+    /// For [`RenderType::Render`] and [`RenderType::OptionsRender`] this must happen within an ImGui frame.
+    fn render(&self, render_type: RenderType) {
+        let callbacks = with_state(|state| {
+            state
+                .renders
+                .iter()
+                .filter(|(ty, _)| *ty == render_type)
+                .map(|(_, callback)| *callback)
+                .collect::<Vec<_>>()
+        });
+        for callback in callbacks {
+            callback();
+        }
+    }
+
+    /// Renders the context menu of a quick access shortcut within an ImGui frame.
+    ///
+    /// Returns `false` if no context menu is registered with the identifier.
+    pub fn render_context_menu(&mut self, identifier: &str) -> bool {
+        let callback = with_state(|state| {
+            state
+                .context_menus
+                .get(identifier)
+                .map(|(_, callback)| *callback)
+        });
+        if let Some(callback) = callback {
+            unsafe { sys::igNewFrame() };
+            callback();
+            unsafe { sys::igRender() };
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Raises an event with the given data.
+    ///
+    /// # Safety
+    /// The subscribed callbacks have to expect the data type.
+    pub unsafe fn raise_event<T>(&self, identifier: &str, data: &T) {
+        api::event::dispatch(identifier, (data as *const T).cast());
+    }
+
+    /// Raises an event without data.
+    pub fn raise_notification(&self, identifier: &str) {
+        api::event::dispatch(identifier, ptr::null::<c_void>())
+    }
+
+    /// Presses a registered keybind.
+    ///
+    /// Returns `false` if the keybind is not registered.
+    pub fn press_keybind(&self, identifier: &str) -> bool {
+        api::input::dispatch_keybind(identifier, false)
+    }
+
+    /// Releases a registered keybind.
+    ///
+    /// Returns `false` if the keybind is not registered.
+    pub fn release_keybind(&self, identifier: &str) -> bool {
+        api::input::dispatch_keybind(identifier, true)
+    }
+
+    /// Presses and releases a registered keybind.
+    ///
+    /// Returns `false` if the keybind is not registered.
+    pub fn trigger_keybind(&self, identifier: &str) -> bool {
+        self.press_keybind(identifier) && self.release_keybind(identifier)
+    }
+
+    /// Sends a window message to the registered WNDPROC callbacks.
+    ///
+    /// Returns `true` if the message was consumed.
+    pub fn send_wnd_proc(&self, u_msg: u32, w_param: WPARAM, l_param: LPARAM) -> bool {
+        api::input::dispatch_wnd_proc(HWND::default(), u_msg, w_param, l_param)
+    }
+
+    /// Presses escape, closing all visible windows registered to close on escape.
+    ///
+    /// Returns the names of the closed windows.
+    pub fn press_escape(&self) -> Vec<String> {
+        with_state(|state| {
+            let mut closed = Vec::new();
+            for (name, is_visible) in &state.close_on_escape {
+                unsafe {
+                    if **is_visible {
+                        **is_visible = false;
+                        closed.push(name.clone());
+                    }
+                }
+            }
+            closed.sort();
+            closed
+        })
+    }
+
+    /// Sets whether a game bind is reported as bound.
+    pub fn set_gamebind_bound(&self, bind: GameBind, bound: bool) {
+        with_state(|state| {
+            if bound {
+                state.bound_gamebinds.insert(bind);
+            } else {
+                state.bound_gamebinds.remove(&bind);
+            }
+        })
+    }
+
+    /// Sets the current language.
+    pub fn set_language(&self, language_identifier: impl Into<String>) {
+        with_state(|state| state.language = language_identifier.into())
+    }
+
+    /// Sets a translated string.
+    pub fn set_translation(
+        &self,
+        identifier: impl Into<String>,
+        language_identifier: impl Into<String>,
+        string: impl Into<String>,
+    ) {
+        with_state(|state| {
+            state.translations.insert(
+                (identifier.into(), language_identifier.into()),
+                string.into(),
+            );
+        })
+    }
+
+    /// Inserts a texture with the given size.
+    pub fn insert_texture(&self, identifier: impl Into<String>, width: u32, height: u32) {
+        with_state(|state| {
+            state.textures.insert(
+                identifier.into(),
+                Box::new(placeholder_texture(width, height)),
+            );
+        })
+    }
+
+    /// Checks whether a texture exists.
+    pub fn has_texture(&self, identifier: &str) -> bool {
+        with_state(|state| state.textures.contains_key(identifier))
+    }
+
+    /// Shares a resource, returning a pointer to its data.
+    ///
+    /// The data is zeroed if newly created.
+    pub fn share_resource<T>(&self, identifier: impl Into<String>) -> *mut T {
+        with_state(|state| {
+            state
+                .share(identifier.into(), std::mem::size_of::<T>())
+                .cast()
+        })
+    }
+
+    /// Returns all recorded calls.
+    pub fn calls(&self) -> Vec<Call> {
+        with_state(|state| state.calls.clone())
+    }
+
+    /// Returns and clears all recorded calls.
+    pub fn take_calls(&self) -> Vec<Call> {
+        with_state(|state| std::mem::take(&mut state.calls))
+    }
+
+    /// Clears all recorded calls.
+    pub fn clear_calls(&self) {
+        with_state(|state| state.calls.clear())
+    }
+
+    /// Returns all logged messages.
+    pub fn logs(&self) -> Vec<LogEntry> {
+        with_state(|state| state.logs.clone())
+    }
+
+    /// Returns all sent alerts.
+    pub fn alerts(&self) -> Vec<String> {
+        with_state(|state| state.alerts.clone())
+    }
+
+    /// Returns the number of registered render callbacks of the given type.
+    pub fn render_count(&self, render_type: RenderType) -> usize {
+        with_state(|state| {
+            state
+                .renders
+                .iter()
+                .filter(|(ty, _)| *ty == render_type)
+                .count()
+        })
+    }
+
+    /// Returns the number of callbacks subscribed to the event.
+    pub fn event_subscribers(&self, identifier: &str) -> usize {
+        with_state(|state| state.events.get(identifier).map_or(0, Vec::len))
+    }
+
+    /// Returns the number of registered WNDPROC callbacks.
+    pub fn wnd_proc_count(&self) -> usize {
+        with_state(|state| state.wnd_procs.len())
+    }
+
+    /// Returns the binding of a registered keybind.
+    pub fn keybind(&self, identifier: &str) -> Option<KeybindBinding> {
+        with_state(|state| {
+            state
+                .keybinds
+                .get(identifier)
+                .map(|(_, binding)| binding.clone())
+        })
+    }
+
+    /// Returns the identifiers of all registered keybinds.
+    pub fn keybinds(&self) -> Vec<String> {
+        with_state(|state| {
+            let mut keybinds: Vec<_> = state.keybinds.keys().cloned().collect();
+            keybinds.sort();
+            keybinds
+        })
+    }
+
+    /// Returns all quick access shortcuts.
+    pub fn quick_access(&self) -> Vec<QuickAccessEntry> {
+        with_state(|state| state.quick_access.clone())
+    }
+
+    /// Returns the identifiers of all quick access context menus.
+    pub fn context_menus(&self) -> Vec<String> {
+        with_state(|state| {
+            let mut menus: Vec<_> = state.context_menus.keys().cloned().collect();
+            menus.sort();
+            menus
+        })
+    }
+
+    /// Returns the names of all windows registered to close on escape.
+    pub fn close_on_escape(&self) -> Vec<String> {
+        with_state(|state| {
+            let mut windows: Vec<_> = state.close_on_escape.keys().cloned().collect();
+            windows.sort();
+            windows
+        })
+    }
+
+    /// Returns the size of an added font.
+    pub fn font_size(&self, identifier: &str) -> Option<f32> {
+        with_state(|state| state.fonts.get(identifier).and_then(|font| font.size))
+    }
+
+    /// Returns the number of receivers of a font.
+    pub fn font_receivers(&self, identifier: &str) -> usize {
+        with_state(|state| {
+            state
+                .fonts
+                .get(identifier)
+                .map_or(0, |font| font.receivers.len())
+        })
+    }
+
+    /// Returns the game directory.
+    pub fn game_dir(&self) -> PathBuf {
+        with_state(|state| state.game_dir.clone())
+    }
+
+    /// Returns the addon directory for the given name.
+    pub fn addon_dir(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.game_dir().join("addons").join(name)
+    }
+}
+
+impl Default for TestHost {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestHost {
+    fn drop(&mut self) {
+        // avoid a double panic when a test fails
+        if std::thread::panicking() {
+            // the panic hook of a loaded addon reports the failure to its log instead of stderr
+            if self.loaded {
+                for LogEntry {
+                    level,
+                    channel,
+                    message,
+                } in self.logs()
+                {
+                    eprintln!("[{level:?}] {channel}: {message}");
+                }
+            }
+        } else {
+            self.unload();
+        }
+        set_state(None);
+    }
+}
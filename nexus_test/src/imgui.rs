@@ -0,0 +1,86 @@
+//! ImGui context of the host.
+
+use nexus::imgui::sys;
+use std::{
+    alloc::{self, Layout},
+    ffi::c_void,
+    ptr,
+    sync::OnceLock,
+};
+
+/// Display size reported to ImGui.
+pub const DISPLAY_SIZE: [f32; 2] = [1920.0, 1080.0];
+
+/// Frame time reported to ImGui.
+pub const DELTA_TIME: f32 = 1.0 / 60.0;
+
+/// Alignment & size of the allocation header.
+const HEADER: usize = 16;
+
+/// Context shared by all hosts, ImGui does not support destroying it safely with addon references around.
+#[derive(Debug, Clone, Copy)]
+pub struct ImguiContext {
+    pub context: *mut sys::ImGuiContext,
+    pub font: *mut sys::ImFont,
+}
+
+unsafe impl Send for ImguiContext {}
+
+unsafe impl Sync for ImguiContext {}
+
+/// Returns the ImGui context, creating it if necessary.
+pub fn context() -> ImguiContext {
+    static CONTEXT: OnceLock<ImguiContext> = OnceLock::new();
+
+    *CONTEXT.get_or_init(|| unsafe {
+        sys::igSetAllocatorFunctions(Some(imgui_malloc), Some(imgui_free), ptr::null_mut());
+        let context = sys::igCreateContext(ptr::null_mut());
+        let io = &mut *sys::igGetIO();
+        io.IniFilename = ptr::null();
+        io.LogFilename = ptr::null();
+        io.DisplaySize = sys::ImVec2 {
+            x: DISPLAY_SIZE[0],
+            y: DISPLAY_SIZE[1],
+        };
+        io.DeltaTime = DELTA_TIME;
+
+        // font atlas needs to be built before the first frame
+        let font = sys::ImFontAtlas_AddFontDefault(io.Fonts, ptr::null());
+        let mut pixels = ptr::null_mut();
+        let (mut width, mut height, mut bpp) = (0, 0, 0);
+        sys::ImFontAtlas_GetTexDataAsRGBA32(
+            io.Fonts,
+            &mut pixels,
+            &mut width,
+            &mut height,
+            &mut bpp,
+        );
+
+        ImguiContext { context, font }
+    })
+}
+
+/// Allocator passed to ImGui and the addon.
+pub unsafe extern "C" fn imgui_malloc(size: usize, _user_data: *mut c_void) -> *mut c_void {
+    let Ok(layout) = Layout::from_size_align(size + HEADER, HEADER) else {
+        return ptr::null_mut();
+    };
+    let ptr = alloc::alloc(layout);
+    if ptr.is_null() {
+        return ptr::null_mut();
+    }
+    ptr.cast::<usize>().write(size);
+    ptr.add(HEADER).cast()
+}
+
+/// Deallocator passed to ImGui and the addon.
+pub unsafe extern "C" fn imgui_free(ptr: *mut c_void, _user_data: *mut c_void) {
+    if !ptr.is_null() {
+        let ptr = ptr.cast::<u8>().sub(HEADER);
+        let size = ptr.cast::<usize>().read();
+        alloc::dealloc(
+            ptr,
+            Layout::from_size_align_unchecked(size + HEADER, HEADER),
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! In-process fake Raidcore Nexus host for testing addons.
+//!
+//! The [`TestHost`] provides a complete fake [`AddonApi`](nexus::AddonApi) implemented in Rust.
+//! Every call made by the addon is recorded as a [`Call`].
+//! Addons are loaded via their exported `GetAddonDef` the same way Nexus does.
+//! Tests can then render frames, raise events and press keybinds.
+//!
+//! Textures and fonts are placeholders without any GPU resources.
+//! Hooks always fail with [`HookStatus::ErrorNotInitialized`](nexus::hook::HookStatus::ErrorNotInitialized).
+//!
+//! # Usage
+//! The addon crate needs to be built as `rlib` in addition to `cdylib` in order to be linked into tests.
+//! ```no_run
+//! use nexus_test::{addon_def, Call, TestHost};
+//!
+//! let mut host = TestHost::new();
+//! unsafe { host.load(addon_def!()) };
+//!
+//! host.render_frame();
+//! assert!(host.trigger_keybind("MY_KEYBIND"));
+//! assert!(host
+//!     .calls()
+//!     .iter()
+//!     .any(|call| matches!(call, Call::SendAlert { .. })));
+//!
+//! host.unload();
+//! ```
+
+mod api;
+mod call;
+mod host;
+mod imgui;
+mod state;
+
+pub use self::{
+    call::{Call, LogEntry},
+    host::TestHost,
+    state::{KeybindBinding, QuickAccessEntry},
+};
+pub use nexus;
+
+/// Returns the `GetAddonDef` function exported by the [`export`](nexus::export) macro.
+///
+/// The addon has to be linked into the current binary.
+#[macro_export]
+macro_rules! addon_def {
+    () => {{
+        extern "system-unwind" {
+            fn GetAddonDef() -> *const $crate::nexus::addon::AddonDefinition;
+        }
+
+        GetAddonDef as $crate::nexus::addon::RawGetAddonDef
+    }};
+}
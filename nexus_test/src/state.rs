@@ -0,0 +1,218 @@
+//! Global state of the active test host.
+
+use crate::call::{Call, LogEntry};
+use nexus::{
+    event::RawEventConsumeUnknown,
+    font::RawFontReceive,
+    gamebind::GameBind,
+    gui::{RawGuiRender, RenderType},
+    imgui::sys::ImFont,
+    keybind::{Keybind, RawKeybindHandler, RawKeybindHandlerOld},
+    texture::{RawTextureReceiveCallback, Texture},
+    wnd_proc::RawWndProcCallback,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{c_char, CString},
+    path::PathBuf,
+    ptr,
+    sync::{Mutex, PoisonError},
+};
+
+/// State of the active host.
+///
+/// The extern functions in the fake [`AddonApi`](nexus::AddonApi) can not capture, so they access this.
+static STATE: Mutex<Option<HostState>> = Mutex::new(None);
+
+/// Runs the closure with the state of the active host.
+///
+/// Panics if no host is active.
+/// Callbacks into the addon must not be invoked from within the closure.
+pub fn with_state<R>(f: impl FnOnce(&mut HostState) -> R) -> R {
+    let mut guard = STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    let state = guard.as_mut().expect("no test host active");
+    f(state)
+}
+
+/// Replaces the state of the active host.
+pub fn set_state(state: Option<HostState>) {
+    *STATE.lock().unwrap_or_else(PoisonError::into_inner) = state;
+}
+
+/// Keybind registered by the addon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeybindBinding {
+    /// Registered with a keybind string like `"ALT+SHIFT+T"`.
+    String(String),
+
+    /// Registered with a [`Keybind`] struct.
+    Struct(Keybind),
+}
+
+/// Keybind handler registered by the addon.
+#[derive(Debug, Clone, Copy)]
+pub enum KeybindHandler {
+    /// Handler receiving presses & releases.
+    New(RawKeybindHandler),
+
+    /// Handler of older API revisions receiving only presses.
+    Old(RawKeybindHandlerOld),
+}
+
+/// Quick access shortcut added by the addon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickAccessEntry {
+    /// Shortcut identifier.
+    pub identifier: String,
+
+    /// Texture identifier.
+    pub texture_identifier: String,
+
+    /// Hover texture identifier.
+    pub texture_hover_identifier: String,
+
+    /// Keybind identifier invoked on click.
+    pub keybind_identifier: String,
+
+    /// Tooltip text.
+    pub tooltip_text: String,
+}
+
+/// Font known to the host.
+#[derive(Debug, Default)]
+pub struct Font {
+    /// Font size, [`None`] if the font was only requested and not added yet.
+    pub size: Option<f32>,
+
+    /// Callbacks receiving the font.
+    pub receivers: Vec<RawFontReceive>,
+}
+
+#[derive(Debug)]
+pub struct HostState {
+    pub calls: Vec<Call>,
+    pub logs: Vec<LogEntry>,
+    pub alerts: Vec<String>,
+    pub signature: Option<i32>,
+    pub game_dir: PathBuf,
+    pub strings: HashMap<String, CString>,
+    pub renders: Vec<(RenderType, RawGuiRender)>,
+    pub close_on_escape: HashMap<String, *mut bool>,
+    pub events: HashMap<String, Vec<RawEventConsumeUnknown>>,
+    pub wnd_procs: Vec<RawWndProcCallback>,
+    pub keybinds: HashMap<String, (KeybindHandler, KeybindBinding)>,
+    pub bound_gamebinds: HashSet<GameBind>,
+    pub resources: HashMap<String, Box<[u64]>>,
+    pub textures: HashMap<String, Box<Texture>>,
+    pub pending_textures: Vec<(String, RawTextureReceiveCallback)>,
+    pub font: *mut ImFont,
+    pub fonts: HashMap<String, Font>,
+    pub pending_fonts: Vec<(String, RawFontReceive)>,
+    pub quick_access: Vec<QuickAccessEntry>,
+    pub context_menus: HashMap<String, (Option<String>, RawGuiRender)>,
+    pub language: String,
+    pub translations: HashMap<(String, String), String>,
+}
+
+// pointers handed to the host by the addon are only used while the host is active
+unsafe impl Send for HostState {}
+
+impl HostState {
+    pub fn new(game_dir: PathBuf, font: *mut ImFont) -> Self {
+        Self {
+            calls: Vec::new(),
+            logs: Vec::new(),
+            alerts: Vec::new(),
+            signature: None,
+            game_dir,
+            strings: HashMap::new(),
+            renders: Vec::new(),
+            close_on_escape: HashMap::new(),
+            events: HashMap::new(),
+            wnd_procs: Vec::new(),
+            keybinds: HashMap::new(),
+            bound_gamebinds: HashSet::new(),
+            resources: HashMap::new(),
+            textures: HashMap::new(),
+            pending_textures: Vec::new(),
+            font,
+            fonts: HashMap::new(),
+            pending_fonts: Vec::new(),
+            quick_access: Vec::new(),
+            context_menus: HashMap::new(),
+            language: "en".into(),
+            translations: HashMap::new(),
+        }
+    }
+
+    /// Records a call.
+    #[inline]
+    pub fn record(&mut self, call: Call) {
+        self.calls.push(call)
+    }
+
+    /// Returns a C string pointer valid for the lifetime of the host.
+    pub fn intern(&mut self, string: impl Into<String>) -> *const c_char {
+        let string = string.into();
+        self.strings
+            .entry(string)
+            .or_insert_with_key(|string| {
+                CString::new(string.as_str()).expect("interned string contains nul")
+            })
+            .as_ptr()
+    }
+
+    /// Returns a pointer to the texture, creating a placeholder if necessary.
+    pub fn texture_or_create(&mut self, identifier: String) -> *const Texture {
+        self.textures
+            .entry(identifier)
+            .or_insert_with(|| Box::new(placeholder_texture(1, 1)))
+            .as_ref()
+    }
+
+    /// Returns a pointer to the texture or `null`.
+    pub fn texture(&self, identifier: &str) -> *const Texture {
+        self.textures
+            .get(identifier)
+            .map(|texture| texture.as_ref() as *const _)
+            .unwrap_or(ptr::null())
+    }
+
+    /// Returns a pointer to the shared resource, creating it if necessary.
+    pub fn share(&mut self, identifier: String, size: usize) -> *mut u64 {
+        let words = ((size + 7) / 8).max(1);
+        self.resources
+            .entry(identifier)
+            .or_insert_with(|| vec![0; words].into_boxed_slice())
+            .as_mut_ptr()
+    }
+}
+
+/// Creates a texture without a GPU resource.
+pub fn placeholder_texture(width: u32, height: u32) -> Texture {
+    Texture {
+        width,
+        height,
+        resource: None,
+    }
+}
+
+/// Converts a C string pointer to an owned [`String`].
+///
+/// # Safety
+/// The pointer must be `null` or point to a valid C string.
+pub unsafe fn string_from_c(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+/// Converts a C string pointer to an optional owned [`String`].
+///
+/// # Safety
+/// The pointer must be `null` or point to a valid C string.
+pub unsafe fn opt_string_from_c(ptr: *const c_char) -> Option<String> {
+    (!ptr.is_null()).then(|| string_from_c(ptr))
+}
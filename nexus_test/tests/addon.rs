@@ -0,0 +1,195 @@
+//! Tests against a small fixture addon linked into the test binary.
+
+use nexus::{
+    alert::send_alert,
+    event::event_subscribe,
+    gui::{register_render, render, RenderType},
+    keybind::{keybind_handler, register_keybind_with_string},
+    log::{log, LogLevel},
+};
+use nexus_test::{addon_def, Call, LogEntry, TestHost};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const NAME: &str = "Fixture Addon";
+const SIGNATURE: i32 = -0x7E57;
+const EVENT: &str = "FIXTURE_EVENT";
+const KEYBIND: &str = "FIXTURE_KEYBIND";
+
+/// Number of frames rendered by the fixture.
+static FRAMES: AtomicUsize = AtomicUsize::new(0);
+
+nexus::export! {
+    name: "Fixture Addon",
+    signature: -0x7E57,
+    load,
+    unload,
+}
+
+fn load() {
+    register_render(
+        RenderType::Render,
+        render!(|ui| {
+            ui.text("fixture");
+            FRAMES.fetch_add(1, Ordering::SeqCst);
+        }),
+    )
+    .revert_on_unload();
+
+    register_keybind_with_string(
+        KEYBIND,
+        keybind_handler!(|id, is_release| {
+            if !is_release {
+                send_alert(format!("{id} pressed"))
+            }
+        }),
+        "ALT+SHIFT+F",
+    )
+    .revert_on_unload();
+
+    unsafe {
+        event_subscribe!(EVENT => i32, |data| {
+            log(LogLevel::Info, NAME, format!("received {data:?}"))
+        })
+    }
+    .revert_on_unload();
+
+    log(LogLevel::Info, NAME, "loaded");
+}
+
+fn unload() {
+    log(LogLevel::Info, NAME, "unloaded");
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    let definition = unsafe { host.load(addon_def!()) };
+    assert_eq!(definition.signature, SIGNATURE);
+    host
+}
+
+fn info(message: &str) -> Call {
+    Call::Log {
+        level: LogLevel::Info,
+        channel: NAME.into(),
+        message: message.into(),
+    }
+}
+
+#[test]
+fn load_registers() {
+    let host = load_fixture();
+
+    assert!(host.is_loaded());
+
+    // the persisted log filter is looked up in the addon directory with the `log_filter` feature
+    let calls: Vec<_> = host
+        .take_calls()
+        .into_iter()
+        .filter(|call| !matches!(call, Call::GetAddonDir { .. }))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            Call::RegisterRender {
+                render_type: RenderType::Render
+            },
+            Call::KeybindRegisterWithString {
+                identifier: KEYBIND.into(),
+                keybind: "ALT+SHIFT+F".into(),
+            },
+            Call::EventSubscribe {
+                identifier: EVENT.into()
+            },
+            info("loaded"),
+        ]
+    );
+    assert_eq!(host.render_count(RenderType::Render), 1);
+    assert_eq!(host.keybinds(), [KEYBIND]);
+    assert_eq!(host.event_subscribers(EVENT), 1);
+}
+
+#[test]
+fn render_frame() {
+    let mut host = load_fixture();
+    host.clear_calls();
+
+    let frames = FRAMES.load(Ordering::SeqCst);
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(FRAMES.load(Ordering::SeqCst), frames + 2);
+    assert_eq!(host.take_calls(), []);
+}
+
+#[test]
+fn raise_event() {
+    let host = load_fixture();
+    host.clear_calls();
+
+    unsafe { host.raise_event(EVENT, &123i32) };
+    host.raise_notification(EVENT);
+    assert_eq!(
+        host.take_calls(),
+        [info("received Some(123)"), info("received None")]
+    );
+}
+
+#[test]
+fn trigger_keybind() {
+    let host = load_fixture();
+    host.clear_calls();
+
+    assert!(host.trigger_keybind(KEYBIND));
+    assert!(!host.trigger_keybind("UNKNOWN_KEYBIND"));
+    assert_eq!(
+        host.take_calls(),
+        [Call::SendAlert {
+            message: format!("{KEYBIND} pressed")
+        }]
+    );
+    assert_eq!(host.alerts(), [format!("{KEYBIND} pressed")]);
+}
+
+#[test]
+fn unload_reverts() {
+    let mut host = load_fixture();
+    host.clear_calls();
+
+    host.unload();
+    assert!(!host.is_loaded());
+
+    let calls = host.take_calls();
+    assert_eq!(calls.first(), Some(&info("unloaded")));
+    for call in [
+        Call::DeregisterRender,
+        Call::KeybindDeregister {
+            identifier: KEYBIND.into(),
+        },
+        Call::EventUnsubscribe {
+            identifier: EVENT.into(),
+        },
+    ] {
+        assert!(calls.contains(&call), "missing {call:?} in {calls:?}");
+    }
+    assert_eq!(host.render_count(RenderType::Render), 0);
+    assert_eq!(host.keybinds(), Vec::<String>::new());
+    assert_eq!(host.event_subscribers(EVENT), 0);
+    assert_eq!(
+        host.logs().last(),
+        Some(&LogEntry {
+            level: LogLevel::Info,
+            channel: NAME.into(),
+            message: "unloaded".into(),
+        })
+    );
+}
+
+#[test]
+fn reload() {
+    let mut host = load_fixture();
+    host.unload();
+    host.clear_calls();
+
+    unsafe { host.load(addon_def!()) };
+    assert!(host.calls().contains(&info("loaded")));
+    assert_eq!(host.event_subscribers(EVENT), 1);
+}
@@ -0,0 +1,107 @@
+//! Tests for addons defined via the `Addon` trait.
+
+use nexus::{
+    addon::{Addon, AddonContext},
+    gui::RenderType,
+    imgui::Ui,
+    log::{log, LogLevel},
+    state::AddonState,
+};
+use nexus_test::{addon_def, Call, TestHost};
+use std::thread;
+
+const NAME: &str = "Trait Addon";
+
+#[nexus::addon(name = "Trait Addon", signature = -0x7EA1)]
+#[derive(Debug, Default)]
+struct TraitAddon {
+    loaded: bool,
+    frames: usize,
+    options: usize,
+}
+
+impl Addon for TraitAddon {
+    fn load(&mut self, ctx: &AddonContext) {
+        self.loaded = true;
+
+        // the state is stored after load, other threads do not block on it
+        let available = thread::spawn(|| AddonState::<Self>::try_get().is_some())
+            .join()
+            .unwrap();
+        log(
+            LogLevel::Info,
+            ctx.name(),
+            format!("loaded, state available: {available}"),
+        );
+    }
+
+    fn unload(&mut self) {
+        log(
+            LogLevel::Info,
+            NAME,
+            format!("unloaded after {} frames", self.frames),
+        );
+    }
+
+    fn render(&mut self, _ui: &Ui) {
+        self.frames += 1;
+    }
+
+    fn options(&mut self, _ui: &Ui) {
+        self.options += 1;
+    }
+}
+
+fn info(message: &str) -> Call {
+    Call::Log {
+        level: LogLevel::Info,
+        channel: NAME.into(),
+        message: message.into(),
+    }
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+#[test]
+fn load() {
+    let host = load_fixture();
+
+    assert!(host
+        .calls()
+        .contains(&info("loaded, state available: false")));
+    assert_eq!(
+        AddonState::with(|addon: &mut TraitAddon| addon.loaded),
+        Some(true)
+    );
+    assert_eq!(host.render_count(RenderType::Render), 1);
+    assert_eq!(host.render_count(RenderType::OptionsRender), 1);
+}
+
+#[test]
+fn render() {
+    let mut host = load_fixture();
+
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(
+        AddonState::with(|addon: &mut TraitAddon| (addon.frames, addon.options)),
+        Some((2, 2))
+    );
+}
+
+#[test]
+fn unload() {
+    let mut host = load_fixture();
+    host.render_frame();
+    host.clear_calls();
+
+    host.unload();
+    assert!(host.calls().contains(&info("unloaded after 1 frames")));
+    assert!(AddonState::<TraitAddon>::try_get().is_none());
+    assert_eq!(host.render_count(RenderType::Render), 0);
+    assert_eq!(host.render_count(RenderType::OptionsRender), 0);
+}
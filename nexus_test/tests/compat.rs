@@ -0,0 +1,104 @@
+//! Tests against a fixture addon requesting an older Addon API revision.
+
+use nexus::{
+    font::{try_get_font, try_resize_font},
+    font_receive,
+    gamebind::{press_gamebind, GameBind},
+    keybind::try_invoke_keybind,
+    log::LogLevel,
+    AddonApi, Error,
+};
+use nexus_test::{addon_def, Call, LogEntry, TestHost};
+
+const NAME: &str = "Compat Addon";
+
+nexus::export! {
+    name: "Compat Addon",
+    signature: -0xC0A7,
+    min_api_version: 4,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    let definition = unsafe { host.load(addon_def!()) };
+    assert_eq!(definition.api_version, 4);
+    host.clear_calls();
+    host
+}
+
+fn unsupported(what: &'static str) -> Error {
+    Error::Unsupported { what, version: 4 }
+}
+
+#[test]
+fn version() {
+    let _host = load_fixture();
+
+    let api = AddonApi::get();
+    assert_eq!(api.version(), 4);
+    assert!(api.has_fonts());
+    assert!(!api.has_font_resize());
+    assert!(!api.has_gamebinds());
+    assert_eq!(TestHost::api().version(), AddonApi::VERSION);
+}
+
+#[test]
+fn try_unsupported() {
+    let host = load_fixture();
+
+    assert_eq!(
+        try_resize_font("COMPAT_FONT", 20.0),
+        Err(unsupported("font resizing"))
+    );
+    assert_eq!(
+        try_invoke_keybind("COMPAT_KEYBIND", false),
+        Err(unsupported("invoking keybinds"))
+    );
+    assert_eq!(
+        unsupported("font resizing").to_string(),
+        "font resizing not supported by Addon API version 4"
+    );
+    assert_eq!(host.take_calls(), []);
+}
+
+#[test]
+fn try_supported() {
+    let host = load_fixture();
+
+    let font =
+        try_get_font("COMPAT_FONT", font_receive!(|_id, _font| {})).expect("fonts are supported");
+    assert_eq!(
+        host.take_calls(),
+        [Call::FontGet {
+            identifier: "COMPAT_FONT".into()
+        }]
+    );
+    font.revert();
+}
+
+#[test]
+fn fallback_logs_once() {
+    let mut host = load_fixture();
+
+    let warning = LogEntry {
+        level: LogLevel::Warning,
+        channel: NAME.into(),
+        message: "game binds not supported by Addon API version 4, ignoring".into(),
+    };
+    let warnings = |host: &TestHost| {
+        host.logs()
+            .into_iter()
+            .filter(|entry| *entry == warning)
+            .count()
+    };
+
+    press_gamebind(GameBind::MoveForward);
+    press_gamebind(GameBind::MoveBackward);
+    assert_eq!(warnings(&host), 1);
+
+    // reported functions are reset on reload
+    host.unload();
+    unsafe { host.load(addon_def!()) };
+    press_gamebind(GameBind::MoveForward);
+    assert_eq!(warnings(&host), 2);
+}
@@ -0,0 +1,71 @@
+//! Tests for crash reports written on panic.
+
+use nexus::{
+    gui::{register_render_fn, RenderType},
+    log::{log, LogLevel},
+    panic::{set_panic_policy, PanicPolicy},
+};
+use nexus_test::{addon_def, TestHost};
+use std::{
+    fs,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+const NAME: &str = "Crash Addon";
+
+/// Whether the render closure panics.
+static PANIC_RENDER: AtomicBool = AtomicBool::new(false);
+
+nexus::export! {
+    name: "Crash Addon",
+    signature: -0xC8A5,
+    load: || {
+        log(LogLevel::Info, NAME, "before crash");
+        register_render_fn(RenderType::Render, |_, _| {
+            if PANIC_RENDER.load(Ordering::SeqCst) {
+                panic!("render crashed");
+            }
+        })
+        .revert_on_unload();
+    },
+}
+
+fn reports(host: &TestHost) -> Vec<String> {
+    host.logs()
+        .into_iter()
+        .filter_map(|entry| {
+            entry
+                .message
+                .strip_prefix("crash report written to ")
+                .map(Into::into)
+        })
+        .collect()
+}
+
+#[test]
+fn report() {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    set_panic_policy(PanicPolicy::Continue);
+
+    PANIC_RENDER.store(true, Ordering::SeqCst);
+    host.render_frame();
+    host.render_frame();
+    PANIC_RENDER.store(false, Ordering::SeqCst);
+
+    // only the first panic at a location is reported
+    let reports = reports(&host);
+    assert_eq!(reports.len(), 1);
+
+    let path = &reports[0];
+    assert!(path.starts_with(host.addon_dir(NAME).to_str().unwrap()));
+    let report = fs::read_to_string(path).expect("failed to read crash report");
+    fs::remove_file(path).unwrap();
+
+    let title = report.lines().next().unwrap();
+    assert!(title.starts_with(NAME) && title.ends_with("crash report"));
+    assert!(report.contains("Addon API version: 6"));
+    assert!(report.contains("render crashed"));
+    assert!(report.contains("Backtrace:"));
+    assert!(report.contains("[Info] Crash Addon: before crash"));
+}
@@ -0,0 +1,85 @@
+//! Tests for the developer diagnostics of the `debug` feature.
+
+use nexus::{
+    debug::{event_stats, keybinds, register_window, textures, EventStats},
+    event::{event_subscribe, event_subscribe_typed_fn, event_subscribe_unknown},
+    gui::RenderType,
+    keybind::{keybind_handler, register_keybind_with_string},
+    texture::get_texture_or_create_from_memory,
+};
+use nexus_test::{addon_def, TestHost};
+
+nexus::export! {
+    name: "Debug Addon",
+    signature: -0xDEB,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host.clear_calls();
+    host
+}
+
+fn stats(hits: u64, last_payload_size: Option<usize>) -> EventStats {
+    EventStats {
+        hits,
+        last_payload_size,
+    }
+}
+
+#[test]
+fn closure_events() {
+    let host = load_fixture();
+
+    let _ = unsafe { event_subscribe_typed_fn::<u64>("DEBUG_CLOSURE", |_| {}) };
+    unsafe { host.raise_event("DEBUG_CLOSURE", &1u64) };
+    host.raise_notification("DEBUG_CLOSURE");
+    assert_eq!(event_stats().get("DEBUG_CLOSURE"), Some(&stats(2, Some(0))));
+}
+
+#[test]
+fn raw_events() {
+    let host = load_fixture();
+
+    let _ = unsafe { event_subscribe!("DEBUG_RAW" => u32, |_| {}) };
+    unsafe { host.raise_event("DEBUG_RAW", &1u32) };
+    unsafe { host.raise_event("DEBUG_RAW", &2u32) };
+    assert_eq!(event_stats().get("DEBUG_RAW"), Some(&stats(2, Some(4))));
+
+    // raw callbacks without the macro are not counted
+    extern "C-unwind" fn callback(_: *const std::ffi::c_void) {}
+    let _ = event_subscribe_unknown("DEBUG_UNCOUNTED", callback);
+    host.raise_notification("DEBUG_UNCOUNTED");
+    assert_eq!(event_stats().get("DEBUG_UNCOUNTED"), None);
+}
+
+#[test]
+fn recorded() {
+    let mut host = load_fixture();
+
+    let _ = register_keybind_with_string("DEBUG_KEYBIND", keybind_handler!(|_, _| {}), "ALT+D");
+    get_texture_or_create_from_memory("DEBUG_TEXTURE", [0u8; 4]);
+    assert_eq!(
+        keybinds().get("DEBUG_KEYBIND").map(String::as_str),
+        Some("ALT+D")
+    );
+    assert!(textures().contains("DEBUG_TEXTURE"));
+
+    // diagnostics are cleared on unload
+    host.unload();
+    assert!(keybinds().is_empty());
+    assert!(textures().is_empty());
+    assert!(event_stats().is_empty());
+}
+
+#[test]
+fn window() {
+    let mut host = load_fixture();
+
+    let window = register_window();
+    assert_eq!(host.render_count(RenderType::Render), 1);
+    host.render_frame();
+    window.revert();
+    assert_eq!(host.render_count(RenderType::Render), 0);
+}
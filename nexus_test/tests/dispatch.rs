@@ -0,0 +1,133 @@
+//! Tests for closures dispatched through shared trampolines.
+
+use nexus::{
+    event::{event_subscribe_unknown_fn, try_event_subscribe_unknown_fn},
+    Error,
+};
+use nexus_test::{addon_def, Call, TestHost};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+const EVENT: &str = "DISPATCH_EVENT";
+
+nexus::export! {
+    name: "Dispatch Addon",
+    signature: -0xD15,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host.clear_calls();
+    host
+}
+
+fn counter() -> (Arc<AtomicUsize>, impl FnMut(*const std::ffi::c_void) + Send) {
+    let count = Arc::new(AtomicUsize::new(0));
+    let inner = count.clone();
+    (count, move |_| {
+        inner.fetch_add(1, Ordering::SeqCst);
+    })
+}
+
+#[test]
+fn shared_trampoline() {
+    let host = load_fixture();
+
+    let (first_count, first) = counter();
+    let (second_count, second) = counter();
+    let first = event_subscribe_unknown_fn(EVENT, first);
+    let second = event_subscribe_unknown_fn(EVENT, second);
+    assert_eq!(
+        host.take_calls(),
+        [Call::EventSubscribe {
+            identifier: EVENT.into()
+        }]
+    );
+    assert_eq!(host.event_subscribers(EVENT), 1);
+
+    host.raise_notification(EVENT);
+    assert_eq!(first_count.load(Ordering::SeqCst), 1);
+    assert_eq!(second_count.load(Ordering::SeqCst), 1);
+
+    first.revert();
+    assert_eq!(host.take_calls(), []);
+    host.raise_notification(EVENT);
+    assert_eq!(first_count.load(Ordering::SeqCst), 1);
+    assert_eq!(second_count.load(Ordering::SeqCst), 2);
+
+    second.revert();
+    assert_eq!(
+        host.take_calls(),
+        [Call::EventUnsubscribe {
+            identifier: EVENT.into()
+        }]
+    );
+    assert_eq!(host.event_subscribers(EVENT), 0);
+}
+
+#[test]
+fn slot_limit() {
+    let host = load_fixture();
+
+    let subscribed: Vec<_> = (0..64)
+        .map(|i| event_subscribe_unknown_fn(format!("DISPATCH_EVENT_{i}"), |_| {}))
+        .collect();
+    host.clear_calls();
+
+    let result = try_event_subscribe_unknown_fn("DISPATCH_EVENT_FULL", |_| {});
+    assert_eq!(
+        result.err(),
+        Some(Error::TooManySubscriptions { capacity: 64 })
+    );
+    assert_eq!(host.take_calls(), []);
+
+    // further closures for an already subscribed event need no slot
+    event_subscribe_unknown_fn("DISPATCH_EVENT_0", |_| {}).revert();
+
+    let mut subscribed = subscribed.into_iter();
+    subscribed.next().unwrap().revert();
+    let (count, callback) = counter();
+    let full =
+        try_event_subscribe_unknown_fn("DISPATCH_EVENT_FULL", callback).expect("slot was freed");
+    host.raise_notification("DISPATCH_EVENT_FULL");
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+
+    full.revert();
+    subscribed.for_each(|revertible| revertible.revert());
+}
+
+#[test]
+fn concurrent_subscribe() {
+    let host = load_fixture();
+
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            thread::spawn(|| {
+                for _ in 0..200 {
+                    event_subscribe_unknown_fn(EVENT, |_| {}).revert();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(host.event_subscribers(EVENT), 0);
+
+    let calls = host.take_calls();
+    let subscribes = calls
+        .iter()
+        .filter(|call| matches!(call, Call::EventSubscribe { .. }))
+        .count();
+    let unsubscribes = calls
+        .iter()
+        .filter(|call| matches!(call, Call::EventUnsubscribe { .. }))
+        .count();
+    assert_eq!(subscribes, unsubscribes);
+}
@@ -0,0 +1,206 @@
+//! Tests for the lifecycle-bound executor.
+
+use nexus::{
+    executor::{is_cancelled, spawn, spawn_blocking, spawn_local_render, JoinHandle},
+    gui::RenderThread,
+    log::LogLevel,
+    on_unload,
+    panic::{set_panic_policy, PanicPolicy},
+};
+use nexus_test::{addon_def, TestHost};
+use std::{
+    future::{self, Future},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+nexus::export! {
+    name: "Executor Addon",
+    signature: -0xE8EC,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+/// Waits until the condition holds, panicking after a timeout.
+fn wait(condition: impl Fn() -> bool) {
+    for _ in 0..1000 {
+        if condition() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(2));
+    }
+    panic!("timed out waiting");
+}
+
+/// Future never completing, records when it is dropped.
+struct Pending(Arc<AtomicBool>);
+
+impl Pending {
+    fn new() -> (Self, Arc<AtomicBool>) {
+        let dropped = Arc::new(AtomicBool::new(false));
+        (Self(dropped.clone()), dropped)
+    }
+}
+
+impl Future for Pending {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        Poll::Pending
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn spawned() {
+    let mut host = load_fixture();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let inner = seen.clone();
+    let handle = spawn(async move {
+        let value = spawn_blocking(|| 21 * 2).await.unwrap();
+        inner.lock().unwrap().push(format!("async {value}"));
+        let inner = inner.clone();
+        spawn_local_render(async move {
+            let rendering = RenderThread::current().is_some();
+            inner.lock().unwrap().push(format!("render {rendering}"));
+        })
+        .await;
+    });
+    wait(|| seen.lock().unwrap().len() == 1);
+
+    // render tasks are only polled during a frame
+    assert!(!handle.is_finished());
+    host.render_frame();
+    wait(|| handle.is_finished());
+    assert_eq!(*seen.lock().unwrap(), ["async 42", "render true"]);
+}
+
+#[test]
+fn cancel() {
+    let _host = load_fixture();
+
+    let (future, dropped) = Pending::new();
+    let handle = spawn(future);
+    handle.cancel();
+    assert!(dropped.load(Ordering::SeqCst));
+    assert!(handle.is_finished());
+}
+
+#[test]
+fn cancel_while_polled() {
+    static HANDLE: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+    let mut host = load_fixture();
+    let (pending, dropped) = Pending::new();
+    let handle = spawn_local_render(async move {
+        // the task cancels itself during its poll
+        if let Some(handle) = HANDLE.lock().unwrap().take() {
+            handle.cancel();
+        }
+        pending.await
+    });
+    *HANDLE.lock().unwrap() = Some(handle);
+    host.render_frame();
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[test]
+fn panicking() {
+    let _host = load_fixture();
+
+    let panicked = spawn(async { panic!("task failed") });
+    let blocking = spawn_blocking(|| panic!("blocking task failed"));
+    wait(|| panicked.is_finished() && blocking.is_finished());
+
+    // other tasks keep running
+    let handle = spawn(async { 1 });
+    wait(|| handle.is_finished());
+}
+
+#[test]
+fn disable_addon() {
+    let _host = load_fixture();
+    set_panic_policy(PanicPolicy::DisableAddon);
+
+    let panicked = spawn(async { panic!("task failed") });
+    wait(|| panicked.is_finished());
+    assert!(is_cancelled());
+
+    // tasks of a disabled addon are not run
+    let ran = Arc::new(AtomicBool::new(false));
+    let handle = spawn({
+        let ran = ran.clone();
+        async move { ran.store(true, Ordering::SeqCst) }
+    });
+    wait(|| handle.is_finished());
+    assert!(!ran.load(Ordering::SeqCst));
+}
+
+#[test]
+fn unload() {
+    let mut host = load_fixture();
+
+    let (future, dropped) = Pending::new();
+    let worker = spawn(future);
+    let (future, render_dropped) = Pending::new();
+    let render = spawn_local_render(future);
+    let (started, stopped) = (
+        Arc::new(AtomicBool::new(false)),
+        Arc::new(AtomicBool::new(false)),
+    );
+    let blocking = spawn_blocking({
+        let (started, stopped) = (started.clone(), stopped.clone());
+        move || {
+            started.store(true, Ordering::SeqCst);
+            while !is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            stopped.store(true, Ordering::SeqCst);
+        }
+    });
+    wait(|| started.load(Ordering::SeqCst));
+    let spawned_on_unload = Arc::new(Mutex::new(None));
+    on_unload({
+        let spawned_on_unload = spawned_on_unload.clone();
+        move || *spawned_on_unload.lock().unwrap() = Some(spawn(future::pending::<()>()))
+    });
+    host.unload();
+
+    assert!(dropped.load(Ordering::SeqCst) && worker.is_finished());
+    assert!(render_dropped.load(Ordering::SeqCst) && render.is_finished());
+    assert!(stopped.load(Ordering::SeqCst) && blocking.is_finished());
+    assert!(host
+        .logs()
+        .iter()
+        .any(|entry| entry.level == LogLevel::Warning
+            && entry
+                .message
+                .starts_with("waiting for blocking task spawned at ")
+            && entry.message.contains("tests/executor.rs")));
+
+    // tasks spawned during unload are cancelled immediately
+    let spawned = spawned_on_unload.lock().unwrap().take().unwrap();
+    assert!(spawned.is_finished());
+
+    // tasks can be spawned again after reload
+    unsafe { host.load(addon_def!()) };
+    assert!(!is_cancelled());
+    let handle = spawn(async { 1 });
+    wait(|| handle.is_finished());
+}
@@ -0,0 +1,76 @@
+//! Tests for resetting globals on unload.
+
+use nexus::{log::LogLevel, on_unload, AddonApi};
+use nexus_test::{addon_def, TestHost};
+use std::{
+    panic,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+/// Number of unload actions performed.
+static UNLOAD_ACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+nexus::export! {
+    name: "Globals Addon",
+    signature: -0x610B,
+    load: || {
+        on_unload(|| {
+            UNLOAD_ACTIONS.fetch_add(1, Ordering::SeqCst);
+
+            // actions added by other actions are performed as well
+            on_unload(|| {
+                UNLOAD_ACTIONS.fetch_add(1, Ordering::SeqCst);
+            });
+        });
+    },
+}
+
+fn is_initialized() -> bool {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AddonApi::get);
+    panic::set_hook(hook);
+    result.is_ok()
+}
+
+#[test]
+fn reload() {
+    let mut host = TestHost::new();
+
+    for _ in 0..3 {
+        let before = UNLOAD_ACTIONS.load(Ordering::SeqCst);
+        unsafe { host.load(addon_def!()) };
+        assert!(is_initialized());
+        assert_eq!(AddonApi::get().version(), AddonApi::VERSION);
+
+        host.unload();
+        assert!(!is_initialized());
+        assert_eq!(UNLOAD_ACTIONS.load(Ordering::SeqCst), before + 2);
+    }
+}
+
+#[test]
+fn panic_hook_restored() {
+    static PREVIOUS_HOOK: AtomicBool = AtomicBool::new(false);
+
+    let mut host = TestHost::new();
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| PREVIOUS_HOOK.store(true, Ordering::SeqCst)));
+
+    unsafe { host.load(addon_def!()) };
+    let _ = panic::catch_unwind(|| panic!("while loaded"));
+    assert!(!PREVIOUS_HOOK.load(Ordering::SeqCst));
+    assert!(host.logs().iter().any(|entry| {
+        entry.level == LogLevel::Critical && entry.message.contains("while loaded")
+    }));
+
+    host.unload();
+    let _ = panic::catch_unwind(|| panic!("after unload"));
+    assert!(PREVIOUS_HOOK.load(Ordering::SeqCst));
+    assert!(!host
+        .logs()
+        .iter()
+        .any(|entry| entry.message.contains("after unload")));
+
+    panic::set_hook(hook);
+}
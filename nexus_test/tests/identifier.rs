@@ -0,0 +1,98 @@
+//! Tests for identifiers passed to Nexus.
+
+use nexus::{
+    c_ident,
+    event::Event,
+    texture::{get_texture, try_get_texture},
+    Error, Identifier, IntoCStr,
+};
+use nexus_test::{addon_def, Call, TestHost};
+use std::{
+    ffi::CStr,
+    sync::{Arc, Mutex},
+};
+
+const TEX_ICON: Identifier = c_ident!("TEX_IDENTIFIER_ICON");
+
+const EVENT: Event<u32> =
+    unsafe { Event::new(CStr::from_bytes_with_nul_unchecked(b"IDENTIFIER_EVENT\0")) };
+
+nexus::export! {
+    name: "Identifier Addon",
+    signature: -0x1DE,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host.clear_calls();
+    host
+}
+
+fn texture_get(identifier: &str) -> Call {
+    Call::TextureGet {
+        identifier: identifier.into(),
+    }
+}
+
+#[test]
+fn event_borrowed() {
+    let host = load_fixture();
+
+    // the event identifier is passed without allocation
+    let identifier = EVENT.into_c_str().unwrap();
+    assert!(std::ptr::eq(identifier, EVENT.identifier));
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let inner = received.clone();
+    let subscription = EVENT.subscribe_fn(move |data| {
+        inner.lock().unwrap().push(data.copied());
+    });
+    EVENT.raise(&7);
+    unsafe { host.raise_event("IDENTIFIER_EVENT", &8u32) };
+    subscription.revert();
+
+    assert_eq!(*received.lock().unwrap(), [Some(7), Some(8)]);
+    assert!(host.calls().contains(&Call::EventRaise {
+        identifier: "IDENTIFIER_EVENT".into()
+    }));
+}
+
+#[test]
+fn conversions() {
+    let host = load_fixture();
+    host.insert_texture("TEX_IDENTIFIER_ICON", 32, 16);
+
+    let owned = Identifier::new("TEX_IDENTIFIER_ICON").unwrap();
+    assert_eq!(owned, TEX_ICON);
+    assert_eq!(TEX_ICON.to_str(), Some("TEX_IDENTIFIER_ICON"));
+
+    assert!(get_texture(&TEX_ICON).is_some());
+    assert!(get_texture(&owned).is_some());
+    assert!(get_texture("TEX_IDENTIFIER_ICON").is_some());
+    assert!(get_texture(String::from("TEX_IDENTIFIER_ICON")).is_some());
+    assert_eq!(
+        host.take_calls(),
+        [
+            texture_get("TEX_IDENTIFIER_ICON"),
+            texture_get("TEX_IDENTIFIER_ICON"),
+            texture_get("TEX_IDENTIFIER_ICON"),
+            texture_get("TEX_IDENTIFIER_ICON"),
+        ]
+    );
+}
+
+#[test]
+fn nul() {
+    let host = load_fixture();
+
+    assert!(matches!(
+        Identifier::new("TEX\0ICON"),
+        Err(Error::Nul { position: 3, .. })
+    ));
+    assert!(matches!(
+        try_get_texture("TEX\0ICON"),
+        Err(Error::Nul { position: 3, .. })
+    ));
+    assert_eq!(host.take_calls(), []);
+}
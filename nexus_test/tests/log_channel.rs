@@ -0,0 +1,169 @@
+//! Tests for log channels mapped by target.
+
+use nexus::log::{
+    map_log_target, set_default_log_channel, unmap_log_target, Color, LogChannel, LogLevel,
+    LogMessage,
+};
+use nexus_test::{addon_def, LogEntry, TestHost};
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+nexus::export! {
+    name: "Log Channel Addon",
+    signature: -0x10C4,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+fn logged(host: &TestHost) -> Vec<(LogLevel, String, String)> {
+    host.logs()
+        .into_iter()
+        .map(
+            |LogEntry {
+                 level,
+                 channel,
+                 message,
+             }| (level, channel, message),
+        )
+        .collect()
+}
+
+fn entry(level: LogLevel, channel: &str, message: &str) -> (LogLevel, String, String) {
+    (level, channel.into(), message.into())
+}
+
+#[test]
+fn targets() {
+    let host = load_fixture();
+
+    map_log_target("my_addon", LogChannel::new("MyAddon"));
+    map_log_target("my_addon::combat", LogChannel::new("MyAddon/Combat"));
+    log::info!(target: "my_addon::combat::skills", "skill");
+    log::info!(target: "my_addon::combatant", "combatant");
+    log::info!(target: "my_addon", "root");
+    log::info!(target: "other", "other");
+
+    // removed targets fall back to the parent module
+    unmap_log_target("my_addon::combat");
+    log::info!(target: "my_addon::combat", "unmapped");
+    assert_eq!(
+        logged(&host),
+        [
+            entry(LogLevel::Info, "MyAddon/Combat", "skill"),
+            entry(LogLevel::Info, "MyAddon", "combatant"),
+            entry(LogLevel::Info, "MyAddon", "root"),
+            entry(LogLevel::Info, "Log Channel Addon", "other"),
+            entry(LogLevel::Info, "MyAddon", "unmapped"),
+        ]
+    );
+}
+
+#[test]
+fn colors() {
+    let host = load_fixture();
+
+    set_default_log_channel(LogChannel::addon().with_default_colors());
+    map_log_target(
+        "combat",
+        LogChannel::new("Combat").with_color(LogLevel::Info, Color::BLUE),
+    );
+    log::error!("error");
+    log::info!("info");
+    log::debug!("debug");
+    log::info!(target: "combat", "hit");
+    log::warn!(target: "combat", "miss");
+    assert_eq!(
+        logged(&host),
+        [
+            entry(
+                LogLevel::Critical,
+                "Log Channel Addon",
+                "<c=#FF4D4D>error</c>"
+            ),
+            entry(LogLevel::Info, "Log Channel Addon", "info"),
+            entry(LogLevel::Debug, "Log Channel Addon", "<c=#A0A0A0>debug</c>"),
+            entry(LogLevel::Info, "Combat", "<c=#60A0FF>hit</c>"),
+            entry(LogLevel::Warning, "Combat", "miss"),
+        ]
+    );
+}
+
+#[test]
+fn colored_message() {
+    let host = load_fixture();
+
+    map_log_target(
+        "combat",
+        LogChannel::new("Combat").with_color(LogLevel::Info, Color::GRAY),
+    );
+    let message = LogMessage::new()
+        .text("Hit ")
+        .colored(Color::RED, "</c> Player")
+        .text(" for 1234");
+    log::info!(target: "combat", "{message}");
+    log::info!(target: "plain", "{message}");
+
+    // markup of the message is kept, its user content is escaped once
+    assert_eq!(
+        logged(&host),
+        [
+            entry(
+                LogLevel::Info,
+                "Combat",
+                "<c=#A0A0A0>Hit <c=#FF4D4D><\u{200B}/c> Player</c> for 1234</c>"
+            ),
+            entry(
+                LogLevel::Info,
+                "Log Channel Addon",
+                "Hit <c=#FF4D4D><\u{200B}/c> Player</c> for 1234"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn tracing_targets() {
+    let host = load_fixture();
+
+    map_log_target(
+        "combat",
+        LogChannel::new("Combat").with_color(LogLevel::Warning, Color::ORANGE),
+    );
+    let subscriber = Registry::default().with(nexus::tracing::NexusLayer::new());
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(target: "combat", "miss");
+        tracing::warn!(target: "other", "other");
+    });
+    assert_eq!(
+        logged(&host),
+        [
+            entry(LogLevel::Warning, "Combat", "<c=#FFA040>miss</c>"),
+            entry(LogLevel::Warning, "Log Channel Addon", "other"),
+        ]
+    );
+}
+
+#[test]
+fn unload() {
+    let mut host = load_fixture();
+
+    map_log_target("combat", LogChannel::new("Combat"));
+    set_default_log_channel(LogChannel::new("Default"));
+    host.unload();
+
+    // channels are reset on unload
+    unsafe { host.load(addon_def!()) };
+    let logged = host.logs().len();
+    log::info!(target: "combat", "hit");
+    assert_eq!(
+        host.logs()[logged..],
+        [LogEntry {
+            level: LogLevel::Info,
+            channel: "Log Channel Addon".into(),
+            message: "hit".into(),
+        }]
+    );
+}
@@ -0,0 +1,117 @@
+//! Tests for the runtime-adjustable log filter.
+
+use nexus::{
+    gui::{register_render_fn, RenderType},
+    log::{
+        log_filter, render_log_filter_options, reset_log_filter, set_log_filter,
+        try_set_log_filter, LOG_FILTER_FILE,
+    },
+    Error,
+};
+use nexus_test::{addon_def, TestHost};
+use std::fs;
+
+nexus::export! {
+    name: "Log Filter Addon",
+    signature: -0x10F1,
+    log_filter: "info",
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+fn messages(host: &TestHost) -> Vec<String> {
+    host.logs().into_iter().map(|entry| entry.message).collect()
+}
+
+/// Logs a message for every level.
+fn log_all(target: &str) {
+    log::warn!(target: target, "warn");
+    log::info!(target: target, "info");
+    log::debug!(target: target, "debug");
+}
+
+#[test]
+fn default() {
+    let host = load_fixture();
+
+    assert_eq!(log_filter(), "info");
+    log_all("log_filter");
+    assert_eq!(messages(&host), ["warn", "info"]);
+}
+
+#[test]
+fn set() {
+    let host = load_fixture();
+
+    set_log_filter("warn,log_filter::combat=debug");
+    assert_eq!(log_filter(), "warn,log_filter::combat=debug");
+    log_all("log_filter");
+    log_all("log_filter::combat");
+    assert_eq!(messages(&host), ["warn", "warn", "info", "debug"]);
+
+    // invalid directives keep the current filter
+    assert!(matches!(
+        try_set_log_filter("warn=debug=trace"),
+        Err(Error::LogFilter { .. })
+    ));
+    assert_eq!(log_filter(), "warn,log_filter::combat=debug");
+
+    reset_log_filter();
+}
+
+#[test]
+fn persisted() {
+    let mut host = load_fixture();
+    let file = host.addon_dir("Log Filter Addon").join(LOG_FILTER_FILE);
+
+    set_log_filter(" debug ");
+    assert_eq!(fs::read_to_string(&file).unwrap(), "debug");
+
+    // persisted directives are restored on reload
+    host.unload();
+    unsafe { host.load(addon_def!()) };
+    assert_eq!(log_filter(), "debug");
+    let logged = host.logs().len();
+    log_all("log_filter");
+    assert_eq!(messages(&host)[logged..], ["warn", "info", "debug"]);
+
+    // resetting restores the default and removes the file
+    reset_log_filter();
+    assert_eq!(log_filter(), "info");
+    assert!(!file.exists());
+    host.unload();
+    unsafe { host.load(addon_def!()) };
+    assert_eq!(log_filter(), "info");
+}
+
+#[test]
+fn invalid_persisted() {
+    let mut host = load_fixture();
+    let file = host.addon_dir("Log Filter Addon").join(LOG_FILTER_FILE);
+
+    // invalid persisted directives fall back to the default
+    host.unload();
+    fs::create_dir_all(file.parent().unwrap()).unwrap();
+    fs::write(&file, "warn=debug=trace").unwrap();
+    unsafe { host.load(addon_def!()) };
+    assert_eq!(log_filter(), "info");
+
+    reset_log_filter();
+}
+
+#[test]
+fn options() {
+    let mut host = load_fixture();
+
+    register_render_fn(RenderType::OptionsRender, |ui, _| {
+        render_log_filter_options(ui)
+    })
+    .revert_on_unload();
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(log_filter(), "info");
+}
@@ -0,0 +1,88 @@
+//! Tests for log messages with color markup.
+
+use nexus::{
+    alert::send_alert,
+    log::{log, try_log, Color, LogLevel, LogMessage},
+    Error,
+};
+use nexus_test::{addon_def, LogEntry, TestHost};
+
+nexus::export! {
+    name: "Log Message Addon",
+    signature: -0x10E5,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+fn message() -> LogMessage {
+    LogMessage::new()
+        .text("Hit ")
+        .colored(Color::RED, "<c=#FFFFFF>Player</c>")
+        .text(" for 1234")
+}
+
+const MARKUP: &str = "Hit <c=#FF4D4D><\u{200B}c=#FFFFFF>Player<\u{200B}/c></c> for 1234";
+
+#[test]
+fn logged() {
+    let host = load_fixture();
+
+    log(LogLevel::Info, "Channel", message());
+    log(LogLevel::Info, "Channel", format!("{}!", message()));
+    assert_eq!(
+        host.logs(),
+        [
+            LogEntry {
+                level: LogLevel::Info,
+                channel: "Channel".into(),
+                message: MARKUP.into(),
+            },
+            LogEntry {
+                level: LogLevel::Info,
+                channel: "Channel".into(),
+                message: format!("{MARKUP}!"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn alert() {
+    let host = load_fixture();
+
+    send_alert(message());
+    assert_eq!(host.alerts(), [MARKUP]);
+}
+
+#[test]
+fn log_macros() {
+    let host = load_fixture();
+
+    let message = message();
+    log::warn!("{message}");
+    assert_eq!(
+        host.logs(),
+        [LogEntry {
+            level: LogLevel::Warning,
+            channel: "Log Message Addon".into(),
+            message: MARKUP.into(),
+        }]
+    );
+}
+
+#[test]
+fn nul() {
+    let _host = load_fixture();
+
+    assert_eq!(
+        try_log(LogLevel::Info, "Channel", LogMessage::new().text("a\0b")),
+        Err(Error::Nul {
+            what: "log message",
+            position: 1,
+        })
+    );
+}
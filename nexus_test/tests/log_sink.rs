@@ -0,0 +1,101 @@
+//! Tests for asynchronous logging.
+
+use nexus::{
+    log::{enable_async_logging, flush_log, is_async_logging, LogLevel, LogOverflow},
+    tracing::NexusLayer,
+};
+use nexus_test::{addon_def, TestHost};
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+nexus::export! {
+    name: "Log Sink Addon",
+    signature: -0x1055,
+}
+
+fn messages(host: &TestHost) -> Vec<String> {
+    host.logs().into_iter().map(|entry| entry.message).collect()
+}
+
+const DROPPED: &str = "1 log records dropped due to full queue";
+
+#[test]
+fn buffered() {
+    let mut host = TestHost::new();
+
+    enable_async_logging(3, LogOverflow::DropNewest);
+    assert!(is_async_logging());
+    log::info!("first");
+    log::debug!("second");
+    log::warn!("third");
+    log::info!("dropped");
+    assert_eq!(messages(&host), [] as [&str; 0]);
+
+    // buffered records are logged once initialized
+    unsafe { host.load(addon_def!()) };
+    flush_log();
+    assert_eq!(messages(&host), ["first", "second", "third", DROPPED]);
+    let dropped = &host.logs()[3];
+    assert_eq!(dropped.level, LogLevel::Warning);
+    assert_eq!(dropped.channel, "Log Sink Addon");
+}
+
+#[test]
+fn drop_oldest() {
+    let mut host = TestHost::new();
+
+    enable_async_logging(2, LogOverflow::DropOldest);
+    for message in ["first", "second", "third"] {
+        log::info!("{message}");
+    }
+    unsafe { host.load(addon_def!()) };
+    flush_log();
+    assert_eq!(messages(&host), ["second", "third", DROPPED]);
+}
+
+#[test]
+fn block() {
+    let mut host = TestHost::new();
+
+    // blocking is not possible before initialization
+    enable_async_logging(2, LogOverflow::Block);
+    for message in ["first", "second", "third"] {
+        log::info!("{message}");
+    }
+    unsafe { host.load(addon_def!()) };
+    flush_log();
+    assert_eq!(messages(&host), ["first", "second", DROPPED]);
+
+    // afterwards producers wait for the worker
+    let logged = host.logs().len();
+    for i in 0..100 {
+        log::info!("{i}");
+    }
+    flush_log();
+    let expected: Vec<_> = (0..100).map(|i| i.to_string()).collect();
+    assert_eq!(messages(&host)[logged..], expected);
+}
+
+#[test]
+fn unload() {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+
+    enable_async_logging(16, LogOverflow::Block);
+    for i in 0..50 {
+        log::info!("{i}");
+    }
+    let subscriber = Registry::default().with(NexusLayer::new());
+    tracing::subscriber::with_default(subscriber, || tracing::info!("tracing"));
+
+    // queued records are flushed on unload
+    host.unload();
+    assert!(!is_async_logging());
+    let mut expected: Vec<_> = (0..50).map(|i| i.to_string()).collect();
+    expected.push("tracing".into());
+    assert_eq!(messages(&host), expected);
+
+    // logging is synchronous again after reload
+    unsafe { host.load(addon_def!()) };
+    log::info!("sync");
+    assert_eq!(messages(&host).last().map(String::as_str), Some("sync"));
+}
@@ -0,0 +1,111 @@
+//! Tests for identifier namespacing.
+
+use nexus::{
+    event::{event_raise_notification, event_subscribe_unknown_fn, WINDOW_RESIZED},
+    keybind::register_keybind_with_string_fn,
+    namespace,
+    texture::get_texture,
+    Global,
+};
+use nexus_test::{addon_def, Call, TestHost};
+use std::sync::{Arc, Mutex};
+
+nexus::export! {
+    name: "Namespace Addon",
+    signature: -0x5A5E,
+    namespace: true,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host.clear_calls();
+    host
+}
+
+#[test]
+fn enabled() {
+    let mut host = load_fixture();
+    assert_eq!(namespace(), Some("Namespace Addon"));
+
+    host.unload();
+    assert_eq!(namespace(), None);
+}
+
+#[test]
+fn keybind_round_trip() {
+    let host = load_fixture();
+
+    let pressed = Arc::new(Mutex::new(Vec::new()));
+    let inner = pressed.clone();
+    let keybind = register_keybind_with_string_fn(
+        "KB_TOGGLE",
+        move |identifier, _| inner.lock().unwrap().push(identifier.to_owned()),
+        "ALT+N",
+    );
+    assert_eq!(host.keybinds(), ["Namespace Addon::KB_TOGGLE"]);
+
+    // the handler receives the identifier without namespace
+    assert!(host.press_keybind("Namespace Addon::KB_TOGGLE"));
+    assert!(!host.press_keybind("KB_TOGGLE"));
+    assert_eq!(*pressed.lock().unwrap(), ["KB_TOGGLE"]);
+
+    keybind.revert();
+    assert_eq!(host.keybinds(), Vec::<String>::new());
+}
+
+#[test]
+fn event_round_trip() {
+    let host = load_fixture();
+
+    let subscription = event_subscribe_unknown_fn("NS_EVENT", |_| {});
+    assert_eq!(host.event_subscribers("Namespace Addon::NS_EVENT"), 1);
+    assert_eq!(host.event_subscribers("NS_EVENT"), 0);
+
+    event_raise_notification("NS_EVENT");
+    subscription.revert();
+    assert!(host.calls().contains(&Call::EventRaiseNotification {
+        identifier: "Namespace Addon::NS_EVENT".into()
+    }));
+}
+
+#[test]
+fn global() {
+    let host = load_fixture();
+
+    event_raise_notification(Global("EV_REQUEST_ACCOUNT_NAME"));
+    WINDOW_RESIZED.raise(&());
+    assert_eq!(
+        host.take_calls(),
+        [
+            Call::EventRaiseNotification {
+                identifier: "EV_REQUEST_ACCOUNT_NAME".into()
+            },
+            Call::EventRaise {
+                identifier: "EV_WINDOW_RESIZED".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn many_identifiers() {
+    let host = load_fixture();
+
+    // identifiers beyond the cache capacity are namespaced as well
+    for _ in 0..2 {
+        for i in 0..300 {
+            get_texture(format!("TEX_{i}"));
+        }
+    }
+    let calls = host.take_calls();
+    assert_eq!(calls.len(), 600);
+    for (i, call) in calls.into_iter().enumerate() {
+        assert_eq!(
+            call,
+            Call::TextureGet {
+                identifier: format!("Namespace Addon::TEX_{}", i % 300)
+            }
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Tests for panic containment and the panic policy.
+
+use nexus::{
+    gui::{register_render_fn, RenderType},
+    keybind::{keybind_handler, register_keybind_with_string},
+    log::LogLevel,
+    panic::{is_addon_disabled, set_panic_policy, PanicPolicy},
+};
+use nexus_test::{addon_def, Call, TestHost};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+const KEYBIND: &str = "PANIC_KEYBIND";
+
+/// Whether the first render closure panics.
+static PANIC_RENDER: AtomicBool = AtomicBool::new(false);
+
+/// Whether the load panics after registering.
+static PANIC_LOAD: AtomicBool = AtomicBool::new(false);
+
+/// Number of invocations of the first render closure.
+static FIRST: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of invocations of the second render closure.
+static SECOND: AtomicUsize = AtomicUsize::new(0);
+
+nexus::export! {
+    name: "Panic Addon",
+    signature: -0xBAD,
+    load: || {
+        register_render_fn(RenderType::Render, |_, _| {
+            FIRST.fetch_add(1, Ordering::SeqCst);
+            if PANIC_RENDER.load(Ordering::SeqCst) {
+                panic!("render failed");
+            }
+        })
+        .revert_on_unload();
+
+        register_render_fn(RenderType::Render, |_, _| {
+            SECOND.fetch_add(1, Ordering::SeqCst);
+        })
+        .revert_on_unload();
+
+        register_keybind_with_string(KEYBIND, keybind_handler!(|_, _| {}), "ALT+P")
+            .revert_on_unload();
+
+        if PANIC_LOAD.load(Ordering::SeqCst) {
+            panic!("load failed");
+        }
+    },
+}
+
+fn load_fixture(policy: PanicPolicy) -> TestHost {
+    PANIC_RENDER.store(false, Ordering::SeqCst);
+    PANIC_LOAD.store(false, Ordering::SeqCst);
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    set_panic_policy(policy);
+    host.clear_calls();
+    host
+}
+
+fn counts() -> (usize, usize) {
+    (FIRST.load(Ordering::SeqCst), SECOND.load(Ordering::SeqCst))
+}
+
+fn logged(host: &TestHost, level: LogLevel, message: &str) -> bool {
+    host.logs()
+        .iter()
+        .any(|entry| entry.level == level && entry.message.contains(message))
+}
+
+#[test]
+fn continue_policy() {
+    let mut host = load_fixture(PanicPolicy::Continue);
+    PANIC_RENDER.store(true, Ordering::SeqCst);
+
+    let (first, second) = counts();
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(counts(), (first + 2, second + 2));
+    assert!(logged(
+        &host,
+        LogLevel::Critical,
+        "render failed, continuing"
+    ));
+    assert!(!is_addon_disabled());
+}
+
+#[test]
+fn disable_callback() {
+    let mut host = load_fixture(PanicPolicy::DisableCallback);
+    PANIC_RENDER.store(true, Ordering::SeqCst);
+
+    let (first, second) = counts();
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(counts(), (first + 1, second + 2));
+    assert!(logged(
+        &host,
+        LogLevel::Critical,
+        "render failed, callback disabled"
+    ));
+    assert!(!is_addon_disabled());
+    assert_eq!(host.render_count(RenderType::Render), 1);
+}
+
+#[test]
+fn disable_addon() {
+    let mut host = load_fixture(PanicPolicy::DisableAddon);
+    PANIC_RENDER.store(true, Ordering::SeqCst);
+
+    host.render_frame();
+    assert!(is_addon_disabled());
+    assert!(logged(
+        &host,
+        LogLevel::Critical,
+        "render failed, addon disabled"
+    ));
+    assert!(logged(&host, LogLevel::Warning, "reverted, addon disabled"));
+    assert_eq!(host.render_count(RenderType::Render), 0);
+    assert_eq!(host.keybinds(), Vec::<String>::new());
+
+    // registrations are not reverted again on unload
+    host.clear_calls();
+    host.unload();
+    assert_eq!(host.take_calls(), []);
+
+    PANIC_RENDER.store(false, Ordering::SeqCst);
+    unsafe { host.load(addon_def!()) };
+    assert!(!is_addon_disabled());
+    assert_eq!(host.render_count(RenderType::Render), 1);
+}
+
+#[test]
+fn load_panic() {
+    let mut host = TestHost::new();
+    PANIC_LOAD.store(true, Ordering::SeqCst);
+    unsafe { host.load(addon_def!()) };
+    PANIC_LOAD.store(false, Ordering::SeqCst);
+
+    assert!(is_addon_disabled());
+    assert!(logged(&host, LogLevel::Critical, "addon load panicked"));
+    assert!(host.calls().contains(&Call::DeregisterRender));
+    assert!(host.calls().contains(&Call::KeybindDeregister {
+        identifier: KEYBIND.into()
+    }));
+    assert_eq!(host.render_count(RenderType::Render), 0);
+    assert_eq!(host.keybinds(), Vec::<String>::new());
+
+    let (first, second) = counts();
+    host.render_frame();
+    assert_eq!(counts(), (first, second));
+}
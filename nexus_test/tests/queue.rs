@@ -0,0 +1,165 @@
+//! Tests for the main thread task queue.
+
+use nexus::{
+    defer,
+    log::LogLevel,
+    on_unload,
+    queue::{try_defer, MainThreadQueue},
+    Error,
+};
+use nexus_test::{addon_def, TestHost};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+nexus::export! {
+    name: "Queue Addon",
+    signature: -0x0E0E,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+/// Order of run and dropped tasks.
+#[derive(Debug, Clone, Default)]
+struct Tasks(Arc<Mutex<Vec<String>>>);
+
+impl Tasks {
+    fn record(&self, name: impl Into<String>) {
+        self.0.lock().unwrap().push(name.into())
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Records when a task is dropped.
+struct DropGuard(Tasks, &'static str);
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        self.0.record(format!("dropped {}", self.1))
+    }
+}
+
+#[test]
+fn order() {
+    let mut host = load_fixture();
+    let tasks = Tasks::default();
+
+    for name in ["a", "b", "c"] {
+        let tasks = tasks.clone();
+        thread::spawn(move || defer(move |_, _| tasks.record(name)))
+            .join()
+            .unwrap();
+    }
+    let inner = tasks.clone();
+    defer(move |_, _| {
+        inner.record("d");
+        // tasks submitted by tasks are run in the next frame
+        let inner = inner.clone();
+        defer(move |_, _| inner.record("nested"));
+    });
+    assert_eq!(tasks.take(), [] as [&str; 0]);
+
+    host.render_frame();
+    assert_eq!(tasks.take(), ["a", "b", "c", "d"]);
+    host.render_frame();
+    assert_eq!(tasks.take(), ["nested"]);
+    host.render_frame();
+    assert_eq!(tasks.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn capacity() {
+    static QUEUE: MainThreadQueue = MainThreadQueue::new(2);
+
+    let mut host = load_fixture();
+    let tasks = Tasks::default();
+
+    for name in ["a", "b", "c"] {
+        let tasks = tasks.clone();
+        let result = QUEUE.try_push(move |_, _| tasks.record(name));
+        assert_eq!(result.is_ok(), name != "c");
+    }
+    assert_eq!(QUEUE.capacity(), 2);
+    assert_eq!(QUEUE.len(), 2);
+    assert_eq!(
+        QUEUE.try_push(|_, _| {}),
+        Err(Error::QueueFull { capacity: 2 })
+    );
+
+    // tasks are accepted again once the queue is drained
+    host.render_frame();
+    assert!(QUEUE.is_empty());
+    assert_eq!(tasks.take(), ["a", "b"]);
+    assert_eq!(QUEUE.try_push(|_, _| {}), Ok(()));
+}
+
+#[test]
+fn not_loaded() {
+    let _host = TestHost::new();
+
+    assert_eq!(try_defer(|_, _| {}), Err(Error::NotInitialized));
+}
+
+#[test]
+fn unload_flush() {
+    let mut host = load_fixture();
+    let tasks = Tasks::default();
+
+    for name in ["first", "second"] {
+        let guard = DropGuard(tasks.clone(), name);
+        defer(move |_, _| guard.0.record(guard.1));
+    }
+    let closed = Arc::new(Mutex::new(None));
+    on_unload({
+        let closed = closed.clone();
+        move || *closed.lock().unwrap() = Some(try_defer(|_, _| {}))
+    });
+    host.unload();
+
+    // pending tasks are dropped without being run
+    assert_eq!(tasks.take(), ["dropped first", "dropped second"]);
+    assert!(host
+        .logs()
+        .iter()
+        .any(|entry| entry.level == LogLevel::Debug
+            && entry.message == "dropped 2 pending deferred tasks on unload"));
+    assert_eq!(*closed.lock().unwrap(), Some(Err(Error::QueueClosed)));
+
+    // the queue accepts tasks again after reload
+    unsafe { host.load(addon_def!()) };
+    let inner = tasks.clone();
+    defer(move |_, _| inner.record("reloaded"));
+    host.render_frame();
+    assert_eq!(tasks.take(), ["reloaded"]);
+}
+
+#[test]
+fn panicking() {
+    static QUEUE: MainThreadQueue = MainThreadQueue::new(8);
+
+    let mut host = load_fixture();
+    let tasks = Tasks::default();
+
+    // the default policy disables the queue
+    QUEUE.push(|_, _| panic!("task failed"));
+    let inner = tasks.clone();
+    QUEUE.push(move |_, _| inner.record("skipped"));
+    host.render_frame();
+    assert_eq!(tasks.take(), [] as [&str; 0]);
+    assert!(QUEUE.is_empty());
+
+    host.unload();
+    unsafe { host.load(addon_def!()) };
+    let inner = tasks.clone();
+    QUEUE.push(move |_, _| inner.record("enabled"));
+    host.render_frame();
+    assert_eq!(tasks.take(), ["enabled"]);
+}
@@ -0,0 +1,126 @@
+//! Tests for registration tracking and leak detection.
+
+use nexus::{
+    event::event_subscribe_unknown_fn,
+    gui::{register_render, register_render_fn, render, unregister_render, RenderType},
+    keybind::register_keybind_with_string_fn,
+    log::LogLevel,
+    registry::{registrations, Registration, RegistrationKind},
+};
+use nexus_test::{addon_def, Call, TestHost};
+
+nexus::export! {
+    name: "Registry Addon",
+    signature: -0x8E6,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host.clear_calls();
+    host
+}
+
+fn registration(kind: RegistrationKind, name: &str, closure: bool) -> Registration {
+    Registration {
+        kind,
+        name: Some(name.into()),
+        closure,
+    }
+}
+
+fn warned(host: &TestHost, message: &str) -> bool {
+    host.logs()
+        .iter()
+        .any(|entry| entry.level == LogLevel::Warning && entry.message == message)
+}
+
+#[test]
+fn raw() {
+    let host = load_fixture();
+
+    let callback = render!(|_| {});
+    let _ = register_render(RenderType::OptionsRender, callback);
+    assert_eq!(
+        registrations(),
+        [registration(
+            RegistrationKind::Render,
+            "OptionsRender",
+            false
+        )]
+    );
+
+    unregister_render(callback);
+    assert_eq!(registrations(), []);
+    assert_eq!(host.render_count(RenderType::OptionsRender), 0);
+}
+
+#[test]
+fn closure_trampoline() {
+    let host = load_fixture();
+
+    let first = register_render_fn(RenderType::Render, |_, _| {});
+    let second = register_render_fn(RenderType::Render, |_, _| {});
+    assert_eq!(
+        registrations(),
+        [
+            registration(RegistrationKind::Render, "Render", false),
+            registration(RegistrationKind::Render, "Render", true),
+            registration(RegistrationKind::Render, "Render", true),
+        ]
+    );
+
+    // the trampoline is deregistered with the last closure
+    first.revert();
+    assert_eq!(registrations().len(), 2);
+    assert_eq!(host.render_count(RenderType::Render), 1);
+    second.revert();
+    assert_eq!(registrations(), []);
+    assert_eq!(host.render_count(RenderType::Render), 0);
+}
+
+#[test]
+fn keybind_replaced() {
+    let host = load_fixture();
+
+    let _ = register_keybind_with_string_fn("KB_REGISTRY", |_, _| {}, "ALT+R");
+    let _ = register_keybind_with_string_fn("KB_REGISTRY", |_, _| {}, "ALT+T");
+    assert_eq!(
+        registrations(),
+        [
+            registration(RegistrationKind::Keybind, "KB_REGISTRY", false),
+            registration(RegistrationKind::Keybind, "KB_REGISTRY", true),
+        ]
+    );
+    assert_eq!(host.keybinds(), ["KB_REGISTRY"]);
+}
+
+#[test]
+fn leaked() {
+    let mut host = load_fixture();
+
+    let _ = register_render(RenderType::Render, render!(|_| {}));
+    let _ = register_render_fn(RenderType::PostRender, |_, _| {});
+    let _ = event_subscribe_unknown_fn("REGISTRY_EVENT", |_| {});
+    host.unload();
+
+    for message in [
+        "render callback \"Render\" leaked, reverting on unload",
+        "render callback \"PostRender\" (closure) leaked, reverting on unload",
+        "event subscription \"REGISTRY_EVENT\" (closure) leaked, reverting on unload",
+    ] {
+        assert!(warned(&host, message), "missing {message:?}");
+    }
+    assert_eq!(registrations(), []);
+    assert_eq!(host.render_count(RenderType::Render), 0);
+    assert_eq!(host.render_count(RenderType::PostRender), 0);
+    assert_eq!(host.event_subscribers("REGISTRY_EVENT"), 0);
+
+    // trampolines are deregistered once by the dispatch cleanup
+    let calls = host.take_calls();
+    let deregistered = calls
+        .iter()
+        .filter(|call| **call == Call::DeregisterRender)
+        .count();
+    assert_eq!(deregistered, 2);
+}
@@ -0,0 +1,128 @@
+//! Tests for the render thread token.
+
+use nexus::{
+    defer,
+    gui::{register_render, register_render_fn, render, RenderThread, RenderType},
+    scheduler::next_frame,
+};
+use nexus_test::{addon_def, TestHost};
+use std::{
+    cell::Cell,
+    panic,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+nexus::export! {
+    name: "Render Thread Addon",
+    signature: -0x4D7,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+/// Records which callbacks ran with a token for the current thread.
+#[derive(Debug, Clone, Default)]
+struct Seen(Arc<Mutex<Vec<&'static str>>>);
+
+impl Seen {
+    fn record(&self, name: &'static str, thread: RenderThread) {
+        assert!(RenderThread::current().is_some());
+        let _ = nexus::ui(thread);
+        self.0.lock().unwrap().push(name);
+    }
+
+    fn take(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[test]
+fn outside_render() {
+    let _host = load_fixture();
+
+    assert!(RenderThread::current().is_none());
+    assert!(thread::spawn(|| RenderThread::current().is_none())
+        .join()
+        .unwrap());
+}
+
+#[test]
+fn closures() {
+    let mut host = load_fixture();
+    let seen = Seen::default();
+
+    for (render_type, name) in [
+        (RenderType::PreRender, "pre"),
+        (RenderType::Render, "render"),
+        (RenderType::OptionsRender, "options"),
+        (RenderType::PostRender, "post"),
+    ] {
+        let seen = seen.clone();
+        register_render_fn(render_type, move |_ui, thread| seen.record(name, thread))
+            .revert_on_unload();
+    }
+    host.render_frame();
+    assert_eq!(seen.take(), ["pre", "render", "options", "post"]);
+}
+
+#[test]
+fn raw_callback() {
+    static SEEN: Mutex<bool> = Mutex::new(false);
+
+    let mut host = load_fixture();
+    register_render(
+        RenderType::Render,
+        render!(|_ui, thread| {
+            let _ = nexus::ui(thread);
+            *SEEN.lock().unwrap() = true;
+        }),
+    )
+    .revert_on_unload();
+    host.render_frame();
+    assert!(*SEEN.lock().unwrap());
+}
+
+#[test]
+fn deferred() {
+    let mut host = load_fixture();
+    let seen = Seen::default();
+
+    let inner = seen.clone();
+    thread::spawn(move || defer(move |_ui, thread| inner.record("deferred", thread)))
+        .join()
+        .unwrap();
+    let inner = seen.clone();
+    next_frame(move |_ui, thread| inner.record("timer", thread)).leak();
+    assert_eq!(seen.take(), [] as [&str; 0]);
+
+    host.render_frame();
+    assert_eq!(seen.take(), ["deferred", "timer"]);
+}
+
+#[test]
+fn kept_token() {
+    thread_local! {
+        static KEPT: Cell<Option<RenderThread>> = const { Cell::new(None) };
+    }
+
+    let mut host = load_fixture();
+    register_render_fn(RenderType::Render, |_ui, thread| {
+        KEPT.with(|kept| kept.set(Some(thread)))
+    })
+    .revert_on_unload();
+    host.render_frame();
+
+    // the render callback ran on this thread, the token is not usable afterwards
+    let thread = KEPT.with(Cell::take).expect("token not received");
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(|| {
+        let _ = thread.ui();
+    });
+    panic::set_hook(hook);
+    assert!(result.is_err());
+}
@@ -0,0 +1,137 @@
+//! Tests for revertible actions, guards and sets.
+
+use nexus::{
+    gui::{register_render_fn, RenderType},
+    keybind::register_keybind_with_string_fn,
+    Revertible, RevertibleSet,
+};
+use nexus_test::{addon_def, TestHost};
+use std::sync::{Arc, Mutex};
+
+nexus::export! {
+    name: "Revertible Addon",
+    signature: -0x4E7,
+}
+
+/// Order of performed reverts.
+#[derive(Debug, Clone, Default)]
+struct Reverts(Arc<Mutex<Vec<&'static str>>>);
+
+impl Reverts {
+    fn revertible(&self, name: &'static str) -> Revertible<impl FnOnce() + Send + 'static> {
+        let reverts = self.0.clone();
+        Revertible::new(move || reverts.lock().unwrap().push(name))
+    }
+
+    fn take(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[test]
+fn guard() {
+    let reverts = Reverts::default();
+
+    let guard = reverts.revertible("dropped").guard();
+    assert_eq!(reverts.take(), [] as [&str; 0]);
+    drop(guard);
+    assert_eq!(reverts.take(), ["dropped"]);
+
+    let revertible = reverts.revertible("disarmed").guard().disarm();
+    assert_eq!(reverts.take(), [] as [&str; 0]);
+    revertible.revert();
+    assert_eq!(reverts.take(), ["disarmed"]);
+}
+
+#[test]
+fn combinators() {
+    let reverts = Reverts::default();
+
+    reverts
+        .revertible("first")
+        .and(reverts.revertible("second"))
+        .chain({
+            let reverts = reverts.clone();
+            move || reverts.0.lock().unwrap().push("chained")
+        })
+        .boxed()
+        .revert();
+    assert_eq!(reverts.take(), ["second", "first", "chained"]);
+}
+
+#[test]
+fn set_order() {
+    let reverts = Reverts::default();
+
+    let mut set: RevertibleSet = [reverts.revertible("a"), reverts.revertible("b")]
+        .into_iter()
+        .collect();
+    set.push(reverts.revertible("c"));
+    assert_eq!(set.len(), 3);
+
+    set.clear();
+    assert!(set.is_empty());
+    assert_eq!(reverts.take(), ["c", "b", "a"]);
+
+    // the set can be reused after clearing
+    set.push(reverts.revertible("d"));
+    set.push(reverts.revertible("e"));
+    drop(set);
+    assert_eq!(reverts.take(), ["e", "d"]);
+}
+
+#[test]
+fn set_leak_and_into() {
+    let reverts = Reverts::default();
+
+    let mut set = RevertibleSet::new();
+    set.push(reverts.revertible("leaked"));
+    set.leak();
+    assert_eq!(reverts.take(), [] as [&str; 0]);
+
+    let mut set = RevertibleSet::new();
+    set.push(reverts.revertible("x"));
+    set.push(reverts.revertible("y"));
+    let revertible = set.into_revertible();
+    assert_eq!(reverts.take(), [] as [&str; 0]);
+    revertible.revert();
+    assert_eq!(reverts.take(), ["y", "x"]);
+}
+
+#[test]
+fn set_unload() {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    let reverts = Reverts::default();
+
+    let mut set = RevertibleSet::new();
+    set.push(reverts.revertible("first"));
+    set.push(reverts.revertible("second"));
+    set.revert_on_unload();
+    assert_eq!(reverts.take(), [] as [&str; 0]);
+
+    host.unload();
+    assert_eq!(reverts.take(), ["second", "first"]);
+}
+
+#[test]
+fn feature_toggle() {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+
+    let mut feature = RevertibleSet::new();
+    for _ in 0..2 {
+        feature.push(register_render_fn(RenderType::Render, |_, _| {}));
+        feature.push(register_keybind_with_string_fn(
+            "KB_FEATURE",
+            |_, _| {},
+            "ALT+F",
+        ));
+        assert_eq!(host.render_count(RenderType::Render), 1);
+        assert_eq!(host.keybinds(), ["KB_FEATURE"]);
+
+        feature.clear();
+        assert_eq!(host.render_count(RenderType::Render), 0);
+        assert_eq!(host.keybinds(), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,168 @@
+//! Tests for frame-based timers.
+
+use nexus::{
+    gui::RenderThread,
+    imgui::Ui,
+    scheduler::{after, every, next_frame},
+    BoxRevertible,
+};
+use nexus_test::{addon_def, TestHost};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+nexus::export! {
+    name: "Scheduler Addon",
+    signature: -0x5C4E,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+const INTERVAL: Duration = Duration::from_millis(50);
+
+/// Order of run timers.
+#[derive(Debug, Clone, Default)]
+struct Runs(Arc<Mutex<Vec<&'static str>>>);
+
+impl Runs {
+    fn record(&self, name: &'static str) {
+        self.0.lock().unwrap().push(name)
+    }
+
+    fn recorder(&self, name: &'static str) -> impl FnMut(&Ui, RenderThread) + Send + 'static {
+        let runs = self.clone();
+        move |_, _| runs.record(name)
+    }
+
+    fn take(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[test]
+fn frame() {
+    let mut host = load_fixture();
+    let runs = Runs::default();
+
+    next_frame(runs.recorder("first")).leak();
+    let inner = runs.clone();
+    next_frame(move |_, _| {
+        inner.record("second");
+        // timers added by timers are checked in the next frame
+        next_frame(inner.recorder("nested")).leak();
+    })
+    .leak();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+
+    host.render_frame();
+    assert_eq!(runs.take(), ["first", "second"]);
+    host.render_frame();
+    assert_eq!(runs.take(), ["nested"]);
+    host.render_frame();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn delayed() {
+    let mut host = load_fixture();
+    let runs = Runs::default();
+
+    after(INTERVAL, runs.recorder("after")).leak();
+    host.render_frame();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+
+    thread::sleep(INTERVAL);
+    host.render_frame();
+    assert_eq!(runs.take(), ["after"]);
+    thread::sleep(INTERVAL);
+    host.render_frame();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn repeated() {
+    let mut host = load_fixture();
+    let runs = Runs::default();
+
+    let timer = every(INTERVAL, runs.recorder("every"));
+    host.render_frame();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+
+    thread::sleep(INTERVAL);
+    host.render_frame();
+    assert_eq!(runs.take(), ["every"]);
+
+    // missed intervals are skipped instead of run in a burst
+    thread::sleep(INTERVAL * 3);
+    host.render_frame();
+    assert_eq!(runs.take(), ["every"]);
+
+    timer.revert();
+    thread::sleep(INTERVAL);
+    host.render_frame();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn cancel() {
+    let mut host = load_fixture();
+    let runs = Runs::default();
+
+    after(Duration::ZERO, runs.recorder("reverted")).revert();
+    let cancelled = Arc::new(Mutex::new(None::<BoxRevertible>));
+    let inner = cancelled.clone();
+    next_frame(move |_, _| {
+        // timers may cancel timers due in the same frame
+        if let Some(timer) = inner.lock().unwrap().take() {
+            timer.revert();
+        }
+    })
+    .leak();
+    *cancelled.lock().unwrap() = Some(next_frame(runs.recorder("cancelled")).boxed());
+    next_frame(runs.recorder("kept")).leak();
+
+    host.render_frame();
+    assert_eq!(runs.take(), ["kept"]);
+}
+
+#[test]
+fn unload() {
+    let mut host = load_fixture();
+    let runs = Runs::default();
+
+    after(INTERVAL, runs.recorder("after")).leak();
+    every(INTERVAL, runs.recorder("every")).leak();
+    next_frame(runs.recorder("frame")).leak();
+    host.unload();
+
+    // pending timers are cancelled on unload
+    unsafe { host.load(addon_def!()) };
+    thread::sleep(INTERVAL);
+    host.render_frame();
+    assert_eq!(runs.take(), [] as [&str; 0]);
+}
+
+#[test]
+fn panicking() {
+    let mut host = load_fixture();
+    let runs = Runs::default();
+
+    let inner = runs.clone();
+    every(Duration::ZERO, move |_, _| {
+        inner.record("panicking");
+        panic!("timer failed");
+    })
+    .leak();
+    every(Duration::ZERO, runs.recorder("every")).leak();
+
+    // the default policy disables the panicking timer only
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(runs.take(), ["panicking", "every", "every"]);
+}
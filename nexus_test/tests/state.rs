@@ -0,0 +1,94 @@
+//! Tests for the typed addon state.
+
+use nexus::{
+    gui::{register_render_fn, RenderType},
+    state::AddonState,
+};
+use nexus_test::{addon_def, TestHost};
+use std::{sync::mpsc, thread, time::Duration};
+
+#[derive(Debug, Default)]
+struct FixtureState {
+    frames: usize,
+}
+
+nexus::export! {
+    name: "State Addon",
+    signature: -0x57A7E,
+    state: FixtureState::default,
+    load: || {
+        register_render_fn(
+            RenderType::Render,
+            AddonState::render(|_ui, state: &mut FixtureState| state.frames += 1),
+        )
+        .revert_on_unload();
+    },
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+fn frames() -> Option<usize> {
+    AddonState::with(|state: &mut FixtureState| state.frames)
+}
+
+#[test]
+fn lifetime() {
+    let mut host = load_fixture();
+    assert_eq!(frames(), Some(0));
+    assert!(AddonState::<String>::try_get().is_none());
+
+    host.unload();
+    assert_eq!(frames(), None);
+
+    unsafe { host.load(addon_def!()) };
+    assert_eq!(frames(), Some(0));
+}
+
+#[test]
+fn render() {
+    let mut host = load_fixture();
+
+    host.render_frame();
+    host.render_frame();
+    assert_eq!(frames(), Some(2));
+
+    // the render wrapper is skipped while the state is held on the same thread
+    let state = AddonState::<FixtureState>::get();
+    host.render_frame();
+    drop(state);
+    assert_eq!(frames(), Some(2));
+}
+
+#[test]
+fn reentrant() {
+    let _host = load_fixture();
+
+    let inner =
+        AddonState::with(|_: &mut FixtureState| AddonState::<FixtureState>::try_get().is_some());
+    assert_eq!(inner, Some(false));
+    assert!(AddonState::<FixtureState>::try_get().is_some());
+}
+
+#[test]
+fn contention() {
+    let _host = load_fixture();
+
+    let mut state = AddonState::<FixtureState>::get();
+    let (sender, receiver) = mpsc::channel();
+    let other = thread::spawn(move || {
+        let frames = frames();
+        sender.send(()).unwrap();
+        frames
+    });
+
+    // other threads block while the state is held
+    assert!(receiver.recv_timeout(Duration::from_millis(50)).is_err());
+    state.frames = 10;
+    drop(state);
+
+    assert_eq!(other.join().unwrap(), Some(10));
+}
@@ -0,0 +1,149 @@
+//! Tests for the `tracing` layer.
+
+use nexus::{
+    log::{reset_log_filter, set_log_filter, LogLevel},
+    tracing::NexusLayer,
+};
+use nexus_test::{addon_def, LogEntry, TestHost};
+use tracing_subscriber::{layer::SubscriberExt, Layer, Registry};
+
+nexus::export! {
+    name: "Tracing Addon",
+    signature: -0x7ACE,
+    log_filter: "info",
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host
+}
+
+fn logged(host: &TestHost) -> Vec<(LogLevel, String, String)> {
+    host.logs()
+        .into_iter()
+        .map(
+            |LogEntry {
+                 level,
+                 channel,
+                 message,
+             }| (level, channel, message),
+        )
+        .collect()
+}
+
+fn entry(level: LogLevel, channel: &str, message: &str) -> (LogLevel, String, String) {
+    (level, channel.into(), message.into())
+}
+
+#[test]
+fn events() {
+    let host = load_fixture();
+
+    let subscriber = Registry::default().with(NexusLayer::new());
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!("process", events = 1024).entered();
+        let _inner = tracing::warn_span!("skill", id = 5).entered();
+        tracing::error!(skill = 12345, name = "Blast", "processing {}", 3);
+        tracing::warn!("warn");
+        tracing::info!("info");
+        tracing::debug!("debug");
+    });
+    assert_eq!(
+        logged(&host),
+        [
+            entry(
+                LogLevel::Critical,
+                "Tracing Addon",
+                "process{events=1024}:skill{id=5}: processing 3 skill=12345 name=\"Blast\""
+            ),
+            entry(
+                LogLevel::Warning,
+                "Tracing Addon",
+                "process{events=1024}:skill{id=5}: warn"
+            ),
+            entry(
+                LogLevel::Info,
+                "Tracing Addon",
+                "process{events=1024}:skill{id=5}: info"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn span_timing() {
+    let host = load_fixture();
+
+    let subscriber = Registry::default().with(NexusLayer::new().with_span_timing(true));
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("process", events = 1024);
+        span.in_scope(|| {});
+        drop(span);
+    });
+    let logs = host.logs();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].level, LogLevel::Info);
+    assert!(logs[0]
+        .message
+        .starts_with("process{events=1024}: closed after "));
+}
+
+#[test]
+fn addon_filter() {
+    let host = load_fixture();
+
+    let subscriber = Registry::default().with(NexusLayer::new());
+    tracing::subscriber::with_default(subscriber, || {
+        for directives in ["info", "warn", "debug"] {
+            set_log_filter(directives);
+            tracing::warn!("warn");
+            tracing::info!("info");
+            tracing::debug!("debug");
+        }
+    });
+    let messages: Vec<_> = host.logs().into_iter().map(|entry| entry.message).collect();
+    assert_eq!(messages, ["warn", "info", "warn", "warn", "info", "debug"]);
+
+    // persisted directives would affect other tests
+    reset_log_filter();
+}
+
+#[test]
+fn separate_filter() {
+    let host = load_fixture();
+
+    let layer = NexusLayer::new()
+        .with_filter("warn,tracing::noisy=off,/keep")
+        .with_channel("Other");
+    let subscriber = Registry::default().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::error!("keep error");
+        tracing::warn!("drop warning");
+        tracing::info!("keep info");
+        tracing::error!(target: "tracing::noisy", "keep noisy");
+    });
+    assert_eq!(
+        logged(&host),
+        [entry(LogLevel::Critical, "Other", "keep error")]
+    );
+}
+
+#[test]
+fn interest() {
+    let _host = load_fixture();
+
+    let span =
+        tracing::subscriber::with_default(Registry::default(), || tracing::debug_span!("callsite"));
+    let metadata = span.metadata().expect("span disabled");
+
+    // callsites are decided once with a separate filter
+    let layer = NexusLayer::new().with_filter("debug");
+    assert!(Layer::<Registry>::register_callsite(&layer, metadata).is_always());
+    let layer = NexusLayer::new().with_filter("info");
+    assert!(Layer::<Registry>::register_callsite(&layer, metadata).is_never());
+
+    // the log filter of the addon may change
+    let layer = NexusLayer::new();
+    assert!(Layer::<Registry>::register_callsite(&layer, metadata).is_sometimes());
+}
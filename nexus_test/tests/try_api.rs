@@ -0,0 +1,122 @@
+//! Tests for the fallible `try_` API.
+
+use nexus::{
+    alert::{send_alert, try_send_alert},
+    gui::{try_deregister_close_on_escape, try_register_close_on_escape},
+    log::{try_log, LogLevel},
+    paths::try_get_addon_dir,
+    updater::try_request_update,
+    Error,
+};
+use nexus_test::{addon_def, Call, TestHost};
+use std::panic;
+
+const SIGNATURE: i32 = -0x7E7;
+
+nexus::export! {
+    name: "Try Addon",
+    signature: -0x7E7,
+}
+
+fn load_fixture() -> TestHost {
+    let mut host = TestHost::new();
+    unsafe { host.load(addon_def!()) };
+    host.clear_calls();
+    host
+}
+
+#[test]
+fn not_initialized() {
+    let host = TestHost::new();
+
+    assert_eq!(
+        try_log(LogLevel::Info, "Try Addon", "message"),
+        Err(Error::NotInitialized)
+    );
+    assert_eq!(try_send_alert("alert"), Err(Error::NotInitialized));
+    assert_eq!(try_get_addon_dir("Try Addon"), Err(Error::NotInitialized));
+    assert_eq!(
+        try_request_update(SIGNATURE, "https://example.com"),
+        Err(Error::NotInitialized)
+    );
+    assert_eq!(host.take_calls(), []);
+}
+
+#[test]
+fn nul() {
+    let host = load_fixture();
+
+    assert_eq!(
+        try_send_alert("alert\0"),
+        Err(Error::Nul {
+            what: "alert message",
+            position: 5
+        })
+    );
+    assert_eq!(
+        try_register_close_on_escape("Window\0", &mut false),
+        Err(Error::Nul {
+            what: "window name",
+            position: 6
+        })
+    );
+    assert_eq!(
+        try_request_update(SIGNATURE, "\0"),
+        Err(Error::Nul {
+            what: "update url",
+            position: 0
+        })
+    );
+    assert_eq!(host.take_calls(), []);
+}
+
+#[test]
+fn success() {
+    let host = load_fixture();
+
+    let opened = Box::leak(Box::new(true));
+    try_register_close_on_escape("Window", opened).unwrap();
+    assert_eq!(host.press_escape(), ["Window"]);
+    assert!(!*opened);
+    try_deregister_close_on_escape("Window").unwrap();
+
+    try_send_alert("alert").unwrap();
+    try_request_update(SIGNATURE, "https://example.com").unwrap();
+    assert_eq!(
+        host.take_calls(),
+        [
+            Call::RegisterCloseOnEscape {
+                window_name: "Window".into()
+            },
+            Call::DeregisterCloseOnEscape {
+                window_name: "Window".into()
+            },
+            Call::SendAlert {
+                message: "alert".into()
+            },
+            Call::RequestUpdate {
+                signature: SIGNATURE,
+                update_url: "https://example.com".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn panic_location() {
+    let host = load_fixture();
+
+    let line = line!() + 1;
+    let result = panic::catch_unwind(|| send_alert("alert\0"));
+    assert!(result.is_err());
+
+    // the panic is reported at the caller of the panicking wrapper
+    let location = format!("{}:{line}:", file!());
+    assert!(
+        host.logs()
+            .iter()
+            .any(|entry| entry.level == LogLevel::Critical && entry.message.contains(&location)),
+        "missing panic at {location} in {:?}",
+        host.logs()
+    );
+}